@@ -6,7 +6,6 @@
 #[macro_use]
 extern crate serde_derive;
 extern crate csv;
-extern crate petgraph;
 extern crate regex;
 extern crate twiggy_ir as ir;
 extern crate twiggy_opt as opt;
@@ -14,10 +13,12 @@ extern crate twiggy_traits as traits;
 
 mod json;
 
+use std::borrow::Cow;
 use std::cmp;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
 use std::fmt;
 use std::io;
+use std::iter;
 
 #[derive(Debug, Clone, Copy)]
 enum Align {
@@ -118,6 +119,94 @@ impl fmt::Display for Table {
     }
 }
 
+/// Only follow the edges that aren't of the given kinds. The meta root's edges
+/// are always followed, so they can't be ignored.
+fn ignore_edges(items: &mut ir::Items, ignored: &[String]) -> Result<(), traits::Error> {
+    let mut kinds = ir::EdgeKinds::all();
+    for kind in ignored {
+        let kind: ir::EdgeKind = kind.parse().map_err(traits::Error::with_msg)?;
+        if kind == ir::EdgeKind::Root {
+            return Err(traits::Error::with_msg(
+                "edges of kind 'root' are always followed and can't be ignored",
+            ));
+        }
+        kinds.remove(kind);
+    }
+    items.follow_edge_kinds(kinds);
+    Ok(())
+}
+
+/// Replace the items with a subgraph whose roots are the items matching the
+/// `roots` regular expressions, or the original roots if there are none,
+/// except for those matching the `ignored` regular expressions. Every item is
+/// kept, so that the ones that are no longer reachable show up as garbage.
+fn select_roots(
+    items: &mut ir::Items,
+    roots: &[String],
+    ignored: &[String],
+) -> Result<(), traits::Error> {
+    if roots.is_empty() && ignored.is_empty() {
+        return Ok(());
+    }
+
+    let mut selected = if roots.is_empty() {
+        items.roots().iter().cloned().collect()
+    } else {
+        let matching = items_matching_regexps(items, &regex::RegexSet::new(roots)?);
+        if matching.is_empty() {
+            return Err(traits::Error::with_msg("no items match the given roots"));
+        }
+        matching
+    };
+    if !ignored.is_empty() {
+        let ignored = regex::RegexSet::new(ignored)?;
+        selected.retain(|&id| !ignored.is_match(items[id].name()));
+    }
+
+    *items = items.subgraph().with_roots(selected).finish();
+    Ok(())
+}
+
+/// Find the items with each of the given names, skipping names that no item
+/// has. It is an error for several items to share a name.
+fn items_with_names(items: &ir::Items, names: &[String]) -> Result<Vec<ir::Id>, traits::Error> {
+    let mut ids = vec![];
+    for name in names {
+        ids.extend(item_with_name(items, name)?);
+    }
+    Ok(ids)
+}
+
+/// Find the item with the given name, if any. When several items share the
+/// name, which is common with generic instantiations, the error lists them so
+/// that one can be picked by its full name.
+fn item_with_name(items: &ir::Items, name: &str) -> Result<Option<ir::Id>, traits::Error> {
+    match items.get_item_by_name(name) {
+        Ok(item) => Ok(item.map(|item| item.id())),
+        Err(ambiguous) => {
+            let mut msg = format!("{}:", ambiguous);
+            for &id in ambiguous.candidates() {
+                msg.push_str("\n    ");
+                msg.push_str(items[id].name());
+            }
+            msg.push_str("\nuse one of their full names, or `--regex` to select all of them");
+            Err(traits::Error::with_msg(msg))
+        }
+    }
+}
+
+/// Find the items whose names match any of the given regular expressions,
+/// sorted by id.
+fn items_matching_regexps(items: &ir::Items, regexps: &regex::RegexSet) -> Vec<ir::Id> {
+    let mut matching: Vec<_> = items
+        .names()
+        .filter(|&(name, _)| regexps.is_match(name))
+        .flat_map(|(_, ids)| ids.iter().cloned())
+        .collect();
+    matching.sort();
+    matching
+}
+
 struct Top {
     items: Vec<ir::Id>,
     opts: opt::Top,
@@ -151,7 +240,7 @@ impl traits::Emit for Top {
             table.add_row(vec![
                 size.to_string(),
                 format!("{:.2}%", size_percent),
-                item.name().to_string(),
+                items.display_name(id).into_owned(),
             ]);
         }
 
@@ -167,7 +256,7 @@ impl traits::Emit for Top {
             let item = &items[id];
 
             let mut obj = arr.object()?;
-            obj.field("name", item.name())?;
+            obj.field("name", &*items.display_name(id))?;
 
             let size = item.size();
             let size_percent = (size as f64) / (items.size() as f64) * 100.0;
@@ -180,6 +269,16 @@ impl traits::Emit for Top {
                 obj.field("retained_size", size)?;
                 obj.field("retained_size_percent", size_percent)?;
             }
+
+            if let Some(location) = item.location() {
+                let mut loc = obj.object("location")?;
+                loc.field("file", location.file())?;
+                loc.field("line", location.line())?;
+                loc.field("column", location.column())?;
+                if let Some(function) = location.function() {
+                    loc.field("function", function)?;
+                }
+            }
         }
 
         Ok(())
@@ -216,7 +315,7 @@ impl traits::Emit for Top {
             };
 
             wtr.serialize(CsvRecord {
-                name: item.name().to_string(),
+                name: items.display_name(id).into_owned(),
                 shallow_size: shallow_size,
                 shallow_size_percent: shallow_size_percent,
                 retained_size: retained_size,
@@ -230,6 +329,9 @@ impl traits::Emit for Top {
 
 /// Run the `top` analysis on the given IR items.
 pub fn top(items: &mut ir::Items, opts: &opt::Top) -> Result<Box<traits::Emit>, traits::Error> {
+    ignore_edges(items, opts.ignore_edges())?;
+    select_roots(items, opts.roots(), opts.ignore_roots())?;
+
     if opts.retaining_paths() {
         return Err(traits::Error::with_msg(
             "retaining paths are not yet implemented",
@@ -264,6 +366,30 @@ pub fn top(items: &mut ir::Items, opts: &opt::Top) -> Result<Box<traits::Emit>,
     Ok(Box::new(top) as Box<traits::Emit>)
 }
 
+/// Write a line of folded stacks: the names of the given items, from the bottom
+/// of the stack to its top, separated by semicolons and followed by a size.
+/// Semicolons in names would start new frames, so they are replaced.
+#[cfg(feature = "emit_folded")]
+fn write_folded_stack<I>(
+    items: &ir::Items,
+    stack: I,
+    size: u32,
+    dest: &mut io::Write,
+) -> Result<(), traits::Error>
+where
+    I: IntoIterator<Item = ir::Id>,
+{
+    if size == 0 {
+        return Ok(());
+    }
+    let frames: Vec<String> = stack
+        .into_iter()
+        .map(|id| items.display_name(id).replace(';', ":").replace('\n', " "))
+        .collect();
+    writeln!(dest, "{} {}", frames.join(";"), size)?;
+    Ok(())
+}
+
 struct DominatorTree {
     tree: BTreeMap<ir::Id, Vec<ir::Id>>,
     items: Vec<ir::Id>,
@@ -302,20 +428,19 @@ impl traits::Emit for DominatorTree {
             }
 
             if depth > 0 {
-                let item = &items[id];
+                let name = items.display_name(id);
 
                 let size = items.retained_size(id);
                 let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
 
-                let mut label =
-                    String::with_capacity(depth as usize * 4 + item.name().len() + "⤷ ".len());
+                let mut label = String::with_capacity(depth as usize * 4 + name.len() + "⤷ ".len());
                 for _ in 2..depth {
                     label.push_str("    ");
                 }
                 if depth != 1 {
                     label.push_str("  ⤷ ");
                 }
-                label.push_str(item.name());
+                label.push_str(&name);
 
                 table.add_row(vec![
                     size.to_string(),
@@ -370,7 +495,7 @@ impl traits::Emit for DominatorTree {
         ) -> Result<(), traits::Error> {
             let item = &items[id];
 
-            obj.field("name", item.name())?;
+            obj.field("name", &*items.display_name(id))?;
 
             let size = item.size();
             let size_percent = (size as f64) / (items.size() as f64) * 100.0;
@@ -446,7 +571,7 @@ impl traits::Emit for DominatorTree {
 
             let rc = CsvRecord {
                 id: item.id().serializable(),
-                name: item.name().to_string(),
+                name: items.display_name(id).into_owned(),
                 shallow_size: size,
                 shallow_size_percent: size_percent,
                 retained_size: retained_size,
@@ -469,6 +594,75 @@ impl traits::Emit for DominatorTree {
         recursive_add_children(items, &self.opts, &self.tree, items.meta_root(), &mut wtr)?;
         Ok(())
     }
+
+    #[cfg(feature = "emit_folded")]
+    fn emit_folded(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        fn recursive_add_stacks(
+            items: &ir::Items,
+            dominator_tree: &BTreeMap<ir::Id, Vec<ir::Id>>,
+            depth: u32,
+            stack: &mut Vec<ir::Id>,
+            opts: &opt::Dominators,
+            id: ir::Id,
+            dest: &mut io::Write,
+        ) -> Result<(), traits::Error> {
+            if depth > opts.max_depth() {
+                return Ok(());
+            }
+
+            if depth > 0 {
+                stack.push(id);
+                // Every item's own size is its frame's, and flamegraphs add up
+                // the sizes of the items that it dominates. At the maximum
+                // depth, those items aren't shown, so their sizes are the
+                // frame's too.
+                let size = if depth == opts.max_depth() {
+                    items.retained_size(id)
+                } else {
+                    items[id].size()
+                };
+                write_folded_stack(items, stack.iter().cloned(), size, dest)?;
+            }
+
+            if let Some(children) = dominator_tree.get(&id) {
+                if depth < opts.max_depth() {
+                    let mut children: Vec<_> = children.iter().cloned().collect();
+                    children.sort_by(|a, b| items.retained_size(*b).cmp(&items.retained_size(*a)));
+                    for child in children {
+                        recursive_add_stacks(
+                            items,
+                            dominator_tree,
+                            depth + 1,
+                            stack,
+                            opts,
+                            child,
+                            dest,
+                        )?;
+                    }
+                }
+            }
+
+            if depth > 0 {
+                stack.pop();
+            }
+            Ok(())
+        }
+
+        for id in &self.items {
+            let start_depth = if *id == items.meta_root() { 0 } else { 1 };
+            let mut stack = vec![];
+            recursive_add_stacks(
+                items,
+                &self.tree,
+                start_depth,
+                &mut stack,
+                &self.opts,
+                *id,
+                dest,
+            )?;
+        }
+        Ok(())
+    }
 }
 
 /// Compute the dominator tree for the given IR graph.
@@ -476,6 +670,9 @@ pub fn dominators(
     items: &mut ir::Items,
     opts: &opt::Dominators,
 ) -> Result<Box<traits::Emit>, traits::Error> {
+    ignore_edges(items, opts.ignore_edges())?;
+    select_roots(items, opts.roots(), opts.ignore_roots())?;
+
     items.compute_dominator_tree();
     items.compute_dominators();
     items.compute_retained_sizes();
@@ -487,19 +684,11 @@ pub fn dominators(
         false => {
             if opts.using_regexps() {
                 let regexps = regex::RegexSet::new(arguments)?;
-                let mut sorted_items: Vec<_> = items
-                    .iter()
-                    .filter(|item| regexps.is_match(&item.name()))
-                    .map(|item| item.id())
-                    .collect();
+                let mut sorted_items = items_matching_regexps(items, &regexps);
                 sorted_items.sort_by_key(|id| items.retained_size(*id) as i32 * -1);
                 sorted_items
             } else {
-                arguments
-                    .iter()
-                    .filter_map(|name| items.get_item_by_name(name))
-                    .map(|item| item.id())
-                    .collect()
+                items_with_names(items, arguments)?
             }
         }
     };
@@ -518,6 +707,30 @@ struct Paths {
     opts: opt::Paths,
 }
 
+/// The kinds of the edge between an item and the one before it on a path.
+fn path_edge_kinds(
+    items: &ir::Items,
+    opts: &opt::Paths,
+    parent: ir::Id,
+    id: ir::Id,
+) -> ir::EdgeKinds {
+    if opts.descending() {
+        items.edge_kinds(parent, id)
+    } else {
+        items.edge_kinds(id, parent)
+    }
+}
+
+/// The items one step further along a path from the given item: its callers,
+/// or its callees when descending.
+fn path_steps(items: &ir::Items, opts: &opt::Paths, id: ir::Id) -> Vec<ir::Id> {
+    if opts.descending() {
+        items.neighbors(id).collect()
+    } else {
+        items.predecessors(id).collect()
+    }
+}
+
 impl traits::Emit for Paths {
     #[cfg(feature = "emit_text")]
     fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
@@ -528,6 +741,7 @@ impl traits::Emit for Paths {
             depth: u32,
             mut paths: &mut u32,
             opts: &opt::Paths,
+            parent: Option<ir::Id>,
             id: ir::Id,
         ) {
             if opts.max_paths() == *paths || depth > opts.max_depth() {
@@ -539,8 +753,9 @@ impl traits::Emit for Paths {
             }
 
             let item = &items[id];
+            let name = items.display_name(id);
 
-            let mut label = String::with_capacity(depth as usize * 4 + item.name().len());
+            let mut label = String::with_capacity(depth as usize * 4 + name.len());
             for _ in 1..depth {
                 label.push_str("    ");
             }
@@ -551,7 +766,12 @@ impl traits::Emit for Paths {
                     label.push_str("  ⬑ ");
                 }
             }
-            label.push_str(item.name());
+            label.push_str(&name);
+            if let Some(parent) = parent {
+                if opts.show_edge_kinds() {
+                    label.push_str(&format!(" ({})", path_edge_kinds(items, opts, parent, id)));
+                }
+            }
 
             table.add_row(vec![
                 if depth == 0 {
@@ -573,14 +793,32 @@ impl traits::Emit for Paths {
             if opts.descending() {
                 for callee in items.neighbors(id) {
                     *paths += 1;
-                    recursive_callers(items, seen, table, depth + 1, &mut paths, &opts, callee);
+                    recursive_callers(
+                        items,
+                        seen,
+                        table,
+                        depth + 1,
+                        &mut paths,
+                        &opts,
+                        Some(id),
+                        callee,
+                    );
                 }
             } else {
                 for (i, caller) in items.predecessors(id).enumerate() {
                     if i > 0 {
                         *paths += 1;
                     }
-                    recursive_callers(items, seen, table, depth + 1, &mut paths, &opts, caller);
+                    recursive_callers(
+                        items,
+                        seen,
+                        table,
+                        depth + 1,
+                        &mut paths,
+                        &opts,
+                        Some(id),
+                        caller,
+                    );
                 }
             }
 
@@ -598,7 +836,9 @@ impl traits::Emit for Paths {
         for id in &self.items {
             let mut paths = 0 as u32;
             let mut seen = BTreeSet::new();
-            recursive_callers(items, &mut seen, &mut table, 0, &mut paths, &opts, *id);
+            recursive_callers(
+                items, &mut seen, &mut table, 0, &mut paths, &opts, None, *id,
+            );
         }
 
         write!(dest, "{}", table)?;
@@ -614,23 +854,37 @@ impl traits::Emit for Paths {
             depth: u32,
             mut paths: &mut u32,
             opts: &opt::Paths,
+            parent: Option<ir::Id>,
             id: ir::Id,
         ) -> io::Result<()> {
             let item = &items[id];
 
-            obj.field("name", item.name())?;
+            obj.field("name", &*items.display_name(id))?;
 
             let size = item.size();
             let size_percent = (size as f64) / (items.size() as f64) * 100.0;
             obj.field("shallow_size", size)?;
             obj.field("shallow_size_percent", size_percent)?;
 
-            let mut callers = obj.array("callers")?;
+            if let Some(parent) = parent {
+                if opts.show_edge_kinds() {
+                    let mut kinds = obj.array("edge_kinds")?;
+                    for kind in path_edge_kinds(items, opts, parent, id).iter() {
+                        kinds.elem(kind.as_str())?;
+                    }
+                }
+            }
+
+            let mut callers = obj.array(if opts.descending() {
+                "callees"
+            } else {
+                "callers"
+            })?;
 
             let depth = depth + 1;
             if depth <= opts.max_depth() {
                 seen.insert(id);
-                for (i, caller) in items.predecessors(id).enumerate() {
+                for (i, caller) in path_steps(items, opts, id).into_iter().enumerate() {
                     if seen.contains(&caller) || items.meta_root() == caller {
                         continue;
                     }
@@ -643,7 +897,16 @@ impl traits::Emit for Paths {
                     }
 
                     let mut obj = callers.object()?;
-                    recursive_callers(items, seen, &mut obj, depth, &mut paths, &opts, caller)?;
+                    recursive_callers(
+                        items,
+                        seen,
+                        &mut obj,
+                        depth,
+                        &mut paths,
+                        &opts,
+                        Some(id),
+                        caller,
+                    )?;
                 }
                 seen.remove(&id);
             }
@@ -656,7 +919,9 @@ impl traits::Emit for Paths {
             let mut paths = 0 as u32;
             let mut seen = BTreeSet::new();
             let mut obj = arr.object()?;
-            recursive_callers(items, &mut seen, &mut obj, 0, &mut paths, &self.opts, *id)?;
+            recursive_callers(
+                items, &mut seen, &mut obj, 0, &mut paths, &self.opts, None, *id,
+            )?;
         }
 
         Ok(())
@@ -686,16 +951,19 @@ impl traits::Emit for Paths {
             let item = &items[id];
             let size = item.size();
             let size_percent = (size as f64) / (items.size() as f64) * 100.0;
-            let mut callers = items
-                .predecessors(id)
+            let mut steps = path_steps(items, opts, id)
                 .into_iter()
-                .map(|i| items[i].name())
-                .collect::<Vec<&str>>();
-            callers.push(item.name());
-            let path = callers.join(" -> ");
+                .map(|i| items.display_name(i))
+                .collect::<Vec<_>>();
+            if opts.descending() {
+                steps.insert(0, items.display_name(id));
+            } else {
+                steps.push(items.display_name(id));
+            }
+            let path = steps.join(" -> ");
 
             let record = CsvRecord {
-                name: item.name().to_owned(),
+                name: items.display_name(id).into_owned(),
                 shallow_size: size,
                 shallow_size_percent: size_percent,
                 path: Some(path),
@@ -707,7 +975,7 @@ impl traits::Emit for Paths {
             let depth = depth + 1;
             if depth <= opts.max_depth() {
                 seen.insert(id);
-                for (i, caller) in items.predecessors(id).enumerate() {
+                for (i, caller) in path_steps(items, opts, id).into_iter().enumerate() {
                     if seen.contains(&caller) || items.meta_root() == caller {
                         continue;
                     }
@@ -735,177 +1003,2990 @@ impl traits::Emit for Paths {
 
         Ok(())
     }
-}
 
-/// Find all retaining paths for the given items.
-pub fn paths(items: &mut ir::Items, opts: &opt::Paths) -> Result<Box<traits::Emit>, traits::Error> {
-    // The predecessor tree only needs to be computed if we are ascending
-    // through the retaining paths.
-    if !opts.descending() {
-        items.compute_predecessors();
-    }
+    #[cfg(feature = "emit_folded")]
+    fn emit_folded(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        // Retaining paths are written from the root that retains the item up
+        // to the item, with the item's size split across its complete paths.
+        // Descending paths are written from the item down, with the size of
+        // each item along the way, so that flamegraphs add up the sizes of
+        // everything the item reaches.
+        fn recursive_callers(
+            items: &ir::Items,
+            seen: &mut BTreeSet<ir::Id>,
+            stack: &mut Vec<ir::Id>,
+            complete: &mut Vec<Vec<ir::Id>>,
+            paths: &mut u32,
+            opts: &opt::Paths,
+            dest: &mut io::Write,
+        ) -> Result<(), traits::Error> {
+            let id = *stack.last().unwrap();
+            let steps: Vec<ir::Id> = if stack.len() as u32 <= opts.max_depth() {
+                path_steps(items, opts, id)
+                    .into_iter()
+                    .filter(|step| !seen.contains(step) && items.meta_root() != *step)
+                    .collect()
+            } else {
+                vec![]
+            };
 
-    // This closure is used to initialize `functions` if no arguments are given
-    // and we are ascending the retaining paths.
-    let get_functions_default = || {
-        let mut sorted_items: Vec<_> = items
-            .iter()
-            .filter(|item| item.id() != items.meta_root())
-            .collect();
-        sorted_items.sort_by(|a, b| b.size().cmp(&a.size()));
-        sorted_items.iter().map(|item| item.id()).collect()
-    };
+            if opts.descending() {
+                write_folded_stack(items, stack.iter().cloned(), items[id].size(), dest)?;
+            } else if steps.is_empty() {
+                complete.push(stack.clone());
+            }
 
-    // This closure is used to initialize `functions` if no arguments are given
-    // and we are descending the retaining paths.
-    let get_functions_default_desc = || {
-        let mut roots: Vec<_> = items
-            .neighbors(items.meta_root())
-            .map(|id| &items[id])
-            .collect();
-        roots.sort_by(|a, b| b.size().cmp(&a.size()));
-        roots.into_iter().map(|item| item.id()).collect()
-    };
+            seen.insert(id);
+            for (i, step) in steps.into_iter().enumerate() {
+                if i > 0 {
+                    *paths += 1;
+                }
+                if opts.max_paths() == *paths {
+                    break;
+                }
 
-    // Initialize the collection of Id values whose retaining paths we will emit.
-    let functions: Vec<ir::Id> = if opts.functions().is_empty() {
-        match opts.descending() {
-            true => get_functions_default_desc(),
-            false => get_functions_default(),
-        }
-    } else {
-        match opts.using_regexps() {
-            true => {
-                let regexps = regex::RegexSet::new(opts.functions())?;
-                items
-                    .iter()
-                    .filter(|item| regexps.is_match(&item.name()))
-                    .map(|item| item.id())
-                    .collect()
+                stack.push(step);
+                recursive_callers(items, seen, stack, complete, paths, opts, dest)?;
+                stack.pop();
             }
-            false => opts
-                .functions()
-                .iter()
-                .filter_map(|s| items.get_item_by_name(s))
-                .map(|item| item.id())
-                .collect(),
+            seen.remove(&id);
+
+            Ok(())
         }
-    };
 
-    let paths = Paths {
-        items: functions,
-        opts: opts.clone(),
-    };
+        for id in &self.items {
+            let mut paths = 0;
+            let mut seen = BTreeSet::new();
+            let mut stack = vec![*id];
+            let mut complete = vec![];
+            recursive_callers(
+                items,
+                &mut seen,
+                &mut stack,
+                &mut complete,
+                &mut paths,
+                &self.opts,
+                dest,
+            )?;
 
-    Ok(Box::new(paths) as Box<traits::Emit>)
-}
+            let size = items[*id].size();
+            for (i, path) in complete.iter().enumerate() {
+                let size = split_size(size, complete.len(), i);
+                write_folded_stack(items, path.iter().rev().cloned(), size, dest)?;
+            }
+        }
 
-#[derive(Debug)]
-struct Monos {
-    monos: Vec<MonosEntry>,
+        Ok(())
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct MonosEntry {
-    generic: String,
-    insts: Vec<ir::Id>,
-    total: u32,
-    approx_potential_savings: u32,
+/// The `i`th of `count` nearly equal parts of `size`, with the remainder going
+/// to the first parts, so that the parts add up to exactly `size`.
+#[cfg(feature = "emit_folded")]
+fn split_size(size: u32, count: usize, i: usize) -> u32 {
+    let count = count as u32;
+    let i = i as u32;
+    size / count + if i < size % count { 1 } else { 0 }
 }
 
-impl PartialOrd for MonosEntry {
-    fn partial_cmp(&self, rhs: &MonosEntry) -> Option<cmp::Ordering> {
-        Some(self.cmp(rhs))
-    }
+/// The shortest retaining paths to some items. Each path starts at the item,
+/// and ends at one of the roots.
+#[derive(Debug)]
+struct ShortestPaths {
+    paths: Vec<(ir::Id, Vec<Vec<ir::Id>>)>,
+    opts: opt::Paths,
 }
 
-impl Ord for MonosEntry {
-    fn cmp(&self, rhs: &MonosEntry) -> cmp::Ordering {
-        rhs.approx_potential_savings
-            .cmp(&self.approx_potential_savings)
-            .then(self.insts.cmp(&rhs.insts))
-            .then(self.generic.cmp(&rhs.generic))
+/// Group the paths by their item at the given depth, in the order that each
+/// item first appears, so that paths sharing a prefix are displayed as a tree.
+fn group_paths<'a>(paths: &[&'a [ir::Id]], depth: usize) -> Vec<(ir::Id, Vec<&'a [ir::Id]>)> {
+    let mut groups: Vec<(ir::Id, Vec<&[ir::Id]>)> = vec![];
+    for &path in paths {
+        if path.len() <= depth {
+            continue;
+        }
+        let id = path[depth];
+        match groups.iter().position(|&(group, _)| group == id) {
+            Some(i) => groups[i].1.push(path),
+            None => groups.push((id, vec![path])),
+        }
     }
+    groups
 }
 
-impl traits::Emit for Monos {
+impl traits::Emit for ShortestPaths {
     #[cfg(feature = "emit_text")]
     fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
-        let mut table = Table::with_header(vec![
-            (Align::Right, "Apprx. Bloat Bytes".into()),
-            (Align::Right, "Apprx. Bloat %".into()),
-            (Align::Right, "Bytes".into()),
-            (Align::Right, "%".into()),
-            (Align::Left, "Monomorphizations".to_string()),
-        ]);
+        fn add_rows(
+            items: &ir::Items,
+            table: &mut Table,
+            opts: &opt::Paths,
+            paths: &[&[ir::Id]],
+            depth: usize,
+            parent: Option<ir::Id>,
+        ) {
+            for (id, paths) in group_paths(paths, depth) {
+                let name = items.display_name(id);
+                let mut label = String::with_capacity(depth * 4 + name.len());
+                for _ in 1..depth {
+                    label.push_str("    ");
+                }
+                if depth > 0 {
+                    label.push_str("  ⬑ ");
+                }
+                label.push_str(&name);
+                if let Some(parent) = parent {
+                    if opts.show_edge_kinds() {
+                        label.push_str(&format!(" ({})", path_edge_kinds(items, opts, parent, id)));
+                    }
+                }
 
-        for entry in &self.monos {
-            let total_percent = (f64::from(entry.total)) / (f64::from(items.size())) * 100.0;
-            let approx_potential_savings_percent =
-                (f64::from(entry.approx_potential_savings)) / (f64::from(items.size())) * 100.0;
-            table.add_row(vec![
-                entry.approx_potential_savings.to_string(),
-                format!("{:.2}%", approx_potential_savings_percent),
-                entry.total.to_string(),
-                format!("{:.2}%", total_percent),
-                entry.generic.clone(),
-            ]);
+                let (size, size_percent) = if depth == 0 {
+                    let size = items[id].size();
+                    let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                    (size.to_string(), format!("{:.2}%", size_percent))
+                } else {
+                    (String::new(), String::new())
+                };
+                table.add_row(vec![size, size_percent, label]);
 
-            for &id in &entry.insts {
-                let item = &items[id];
+                add_rows(items, table, opts, &paths, depth + 1, Some(id));
+            }
+        }
 
-                let size = item.size();
-                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Shallow Bytes".to_string()),
+            (Align::Right, "Shallow %".to_string()),
+            (Align::Left, "Shortest Retaining Paths".to_string()),
+        ]);
 
-                table.add_row(vec![
-                    "".into(),
-                    "".into(),
-                    size.to_string(),
-                    format!("{:.2}%", size_percent),
-                    format!("    {}", item.name()),
-                ]);
-            }
+        for &(_, ref paths) in &self.paths {
+            let paths: Vec<_> = paths.iter().map(|path| &path[..]).collect();
+            add_rows(items, &mut table, &self.opts, &paths, 0, None);
         }
 
-        write!(dest, "{}", &table)?;
+        write!(dest, "{}", table)?;
         Ok(())
     }
 
     #[cfg(feature = "emit_json")]
     fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
-        let mut arr = json::array(dest)?;
-
-        for entry in &self.monos {
+        fn add_callers(
+            items: &ir::Items,
+            arr: &mut json::Array,
+            opts: &opt::Paths,
+            paths: &[&[ir::Id]],
+            depth: usize,
+            parent: Option<ir::Id>,
+        ) -> io::Result<()> {
+            for (id, paths) in group_paths(paths, depth) {
+                let mut obj = arr.object()?;
+                obj.field("name", &*items.display_name(id))?;
+
+                let size = items[id].size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                obj.field("shallow_size", size)?;
+                obj.field("shallow_size_percent", size_percent)?;
+
+                if let Some(parent) = parent {
+                    if opts.show_edge_kinds() {
+                        let mut kinds = obj.array("edge_kinds")?;
+                        for kind in path_edge_kinds(items, opts, parent, id).iter() {
+                            kinds.elem(kind.as_str())?;
+                        }
+                    }
+                }
+
+                let mut callers = obj.array("callers")?;
+                add_callers(items, &mut callers, opts, &paths, depth + 1, Some(id))?;
+            }
+            Ok(())
+        }
+
+        let mut arr = json::array(dest)?;
+        for &(_, ref paths) in &self.paths {
+            let paths: Vec<_> = paths.iter().map(|path| &path[..]).collect();
+            add_callers(items, &mut arr, &self.opts, &paths, 0, None)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            name: String,
+            shallow_size: u32,
+            shallow_size_percent: f64,
+            path: String,
+        }
+
+        for &(id, ref paths) in &self.paths {
+            let size = items[id].size();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            for path in paths {
+                let path: Vec<_> = path
+                    .iter()
+                    .rev()
+                    .map(|&id| items.display_name(id))
+                    .collect();
+                wtr.serialize(CsvRecord {
+                    name: items.display_name(id).into_owned(),
+                    shallow_size: size,
+                    shallow_size_percent: size_percent,
+                    path: path.join(" -> "),
+                })?;
+            }
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_folded")]
+    fn emit_folded(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        for &(id, ref paths) in &self.paths {
+            for (i, path) in paths.iter().enumerate() {
+                let size = split_size(items[id].size(), paths.len(), i);
+                write_folded_stack(items, path.iter().rev().cloned(), size, dest)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Find the shortest path from `start` back to the meta root through each
+/// item's predecessors, without passing through the `avoided` items or
+/// stepping along the `avoided_steps`. Each step costs the `weight` of the item
+/// it steps to.
+fn shortest_retaining_path<W>(
+    items: &ir::Items,
+    start: ir::Id,
+    avoided: &BTreeSet<ir::Id>,
+    avoided_steps: &BTreeSet<(ir::Id, ir::Id)>,
+    weight: &W,
+) -> Option<Vec<ir::Id>>
+where
+    W: Fn(ir::Id) -> u64,
+{
+    let mut costs = BTreeMap::new();
+    let mut previous = BTreeMap::new();
+    let mut queue = BinaryHeap::new();
+    costs.insert(start, 0);
+    queue.push(cmp::Reverse((0, start)));
+
+    while let Some(cmp::Reverse((cost, id))) = queue.pop() {
+        if id == items.meta_root() {
+            let mut path = vec![id];
+            while let Some(&id) = previous.get(path.last().unwrap()) {
+                path.push(id);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if costs.get(&id).map_or(false, |&best| cost > best) {
+            continue;
+        }
+
+        for caller in items.predecessors(id) {
+            if avoided.contains(&caller) || avoided_steps.contains(&(id, caller)) {
+                continue;
+            }
+            let cost = cost + weight(caller);
+            if costs.get(&caller).map_or(true, |&best| cost < best) {
+                costs.insert(caller, cost);
+                previous.insert(caller, id);
+                queue.push(cmp::Reverse((cost, caller)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Find up to `k` of the shortest retaining paths to the given item with Yen's
+/// algorithm: each path after the first is the cheapest detour from a path
+/// that was already found. The paths start at the item and end at one of the
+/// roots, leaving out the meta root.
+fn shortest_retaining_paths<W>(
+    items: &ir::Items,
+    id: ir::Id,
+    k: usize,
+    weight: W,
+) -> Vec<Vec<ir::Id>>
+where
+    W: Fn(ir::Id) -> u64,
+{
+    let cost = |path: &[ir::Id]| path[1..].iter().map(|&id| weight(id)).sum::<u64>();
+
+    let mut found: Vec<Vec<ir::Id>> = vec![];
+    let mut candidates = BinaryHeap::new();
+    let mut seen = BTreeSet::new();
+    if let Some(path) =
+        shortest_retaining_path(items, id, &BTreeSet::new(), &BTreeSet::new(), &weight)
+    {
+        seen.insert(path.clone());
+        candidates.push(cmp::Reverse((cost(&path), path)));
+    }
+
+    while found.len() < k {
+        let path = match candidates.pop() {
+            Some(cmp::Reverse((_, path))) => path,
+            None => break,
+        };
+
+        for i in 0..path.len() - 1 {
+            let prefix = &path[..i + 1];
+            let avoided_steps: BTreeSet<_> = found
+                .iter()
+                .chain(iter::once(&path))
+                .filter(|other| other.len() > i + 1 && &other[..i + 1] == prefix)
+                .map(|other| (other[i], other[i + 1]))
+                .collect();
+            let avoided: BTreeSet<_> = path[..i].iter().cloned().collect();
+
+            if let Some(detour) =
+                shortest_retaining_path(items, path[i], &avoided, &avoided_steps, &weight)
+            {
+                let mut candidate = path[..i].to_vec();
+                candidate.extend(detour);
+                if seen.insert(candidate.clone()) {
+                    candidates.push(cmp::Reverse((cost(&candidate), candidate)));
+                }
+            }
+        }
+
+        found.push(path);
+    }
+
+    for path in &mut found {
+        path.pop();
+    }
+    found
+}
+
+/// Find all retaining paths for the given items.
+pub fn paths(items: &mut ir::Items, opts: &opt::Paths) -> Result<Box<traits::Emit>, traits::Error> {
+    ignore_edges(items, opts.ignore_edges())?;
+
+    if opts.shortest().is_some() {
+        if opts.descending() {
+            return Err(traits::Error::with_msg(
+                "the shortest paths can't be shown with --descending",
+            ));
+        }
+        if opts.functions().is_empty() {
+            return Err(traits::Error::with_msg(
+                "give the functions to find the shortest paths to",
+            ));
+        }
+    }
+
+    // The predecessor tree only needs to be computed if we are ascending
+    // through the retaining paths.
+    if !opts.descending() {
+        items.compute_predecessors();
+    }
+
+    // This closure is used to initialize `functions` if no arguments are given
+    // and we are ascending the retaining paths.
+    let get_functions_default = || {
+        let mut sorted_items: Vec<_> = items
+            .iter()
+            .filter(|item| item.id() != items.meta_root())
+            .collect();
+        sorted_items.sort_by(|a, b| b.size().cmp(&a.size()));
+        sorted_items.iter().map(|item| item.id()).collect()
+    };
+
+    // This closure is used to initialize `functions` if no arguments are given
+    // and we are descending the retaining paths.
+    let get_functions_default_desc = || {
+        let mut roots: Vec<_> = items
+            .neighbors(items.meta_root())
+            .map(|id| &items[id])
+            .collect();
+        roots.sort_by(|a, b| b.size().cmp(&a.size()));
+        roots.into_iter().map(|item| item.id()).collect()
+    };
+
+    // Initialize the collection of Id values whose retaining paths we will emit.
+    let functions: Vec<ir::Id> = if opts.functions().is_empty() {
+        match opts.descending() {
+            true => get_functions_default_desc(),
+            false => get_functions_default(),
+        }
+    } else {
+        match opts.using_regexps() {
+            true => {
+                let regexps = regex::RegexSet::new(opts.functions())?;
+                items_matching_regexps(items, &regexps)
+            }
+            false => items_with_names(items, opts.functions())?,
+        }
+    };
+
+    if let Some(k) = opts.shortest() {
+        let weight: fn(&ir::Items, ir::Id) -> u64 = match opts.weight() {
+            "hops" => |_, _| 1,
+            "size" => |items, id| u64::from(items[id].size()),
+            other => {
+                return Err(traits::Error::with_msg(format!(
+                    "unknown path weight: {}",
+                    other
+                )))
+            }
+        };
+
+        let paths = functions
+            .into_iter()
+            .map(|id| {
+                let mut paths =
+                    shortest_retaining_paths(items, id, k as usize, |step| weight(items, step));
+                if paths.is_empty() {
+                    paths.push(vec![id]);
+                }
+                (id, paths)
+            })
+            .collect();
+
+        let paths = ShortestPaths {
+            paths,
+            opts: opts.clone(),
+        };
+        return Ok(Box::new(paths) as Box<traits::Emit>);
+    }
+
+    let paths = Paths {
+        items: functions,
+        opts: opts.clone(),
+    };
+
+    Ok(Box::new(paths) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Neighbors {
+    items: Vec<Neighborhood>,
+    max_neighbors: usize,
+}
+
+#[derive(Debug)]
+struct Neighborhood {
+    id: ir::Id,
+    callers: Vec<Neighbor>,
+    callees: Vec<Neighbor>,
+}
+
+#[derive(Debug)]
+struct Neighbor {
+    id: ir::Id,
+    edge_kinds: ir::EdgeKinds,
+    other_callers: u32,
+}
+
+impl traits::Emit for Neighbors {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Shallow Bytes".to_string()),
+            (Align::Right, "Retained Bytes".to_string()),
+            (Align::Right, "Other Callers".to_string()),
+            (Align::Left, "Edge".to_string()),
+            (Align::Left, "Neighbors".to_string()),
+        ]);
+
+        for neighborhood in &self.items {
+            let id = neighborhood.id;
+            table.add_row(vec![
+                items[id].size().to_string(),
+                items.retained_size(id).to_string(),
+                String::new(),
+                String::new(),
+                items.display_name(id).into_owned(),
+            ]);
+
+            let directions = [("⬑", &neighborhood.callers), ("↳", &neighborhood.callees)];
+            for &(arrow, neighbors) in &directions {
+                for neighbor in neighbors.iter().take(self.max_neighbors) {
+                    table.add_row(vec![
+                        items[neighbor.id].size().to_string(),
+                        items.retained_size(neighbor.id).to_string(),
+                        neighbor.other_callers.to_string(),
+                        neighbor.edge_kinds.to_string(),
+                        format!("  {} {}", arrow, items.display_name(neighbor.id)),
+                    ]);
+                }
+                if neighbors.len() > self.max_neighbors {
+                    table.add_row(vec![
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                        format!(
+                            "  {} ... and {} more",
+                            arrow,
+                            neighbors.len() - self.max_neighbors
+                        ),
+                    ]);
+                }
+            }
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        fn sizes(items: &ir::Items, obj: &mut json::Object, id: ir::Id) -> io::Result<()> {
+            obj.field("name", &*items.display_name(id))?;
+
+            let size = items[id].size();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            obj.field("shallow_size", size)?;
+            obj.field("shallow_size_percent", size_percent)?;
+
+            let size = items.retained_size(id);
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            obj.field("retained_size", size)?;
+            obj.field("retained_size_percent", size_percent)
+        }
+
+        let mut arr = json::array(dest)?;
+
+        for neighborhood in &self.items {
+            let mut obj = arr.object()?;
+            sizes(items, &mut obj, neighborhood.id)?;
+
+            let directions = [
+                ("callers", &neighborhood.callers),
+                ("callees", &neighborhood.callees),
+            ];
+            for &(name, neighbors) in &directions {
+                let mut arr = obj.array(name)?;
+                for neighbor in neighbors.iter().take(self.max_neighbors) {
+                    let mut obj = arr.object()?;
+                    sizes(items, &mut obj, neighbor.id)?;
+
+                    {
+                        let mut kinds = obj.array("edge_kinds")?;
+                        for kind in neighbor.edge_kinds.iter() {
+                            kinds.elem(kind.as_str())?;
+                        }
+                    }
+
+                    obj.field("other_callers", neighbor.other_callers)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            item: String,
+            direction: &'static str,
+            name: String,
+            shallow_size: u32,
+            retained_size: u32,
+            edge_kinds: String,
+            other_callers: u32,
+        }
+
+        for neighborhood in &self.items {
+            let directions = [
+                ("Caller", &neighborhood.callers),
+                ("Callee", &neighborhood.callees),
+            ];
+            for &(direction, neighbors) in &directions {
+                for neighbor in neighbors.iter().take(self.max_neighbors) {
+                    wtr.serialize(CsvRecord {
+                        item: items.display_name(neighborhood.id).into_owned(),
+                        direction: direction,
+                        name: items.display_name(neighbor.id).into_owned(),
+                        shallow_size: items[neighbor.id].size(),
+                        retained_size: items.retained_size(neighbor.id),
+                        edge_kinds: neighbor.edge_kinds.to_string(),
+                        other_callers: neighbor.other_callers,
+                    })?;
+                }
+            }
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Find the immediate callers and callees of the given items. Each neighbor
+/// comes with the kinds of its edge to or from the item, and how many callers
+/// it has besides the item, which tells whether it would go away with it.
+pub fn neighbors(
+    items: &mut ir::Items,
+    opts: &opt::Neighbors,
+) -> Result<Box<traits::Emit>, traits::Error> {
+    ignore_edges(items, opts.ignore_edges())?;
+    items.compute_predecessors();
+    items.compute_retained_sizes();
+
+    let names: Vec<_> = opts.items().iter().map(|name| name.as_str()).collect();
+    if names.is_empty() {
+        return Err(traits::Error::with_msg(
+            "give the names of the items whose neighbors to show",
+        ));
+    }
+    let ids = items_named(items, &names, opts.using_regexps())?;
+
+    let meta_root = items.meta_root();
+    let neighbor = |id: ir::Id, other: ir::Id, edge_kinds: ir::EdgeKinds| Neighbor {
+        id,
+        edge_kinds,
+        other_callers: items
+            .predecessors(id)
+            .filter(|&caller| caller != other && caller != meta_root)
+            .count() as u32,
+    };
+    let by_retained_size = |a: &Neighbor, b: &Neighbor| {
+        items
+            .retained_size(b.id)
+            .cmp(&items.retained_size(a.id))
+            .then(a.id.cmp(&b.id))
+    };
+
+    let mut neighborhoods = vec![];
+    for id in ids {
+        let mut callers: Vec<_> = items
+            .predecessors(id)
+            .filter(|&caller| caller != meta_root)
+            .map(|caller| neighbor(caller, id, items.edge_kinds(caller, id)))
+            .collect();
+        callers.sort_by(&by_retained_size);
+
+        let mut callees: Vec<_> = items
+            .neighbors(id)
+            .map(|callee| neighbor(callee, id, items.edge_kinds(id, callee)))
+            .collect();
+        callees.sort_by(&by_retained_size);
+
+        neighborhoods.push(Neighborhood {
+            id,
+            callers,
+            callees,
+        });
+    }
+
+    let neighbors = Neighbors {
+        items: neighborhoods,
+        max_neighbors: opts.max_neighbors() as usize,
+    };
+
+    Ok(Box::new(neighbors) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Monos {
+    monos: Vec<MonosEntry>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct MonosEntry {
+    generic: Option<String>,
+    argument: Option<String>,
+    insts: Vec<ir::Id>,
+    total: u32,
+    approx_potential_savings: u32,
+}
+
+impl MonosEntry {
+    /// The name of this group of monomorphizations, such as the generic
+    /// function they instantiate.
+    fn name(&self) -> String {
+        match (&self.generic, &self.argument) {
+            (&Some(ref generic), &Some(ref argument)) => format!("{} over {}", generic, argument),
+            (&Some(ref generic), &None) => generic.clone(),
+            (&None, &Some(ref argument)) => argument.clone(),
+            (&None, &None) => unreachable!("monomorphizations are grouped by something"),
+        }
+    }
+}
+
+impl PartialOrd for MonosEntry {
+    fn partial_cmp(&self, rhs: &MonosEntry) -> Option<cmp::Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl Ord for MonosEntry {
+    fn cmp(&self, rhs: &MonosEntry) -> cmp::Ordering {
+        rhs.approx_potential_savings
+            .cmp(&self.approx_potential_savings)
+            .then(self.insts.cmp(&rhs.insts))
+            .then(self.generic.cmp(&rhs.generic))
+            .then(self.argument.cmp(&rhs.argument))
+    }
+}
+
+impl traits::Emit for Monos {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Apprx. Bloat Bytes".into()),
+            (Align::Right, "Apprx. Bloat %".into()),
+            (Align::Right, "Bytes".into()),
+            (Align::Right, "%".into()),
+            (Align::Left, "Monomorphizations".to_string()),
+        ]);
+
+        for entry in &self.monos {
+            let total_percent = (f64::from(entry.total)) / (f64::from(items.size())) * 100.0;
+            let approx_potential_savings_percent =
+                (f64::from(entry.approx_potential_savings)) / (f64::from(items.size())) * 100.0;
+            table.add_row(vec![
+                entry.approx_potential_savings.to_string(),
+                format!("{:.2}%", approx_potential_savings_percent),
+                entry.total.to_string(),
+                format!("{:.2}%", total_percent),
+                entry.name(),
+            ]);
+
+            for &id in &entry.insts {
+                let item = &items[id];
+
+                let size = item.size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+
+                table.add_row(vec![
+                    "".into(),
+                    "".into(),
+                    size.to_string(),
+                    format!("{:.2}%", size_percent),
+                    format!("    {}", items.display_name(id)),
+                ]);
+            }
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+
+        for entry in &self.monos {
+            let mut obj = arr.object()?;
+            if let Some(ref generic) = entry.generic {
+                obj.field("generic", generic.as_str())?;
+            }
+            if let Some(ref argument) = entry.argument {
+                obj.field("type_argument", argument.as_str())?;
+            }
+
+            obj.field(
+                "approximate_monomorphization_bloat_bytes",
+                entry.approx_potential_savings,
+            )?;
+            let approx_potential_savings_percent =
+                (f64::from(entry.approx_potential_savings)) / (f64::from(items.size())) * 100.0;
+            obj.field(
+                "approximate_monomorphization_bloat_percent",
+                approx_potential_savings_percent,
+            )?;
+
+            obj.field("total_size", entry.total)?;
+            let total_percent = (f64::from(entry.total)) / (f64::from(items.size())) * 100.0;
+            obj.field("total_size_percent", total_percent)?;
+
+            let mut monos = obj.array("monomorphizations")?;
+            for &id in &entry.insts {
+                let item = &items[id];
+
+                let mut obj = monos.object()?;
+                obj.field("name", &*items.display_name(id))?;
+
+                let size = item.size();
+                obj.field("shallow_size", size)?;
+
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                obj.field("shallow_size_percent", size_percent)?;
+
+                if !item.type_arguments().is_empty() {
+                    let mut args = obj.array("type_arguments")?;
+                    for arg in item.type_arguments() {
+                        args.elem(arg.as_str())?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        #[derive(Debug, Default, Serialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Record {
+            generic: Option<String>,
+            approximate_monomorphization_bloat_bytes: Option<u32>,
+            approximate_monomorphization_bloat_percent: Option<f64>,
+            total_size: Option<u32>,
+            total_size_percent: Option<f64>,
+            monomorphizations: Option<String>,
+        }
+
+        let mut wtr = csv::Writer::from_writer(dest);
+        let mut rc;
+        for entry in &self.monos {
+            let approx_potential_savings_percent =
+                (f64::from(entry.approx_potential_savings)) / (f64::from(items.size())) * 100.0;
+
+            let total_percent = (f64::from(entry.total)) / (f64::from(items.size())) * 100.0;
+            rc = Record {
+                generic: Some(entry.name()),
+                approximate_monomorphization_bloat_bytes: Some(entry.approx_potential_savings),
+                approximate_monomorphization_bloat_percent: Some(approx_potential_savings_percent),
+                total_size: Some(entry.total),
+                total_size_percent: Some(total_percent),
+                ..Default::default()
+            };
+
+            let monos: Vec<_> = entry
+                .insts
+                .iter()
+                .map(|&id| items.display_name(id))
+                .collect();
+            rc.monomorphizations = Some(monos.join(", "));
+            wtr.serialize(rc)?;
+            wtr.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Find bloaty monomorphizations of generic functions.
+pub fn monos(items: &mut ir::Items, opts: &opt::Monos) -> Result<Box<traits::Emit>, traits::Error> {
+    let (by_generic, by_argument) = match opts.group_by() {
+        "template" => (true, false),
+        "argument" => (false, true),
+        "both" => (true, true),
+        other => {
+            return Err(traits::Error::with_msg(format!(
+                "unknown monomorphization grouping: {}",
+                other
+            )))
+        }
+    };
+
+    // Group the monomorphizations by the generic function they instantiate,
+    // by each of their type arguments, or by both.
+    let mut monos: BTreeMap<(Option<&str>, Option<&str>), BTreeSet<ir::Id>> = BTreeMap::new();
+    let mut without_arguments = 0;
+    for item in items.iter() {
+        let generic = match item.monomorphization_of() {
+            Some(generic) => generic,
+            None => continue,
+        };
+        let generic = if by_generic { Some(generic) } else { None };
+        if item.type_arguments().is_empty() {
+            without_arguments += 1;
+        }
+
+        if by_argument {
+            for argument in item.type_arguments() {
+                monos
+                    .entry((generic, Some(argument.as_str())))
+                    .or_insert_with(BTreeSet::new)
+                    .insert(item.id());
+            }
+        } else {
+            monos
+                .entry((generic, None))
+                .or_insert_with(BTreeSet::new)
+                .insert(item.id());
+        }
+    }
+
+    // Legacy Rust symbols don't include their generic arguments, so grouping
+    // their monomorphizations by argument would silently find nothing.
+    if by_argument && monos.is_empty() && without_arguments > 0 {
+        return Err(traits::Error::with_msg(format!(
+            "none of the {} monomorphizations have known type arguments to group by, \
+             legacy Rust symbols don't include them",
+            without_arguments
+        )));
+    }
+
+    let mut monos: Vec<_> = monos
+        .into_iter()
+        .filter_map(|((generic, argument), insts)| {
+            if insts.len() <= 1 {
+                return None;
+            }
+
+            let max = insts.iter().map(|id| items[*id].size()).max().unwrap();
+            let total = insts.iter().map(|id| items[*id].size()).sum();
+            let size_per_inst = total / (insts.len() as u32);
+            let approx_potential_savings =
+                cmp::min(size_per_inst * (insts.len() as u32 - 1), total - max);
+
+            let generic = generic.map(|g| g.to_string());
+            let argument = argument.map(|a| a.to_string());
+
+            let mut insts: Vec<_> = insts.into_iter().collect();
+            insts.sort_by(|a, b| {
+                let a = &items[*a];
+                let b = &items[*b];
+                b.size().cmp(&a.size())
+            });
+            insts.truncate(if opts.only_generics() {
+                0
+            } else {
+                opts.max_monos() as usize
+            });
+
+            Some(MonosEntry {
+                generic,
+                argument,
+                insts,
+                total,
+                approx_potential_savings,
+            })
+        })
+        .collect();
+
+    monos.sort();
+    monos.truncate(opts.max_generics() as usize);
+
+    Ok(Box::new(Monos { monos }) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Dups {
+    groups: Vec<DupsGroup>,
+    near: bool,
+    max_members: usize,
+}
+
+#[derive(Debug)]
+struct DupsGroup {
+    functions: Vec<ir::Id>,
+    total: u32,
+    potential_savings: u32,
+}
+
+impl Dups {
+    /// Describe a group of duplicates, such as "3 identical functions".
+    fn group_name(&self, group: &DupsGroup) -> String {
+        format!(
+            "{} {} functions",
+            group.functions.len(),
+            if self.near {
+                "nearly identical"
+            } else {
+                "identical"
+            }
+        )
+    }
+}
+
+impl traits::Emit for Dups {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Savings Bytes".to_string()),
+            (Align::Right, "Savings %".to_string()),
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "%".to_string()),
+            (Align::Left, "Duplicates".to_string()),
+        ]);
+
+        for group in &self.groups {
+            let savings_percent =
+                (f64::from(group.potential_savings)) / (f64::from(items.size())) * 100.0;
+            let total_percent = (f64::from(group.total)) / (f64::from(items.size())) * 100.0;
+            table.add_row(vec![
+                group.potential_savings.to_string(),
+                format!("{:.2}%", savings_percent),
+                group.total.to_string(),
+                format!("{:.2}%", total_percent),
+                self.group_name(group),
+            ]);
+
+            for &id in group.functions.iter().take(self.max_members) {
+                let size = items[id].size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                table.add_row(vec![
+                    String::new(),
+                    String::new(),
+                    size.to_string(),
+                    format!("{:.2}%", size_percent),
+                    format!("    {}", items.display_name(id)),
+                ]);
+            }
+
+            if group.functions.len() > self.max_members {
+                let rest = &group.functions[self.max_members..];
+                let size: u32 = rest.iter().map(|&id| items[id].size()).sum();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                table.add_row(vec![
+                    String::new(),
+                    String::new(),
+                    size.to_string(),
+                    format!("{:.2}%", size_percent),
+                    format!("    ... and {} more", rest.len()),
+                ]);
+            }
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+
+        for group in &self.groups {
+            let mut obj = arr.object()?;
+
+            let savings_percent =
+                (f64::from(group.potential_savings)) / (f64::from(items.size())) * 100.0;
+            obj.field("potential_savings_bytes", group.potential_savings)?;
+            obj.field("potential_savings_percent", savings_percent)?;
+
+            let total_percent = (f64::from(group.total)) / (f64::from(items.size())) * 100.0;
+            obj.field("total_size", group.total)?;
+            obj.field("total_size_percent", total_percent)?;
+            obj.field("function_count", group.functions.len() as u32)?;
+
+            let mut functions = obj.array("functions")?;
+            for &id in group.functions.iter().take(self.max_members) {
+                let mut obj = functions.object()?;
+                obj.field("name", &*items.display_name(id))?;
+
+                let size = items[id].size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                obj.field("shallow_size", size)?;
+                obj.field("shallow_size_percent", size_percent)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            group: u32,
+            potential_savings_bytes: u32,
+            potential_savings_percent: f64,
+            name: String,
+            shallow_size: u32,
+            shallow_size_percent: f64,
+        }
+
+        for (i, group) in self.groups.iter().enumerate() {
+            let savings_percent =
+                (f64::from(group.potential_savings)) / (f64::from(items.size())) * 100.0;
+            for &id in group.functions.iter().take(self.max_members) {
+                let size = items[id].size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                wtr.serialize(CsvRecord {
+                    group: i as u32 + 1,
+                    potential_savings_bytes: group.potential_savings,
+                    potential_savings_percent: savings_percent,
+                    name: items.display_name(id).into_owned(),
+                    shallow_size: size,
+                    shallow_size_percent: size_percent,
+                })?;
+            }
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Find groups of functions whose bodies hash the same, either exactly or after
+/// normalizing them, and could be folded into one. The potential savings of a
+/// group are the sizes of every function in it but the largest.
+pub fn dups(items: &ir::Items, opts: &opt::Dups) -> Result<Box<traits::Emit>, traits::Error> {
+    let mut by_hash: BTreeMap<u64, Vec<ir::Id>> = BTreeMap::new();
+    for item in items.iter() {
+        if let Some(hash) = item.body_hash(opts.near()) {
+            by_hash.entry(hash).or_insert_with(Vec::new).push(item.id());
+        }
+    }
+
+    if by_hash.is_empty() {
+        return Err(traits::Error::with_msg(
+            "no function bodies were hashed: native binaries stripped of their symbols or \
+             debug info, wasm binaries whose code section could not be decoded, and snapshots \
+             of either have no hashes",
+        ));
+    }
+
+    let mut groups: Vec<_> = by_hash
+        .into_iter()
+        .filter(|&(_, ref functions)| functions.len() > 1)
+        .map(|(_, mut functions)| {
+            functions.sort_by(|&a, &b| items[b].size().cmp(&items[a].size()).then(a.cmp(&b)));
+            let total: u32 = functions.iter().map(|&id| items[id].size()).sum();
+            DupsGroup {
+                potential_savings: total - items[functions[0]].size(),
+                total,
+                functions,
+            }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.potential_savings
+            .cmp(&a.potential_savings)
+            .then(a.functions[0].cmp(&b.functions[0]))
+    });
+    groups.truncate(opts.max_groups() as usize);
+
+    let dups = Dups {
+        groups,
+        near: opts.near(),
+        max_members: opts.max_members() as usize,
+    };
+
+    Ok(Box::new(dups) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Strings {
+    kinds: Vec<StringKindSummary>,
+    strings: Vec<StringEntry>,
+    duplicates: Vec<StringEntry>,
+}
+
+#[derive(Debug)]
+struct StringKindSummary {
+    kind: StringKind,
+    count: u32,
+    size: u32,
+}
+
+#[derive(Debug)]
+struct StringEntry {
+    value: String,
+    kind: StringKind,
+    data: ir::Id,
+    referenced_by: Vec<ir::Id>,
+    copies: u32,
+}
+
+impl StringEntry {
+    fn size(&self) -> u32 {
+        self.value.len() as u32
+    }
+
+    /// The bytes that would be saved by keeping only one copy of this string.
+    fn wasted(&self) -> u32 {
+        self.size() * (self.copies - 1)
+    }
+}
+
+/// The common sources of bloat among string literals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum StringKind {
+    Assertion,
+    Panic,
+    Path,
+    Format,
+    Other,
+}
+
+impl StringKind {
+    /// Guess what a string literal is for from what it looks like.
+    fn classify(value: &str) -> StringKind {
+        const ASSERTIONS: &[&str] = &["assertion failed", "assertion `left", "debug_assert"];
+        const PANICS: &[&str] = &[
+            "panicked",
+            "called `Option::unwrap()`",
+            "called `Result::unwrap()`",
+            "index out of bounds",
+            "attempt to ",
+            "overflow",
+            "unreachable",
+            "explicit panic",
+            "already borrowed",
+            "out of range",
+        ];
+        const PATH_EXTENSIONS: &[&str] = &[".rs", ".c", ".cc", ".cpp", ".h", ".hpp"];
+        const FORMATS: &[&str] = &["{}", "{:", "%s", "%d", "%u", "%x", "%p", "%f", "%l"];
+
+        if ASSERTIONS.iter().any(|s| value.contains(s)) {
+            StringKind::Assertion
+        } else if PANICS.iter().any(|s| value.contains(s)) {
+            StringKind::Panic
+        } else if value.split_whitespace().any(|word| {
+            (word.contains('/') || word.contains('\\'))
+                && PATH_EXTENSIONS.iter().any(|ext| word.ends_with(ext))
+        }) {
+            StringKind::Path
+        } else if FORMATS.iter().any(|s| value.contains(s)) {
+            StringKind::Format
+        } else {
+            StringKind::Other
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match *self {
+            StringKind::Assertion => "assertion",
+            StringKind::Panic => "panic message",
+            StringKind::Path => "file path",
+            StringKind::Format => "format string",
+            StringKind::Other => "other",
+        }
+    }
+}
+
+/// Quote and escape a string literal for display, shortening long ones.
+fn display_string(value: &str) -> String {
+    const MAX_CHARS: usize = 60;
+    if value.chars().count() <= MAX_CHARS {
+        return format!("{:?}", value);
+    }
+    let shortened: String = value.chars().take(MAX_CHARS).collect();
+    format!("{:?}...", shortened)
+}
+
+impl traits::Emit for Strings {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut kinds = Table::with_header(vec![
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "Size %".to_string()),
+            (Align::Right, "Count".to_string()),
+            (Align::Left, "Kind".to_string()),
+        ]);
+        for summary in &self.kinds {
+            let size_percent = (f64::from(summary.size)) / (f64::from(items.size())) * 100.0;
+            kinds.add_row(vec![
+                summary.size.to_string(),
+                format!("{:.2}%", size_percent),
+                summary.count.to_string(),
+                summary.kind.as_str().to_string(),
+            ]);
+        }
+        write!(dest, "{}", &kinds)?;
+
+        let mut strings = Table::with_header(vec![
+            (Align::Right, "Bytes".to_string()),
+            (Align::Left, "Kind".to_string()),
+            (Align::Left, "String".to_string()),
+            (Align::Left, "Referenced By".to_string()),
+        ]);
+        for entry in &self.strings {
+            let referenced_by: Vec<_> = entry
+                .referenced_by
+                .iter()
+                .map(|&id| items.display_name(id).into_owned())
+                .collect();
+            strings.add_row(vec![
+                entry.size().to_string(),
+                entry.kind.as_str().to_string(),
+                display_string(&entry.value),
+                referenced_by.join(", "),
+            ]);
+        }
+        write!(dest, "\n{}", &strings)?;
+
+        if !self.duplicates.is_empty() {
+            let mut duplicates = Table::with_header(vec![
+                (Align::Right, "Wasted Bytes".to_string()),
+                (Align::Right, "Copies".to_string()),
+                (Align::Left, "Duplicated String".to_string()),
+            ]);
+            for entry in &self.duplicates {
+                duplicates.add_row(vec![
+                    entry.wasted().to_string(),
+                    entry.copies.to_string(),
+                    display_string(&entry.value),
+                ]);
+            }
+            write!(dest, "\n{}", &duplicates)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut obj = json::object(dest)?;
+
+        {
+            let mut kinds = obj.array("kinds")?;
+            for summary in &self.kinds {
+                let mut obj = kinds.object()?;
+                let size_percent = (f64::from(summary.size)) / (f64::from(items.size())) * 100.0;
+                obj.field("kind", summary.kind.as_str())?;
+                obj.field("count", summary.count)?;
+                obj.field("size", summary.size)?;
+                obj.field("size_percent", size_percent)?;
+            }
+        }
+
+        {
+            let mut strings = obj.array("strings")?;
+            for entry in &self.strings {
+                let mut obj = strings.object()?;
+                obj.field("value", entry.value.as_str())?;
+                obj.field("kind", entry.kind.as_str())?;
+                obj.field("size", entry.size())?;
+                obj.field("data", &*items.display_name(entry.data))?;
+
+                let mut arr = obj.array("referenced_by")?;
+                for &id in &entry.referenced_by {
+                    arr.elem(&*items.display_name(id))?;
+                }
+            }
+        }
+
+        let mut duplicates = obj.array("duplicates")?;
+        for entry in &self.duplicates {
+            let mut obj = duplicates.object()?;
+            obj.field("value", entry.value.as_str())?;
+            obj.field("kind", entry.kind.as_str())?;
+            obj.field("copies", entry.copies)?;
+            obj.field("wasted_bytes", entry.wasted())?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            value: String,
+            kind: &'static str,
+            size: u32,
+            copies: u32,
+            data: String,
+            referenced_by: String,
+        }
+
+        for entry in &self.strings {
+            let referenced_by: Vec<_> = entry
+                .referenced_by
+                .iter()
+                .map(|&id| items.display_name(id).into_owned())
+                .collect();
+            wtr.serialize(CsvRecord {
+                value: entry.value.clone(),
+                kind: entry.kind.as_str(),
+                size: entry.size(),
+                copies: entry.copies,
+                data: items.display_name(entry.data).into_owned(),
+                referenced_by: referenced_by.join(", "),
+            })?;
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Find the string literals in the binary's static data, and report the
+/// largest, the duplicated ones, and how many bytes go to each kind of string.
+///
+/// Literals that are laid out back to back are found as one run of printable
+/// characters, so each run is split up at every offset that an item refers to
+/// it at, and each piece is attributed to the items that refer to its start.
+pub fn strings(items: &ir::Items, opts: &opt::Strings) -> Result<Box<traits::Emit>, traits::Error> {
+    let mut pieces = vec![];
+    for string in items.strings() {
+        let value = string.value();
+        let mut splits: Vec<usize> = string
+            .references()
+            .iter()
+            .map(|&(offset, _)| offset as usize)
+            .filter(|&offset| value.is_char_boundary(offset))
+            .collect();
+        splits.push(0);
+        splits.push(value.len());
+        splits.sort();
+        splits.dedup();
+
+        for bounds in splits.windows(2) {
+            let (start, end) = (bounds[0], bounds[1]);
+            let referenced_by: BTreeSet<_> = string
+                .references()
+                .iter()
+                .filter(|&&(offset, _)| offset as usize == start)
+                .map(|&(_, id)| id)
+                .collect();
+            let value = &value[start..end];
+            pieces.push(StringEntry {
+                value: value.to_string(),
+                kind: StringKind::classify(value),
+                data: string.data(),
+                referenced_by: referenced_by.into_iter().collect(),
+                copies: 1,
+            });
+        }
+    }
+
+    // How many copies of each string there are, and where the first one is.
+    let mut copies: BTreeMap<&str, (u32, usize)> = BTreeMap::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        copies.entry(piece.value.as_str()).or_insert((0, i)).0 += 1;
+    }
+
+    let mut kinds: BTreeMap<StringKind, StringKindSummary> = BTreeMap::new();
+    for piece in &pieces {
+        let summary = kinds.entry(piece.kind).or_insert(StringKindSummary {
+            kind: piece.kind,
+            count: 0,
+            size: 0,
+        });
+        summary.count += 1;
+        summary.size += piece.size();
+    }
+    let mut kinds: Vec<_> = kinds.into_iter().map(|(_, summary)| summary).collect();
+    kinds.sort_by(|a, b| b.size.cmp(&a.size).then(a.kind.cmp(&b.kind)));
+
+    let mut duplicates: Vec<_> = copies
+        .iter()
+        .filter(|&(_, &(n, _))| n > 1)
+        .map(|(_, &(n, i))| StringEntry {
+            value: pieces[i].value.clone(),
+            kind: pieces[i].kind,
+            data: pieces[i].data,
+            referenced_by: vec![],
+            copies: n,
+        })
+        .collect();
+    duplicates.sort_by(|a, b| b.wasted().cmp(&a.wasted()).then(a.value.cmp(&b.value)));
+    duplicates.truncate(opts.max_strings() as usize);
+
+    let mut strings: Vec<_> = pieces
+        .iter()
+        .map(|piece| StringEntry {
+            value: piece.value.clone(),
+            kind: piece.kind,
+            data: piece.data,
+            referenced_by: piece.referenced_by.clone(),
+            copies: copies[piece.value.as_str()].0,
+        })
+        .collect();
+    strings.sort_by(|a, b| {
+        b.size()
+            .cmp(&a.size())
+            .then(a.value.cmp(&b.value))
+            .then(a.data.cmp(&b.data))
+    });
+    strings.truncate(opts.max_strings() as usize);
+
+    let strings = Strings {
+        kinds,
+        strings,
+        duplicates,
+    };
+
+    Ok(Box::new(strings) as Box<traits::Emit>)
+}
+
+/// The well-known subsystems of the Rust standard library, and the crates it
+/// uses, that bloat binaries. Items are part of a subsystem when their
+/// demangled names match one of its patterns.
+const RUST_BLOAT_SUBSYSTEMS: &[(&str, &[&str])] = &[
+    (
+        "panicking",
+        &[
+            r"^<?(core|std)::(panicking|panic)::",
+            r"^rust_begin_unwind$",
+            r"^rust_panic",
+        ],
+    ),
+    ("core::fmt", &[r"^<?core::fmt::"]),
+    ("alloc::fmt", &[r"^<?alloc::fmt::"]),
+    (
+        "backtrace",
+        &[
+            r"^<?std::(sys_common::|sys::)?backtrace::",
+            r"^<?(backtrace|addr2line|gimli|miniz_oxide|rustc_demangle)::",
+        ],
+    ),
+    (
+        "unwinding",
+        &[
+            r"^<?(panic_unwind|unwind)::",
+            r"^_Unwind_",
+            r"^__rust_start_panic$",
+            r"^rust_eh_personality$",
+            r"^__g(cc|xx)_personality",
+        ],
+    ),
+    (
+        "allocator",
+        &[
+            r"^<?(dlmalloc|wee_alloc)::",
+            r"^__(rust|rdl|rg)_(alloc|dealloc|realloc|alloc_zeroed)$",
+            r"^(malloc|free|calloc|realloc)$",
+        ],
+    ),
+];
+
+#[derive(Debug)]
+struct RustBloat {
+    subsystems: Vec<Subsystem>,
+}
+
+#[derive(Debug)]
+struct Subsystem {
+    name: &'static str,
+    members: u32,
+    exclusive: u32,
+    entry_points: Vec<(ir::Id, u32)>,
+    entry_point_count: u32,
+}
+
+impl traits::Emit for RustBloat {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Exclusive Bytes".to_string()),
+            (Align::Right, "Exclusive %".to_string()),
+            (Align::Right, "Items".to_string()),
+            (Align::Left, "Subsystem and Entry Points".to_string()),
+        ]);
+
+        for subsystem in &self.subsystems {
+            let exclusive_percent =
+                (f64::from(subsystem.exclusive)) / (f64::from(items.size())) * 100.0;
+            table.add_row(vec![
+                subsystem.exclusive.to_string(),
+                format!("{:.2}%", exclusive_percent),
+                subsystem.members.to_string(),
+                subsystem.name.to_string(),
+            ]);
+
+            for &(id, pulled_in) in &subsystem.entry_points {
+                let pulled_in_percent = (f64::from(pulled_in)) / (f64::from(items.size())) * 100.0;
+                table.add_row(vec![
+                    pulled_in.to_string(),
+                    format!("{:.2}%", pulled_in_percent),
+                    String::new(),
+                    format!("    ⬑ {}", items.display_name(id)),
+                ]);
+            }
+
+            let rest = subsystem.entry_point_count as usize - subsystem.entry_points.len();
+            if rest > 0 {
+                table.add_row(vec![
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    format!("    ⬑ ... and {} more", rest),
+                ]);
+            }
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+
+        for subsystem in &self.subsystems {
+            let mut obj = arr.object()?;
+            obj.field("subsystem", subsystem.name)?;
+            obj.field("items", subsystem.members)?;
+
+            let exclusive_percent =
+                (f64::from(subsystem.exclusive)) / (f64::from(items.size())) * 100.0;
+            obj.field("exclusive_size", subsystem.exclusive)?;
+            obj.field("exclusive_size_percent", exclusive_percent)?;
+            obj.field("entry_point_count", subsystem.entry_point_count)?;
+
+            let mut entry_points = obj.array("entry_points")?;
+            for &(id, pulled_in) in &subsystem.entry_points {
+                let mut obj = entry_points.object()?;
+                obj.field("name", &*items.display_name(id))?;
+
+                let pulled_in_percent = (f64::from(pulled_in)) / (f64::from(items.size())) * 100.0;
+                obj.field("pulled_in_size", pulled_in)?;
+                obj.field("pulled_in_size_percent", pulled_in_percent)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            subsystem: &'static str,
+            items: u32,
+            exclusive_size: u32,
+            exclusive_size_percent: f64,
+            entry_points: String,
+        }
+
+        for subsystem in &self.subsystems {
+            let exclusive_percent =
+                (f64::from(subsystem.exclusive)) / (f64::from(items.size())) * 100.0;
+            let entry_points: Vec<_> = subsystem
+                .entry_points
+                .iter()
+                .map(|&(id, _)| items.display_name(id).into_owned())
+                .collect();
+            wtr.serialize(CsvRecord {
+                subsystem: subsystem.name,
+                items: subsystem.members,
+                exclusive_size: subsystem.exclusive,
+                exclusive_size_percent: exclusive_percent,
+                entry_points: entry_points.join(", "),
+            })?;
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Measure each of the well-known Rust bloat subsystems that the binary uses.
+///
+/// A subsystem's exclusive size is the size of everything that would become
+/// garbage if its items were removed. Its entry points are the reachable items
+/// outside of it that refer to it, ranked by how many of those bytes each one
+/// reaches, and then by their own size. Table entries and other items that
+/// only lead into the subsystem, such as the `func[..]` entries of its
+/// functions, are skipped over, so that the entry points are the code that
+/// actually uses it.
+pub fn rust_bloat(
+    items: &ir::Items,
+    opts: &opt::RustBloat,
+) -> Result<Box<traits::Emit>, traits::Error> {
+    let before = items.reachable();
+
+    let mut subsystems = vec![];
+    for &(name, patterns) in RUST_BLOAT_SUBSYSTEMS {
+        let regexps = regex::RegexSet::new(patterns)?;
+        let members: BTreeSet<_> = items
+            .iter()
+            .filter(|item| before.contains(&item.id()) && regexps.is_match(item.name()))
+            .map(|item| item.id())
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+
+        let after = items
+            .subgraph()
+            .keep_if(|item| !members.contains(&item.id()))
+            .finish()
+            .reachable();
+        let freed: BTreeSet<_> = before.difference(&after).cloned().collect();
+        let exclusive = freed.iter().map(|&id| items[id].size()).sum();
+
+        // Skip over the items that only lead into the subsystem.
+        let mut inside = freed.clone();
+        loop {
+            let leading_in: Vec<_> = after
+                .iter()
+                .cloned()
+                .filter(|&id| !inside.contains(&id) && !items.roots().contains(&id))
+                .filter(|&id| match *items[id].kind() {
+                    ir::ItemKind::Code(_) => false,
+                    _ => true,
+                })
+                .filter(|&id| {
+                    // Function entries also refer to their signatures, which
+                    // don't lead anywhere.
+                    let mut neighbors = items
+                        .neighbors(id)
+                        .filter(|&n| items.edge_kinds(id, n) != ir::EdgeKind::Type.into())
+                        .peekable();
+                    neighbors.peek().is_some() && neighbors.all(|n| inside.contains(&n))
+                })
+                .collect();
+            if leading_in.is_empty() {
+                break;
+            }
+            inside.extend(leading_in);
+        }
+
+        let mut entry_points: Vec<_> = after
+            .iter()
+            .cloned()
+            .filter(|&id| !inside.contains(&id) && id != items.meta_root())
+            .filter(|&id| items.neighbors(id).any(|n| inside.contains(&n)))
+            .map(|id| {
+                let mut seen = BTreeSet::new();
+                let mut stack: Vec<_> =
+                    items.neighbors(id).filter(|n| inside.contains(n)).collect();
+                while let Some(id) = stack.pop() {
+                    if seen.insert(id) {
+                        stack.extend(items.neighbors(id).filter(|n| inside.contains(n)));
+                    }
+                }
+                let pulled_in: u32 = seen
+                    .iter()
+                    .filter(|id| freed.contains(id))
+                    .map(|&id| items[id].size())
+                    .sum();
+                (id, pulled_in)
+            })
+            .collect();
+        entry_points.sort_by(|&(a, a_pulled_in), &(b, b_pulled_in)| {
+            b_pulled_in
+                .cmp(&a_pulled_in)
+                .then(items[b].size().cmp(&items[a].size()))
+                .then(a.cmp(&b))
+        });
+        let entry_point_count = entry_points.len() as u32;
+        entry_points.truncate(opts.max_entry_points() as usize);
+
+        subsystems.push(Subsystem {
+            name,
+            members: members.len() as u32,
+            exclusive,
+            entry_points,
+            entry_point_count,
+        });
+    }
+
+    subsystems.sort_by(|a, b| b.exclusive.cmp(&a.exclusive).then(a.name.cmp(b.name)));
+
+    Ok(Box::new(RustBloat { subsystems }) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Stats {
+    kinds: Vec<(&'static str, u32, u32)>,
+    functions: Vec<u32>,
+    tiny: u32,
+    top: u32,
+    top_size: u32,
+}
+
+/// A range of function sizes, and how many functions, and bytes, fall in it.
+#[derive(Debug)]
+struct Bucket {
+    min: u32,
+    max: u32,
+    count: u32,
+    size: u32,
+}
+
+impl Stats {
+    fn total_function_size(&self) -> u32 {
+        self.functions.iter().sum()
+    }
+
+    fn mean(&self) -> f64 {
+        if self.functions.is_empty() {
+            return 0.0;
+        }
+        f64::from(self.total_function_size()) / self.functions.len() as f64
+    }
+
+    /// The smallest function size that at least `p` percent of the functions
+    /// are no larger than.
+    fn percentile(&self, p: u32) -> u32 {
+        if self.functions.is_empty() {
+            return 0;
+        }
+        let rank = (self.functions.len() * p as usize + 99) / 100;
+        self.functions[cmp::max(rank, 1) - 1]
+    }
+
+    fn tiny_functions(&self) -> (u32, u32) {
+        let tiny: Vec<_> = self
+            .functions
+            .iter()
+            .take_while(|&&size| size <= self.tiny)
+            .collect();
+        (tiny.len() as u32, tiny.into_iter().sum())
+    }
+
+    /// Bucket the function sizes by powers of two: the first bucket holds the
+    /// empty functions, then those of 1 byte, of 2 to 3 bytes, of 4 to 7 bytes,
+    /// and so on. Empty buckets after the last function are left out.
+    fn histogram(&self) -> Vec<Bucket> {
+        let mut buckets: Vec<Bucket> = vec![];
+        for &size in &self.functions {
+            let i = (32 - size.leading_zeros()) as usize;
+            while buckets.len() <= i {
+                let max = (1u64 << buckets.len()) - 1;
+                buckets.push(Bucket {
+                    min: ((max + 1) / 2) as u32,
+                    max: max as u32,
+                    count: 0,
+                    size: 0,
+                });
+            }
+            buckets[i].count += 1;
+            buckets[i].size += size;
+        }
+        let first = buckets.iter().position(|b| b.count > 0).unwrap_or(0);
+        buckets.split_off(first)
+    }
+}
+
+impl traits::Emit for Stats {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        const BAR_WIDTH: u32 = 40;
+
+        let mut kinds = Table::with_header(vec![
+            (Align::Right, "Items".to_string()),
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "Size %".to_string()),
+            (Align::Left, "Kind".to_string()),
+        ]);
+        for &(kind, count, size) in &self.kinds {
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            kinds.add_row(vec![
+                count.to_string(),
+                size.to_string(),
+                format!("{:.2}%", size_percent),
+                kind.to_string(),
+            ]);
+        }
+        write!(dest, "{}", &kinds)?;
+
+        let (tiny_count, tiny_size) = self.tiny_functions();
+        let top_percent = (f64::from(self.top_size)) / (f64::from(items.size())) * 100.0;
+        let mut stats = Table::with_header(vec![
+            (Align::Right, "Value".to_string()),
+            (Align::Left, "Statistic".to_string()),
+        ]);
+        let rows = vec![
+            (self.functions.len().to_string(), "functions".to_string()),
+            (
+                format!("{:.2}", self.mean()),
+                "mean function size".to_string(),
+            ),
+            (
+                self.percentile(50).to_string(),
+                "median function size".to_string(),
+            ),
+            (
+                self.percentile(90).to_string(),
+                "90th percentile function size".to_string(),
+            ),
+            (
+                self.percentile(99).to_string(),
+                "99th percentile function size".to_string(),
+            ),
+            (
+                self.functions.last().cloned().unwrap_or(0).to_string(),
+                "largest function size".to_string(),
+            ),
+            (
+                tiny_count.to_string(),
+                format!("tiny functions, of at most {} bytes", self.tiny),
+            ),
+            (tiny_size.to_string(), "bytes in tiny functions".to_string()),
+            (
+                format!("{:.2}%", top_percent),
+                format!("share of the largest {} items", self.top),
+            ),
+        ];
+        for (value, statistic) in rows {
+            stats.add_row(vec![value, statistic]);
+        }
+        write!(dest, "\n{}", &stats)?;
+
+        let histogram = self.histogram();
+        let most = histogram.iter().map(|b| b.count).max().unwrap_or(0);
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Function Size".to_string()),
+            (Align::Right, "Functions".to_string()),
+            (Align::Right, "Bytes".to_string()),
+            (Align::Left, "Histogram".to_string()),
+        ]);
+        for bucket in &histogram {
+            let width = (bucket.count * BAR_WIDTH + most - 1) / most;
+            table.add_row(vec![
+                format!("{}..={}", bucket.min, bucket.max),
+                bucket.count.to_string(),
+                bucket.size.to_string(),
+                "#".repeat(width as usize),
+            ]);
+        }
+        write!(dest, "\n{}", &table)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut obj = json::object(dest)?;
+
+        {
+            let mut kinds = obj.array("kinds")?;
+            for &(kind, count, size) in &self.kinds {
+                let mut obj = kinds.object()?;
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                obj.field("kind", kind)?;
+                obj.field("count", count)?;
+                obj.field("size", size)?;
+                obj.field("size_percent", size_percent)?;
+            }
+        }
+
+        {
+            let (tiny_count, tiny_size) = self.tiny_functions();
+            let mut functions = obj.object("functions")?;
+            functions.field("count", self.functions.len() as u32)?;
+            functions.field("size", self.total_function_size())?;
+            functions.field("mean_size", self.mean())?;
+            functions.field("median_size", self.percentile(50))?;
+            functions.field("p90_size", self.percentile(90))?;
+            functions.field("p99_size", self.percentile(99))?;
+            functions.field("max_size", self.functions.last().cloned().unwrap_or(0))?;
+            functions.field("tiny_threshold", self.tiny)?;
+            functions.field("tiny_count", tiny_count)?;
+            functions.field("tiny_size", tiny_size)?;
+        }
+
+        {
+            let mut histogram = obj.array("histogram")?;
+            for bucket in self.histogram() {
+                let mut obj = histogram.object()?;
+                obj.field("min_size", bucket.min)?;
+                obj.field("max_size", bucket.max)?;
+                obj.field("count", bucket.count)?;
+                obj.field("size", bucket.size)?;
+            }
+        }
+
+        let top_percent = (f64::from(self.top_size)) / (f64::from(items.size())) * 100.0;
+        let mut top = obj.object("top_items")?;
+        top.field("count", self.top)?;
+        top.field("size", self.top_size)?;
+        top.field("size_percent", top_percent)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            category: &'static str,
+            name: String,
+            count: Option<u32>,
+            size: Option<String>,
+            size_percent: Option<f64>,
+        }
+
+        for &(kind, count, size) in &self.kinds {
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            wtr.serialize(CsvRecord {
+                category: "kind",
+                name: kind.to_string(),
+                count: Some(count),
+                size: Some(size.to_string()),
+                size_percent: Some(size_percent),
+            })?;
+        }
+
+        let (tiny_count, tiny_size) = self.tiny_functions();
+        let function_stats = vec![
+            (
+                "all",
+                Some(self.functions.len() as u32),
+                self.total_function_size().to_string(),
+            ),
+            ("mean", None, format!("{:.2}", self.mean())),
+            ("median", None, self.percentile(50).to_string()),
+            ("p90", None, self.percentile(90).to_string()),
+            ("p99", None, self.percentile(99).to_string()),
+            (
+                "max",
+                None,
+                self.functions.last().cloned().unwrap_or(0).to_string(),
+            ),
+            ("tiny", Some(tiny_count), tiny_size.to_string()),
+        ];
+        for (name, count, size) in function_stats {
+            wtr.serialize(CsvRecord {
+                category: "functions",
+                name: name.to_string(),
+                count,
+                size: Some(size),
+                size_percent: None,
+            })?;
+        }
+
+        for bucket in self.histogram() {
+            wtr.serialize(CsvRecord {
+                category: "histogram",
+                name: format!("{}..={}", bucket.min, bucket.max),
+                count: Some(bucket.count),
+                size: Some(bucket.size.to_string()),
+                size_percent: None,
+            })?;
+        }
+
+        let top_percent = (f64::from(self.top_size)) / (f64::from(items.size())) * 100.0;
+        wtr.serialize(CsvRecord {
+            category: "top_items",
+            name: format!("largest {}", self.top),
+            count: Some(self.top),
+            size: Some(self.top_size.to_string()),
+            size_percent: Some(top_percent),
+        })?;
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Summarize the sizes of the items: the number and total size of each kind of
+/// item, the distribution of function sizes, and how much of the binary the
+/// largest items take up. Functions are code and subroutine items.
+pub fn stats(items: &ir::Items, opts: &opt::Stats) -> Result<Box<traits::Emit>, traits::Error> {
+    let mut kinds: BTreeMap<&'static str, (u32, u32)> = BTreeMap::new();
+    let mut functions = vec![];
+    let mut sizes = vec![];
+    for item in items.iter().filter(|item| item.id() != items.meta_root()) {
+        let kind = kinds.entry(item.kind().as_str()).or_insert((0, 0));
+        kind.0 += 1;
+        kind.1 += item.size();
+
+        match *item.kind() {
+            ir::ItemKind::Code(_) | ir::ItemKind::Subroutine(_) => functions.push(item.size()),
+            _ => {}
+        }
+        sizes.push(item.size());
+    }
+
+    let mut kinds: Vec<_> = kinds
+        .into_iter()
+        .map(|(kind, (count, size))| (kind, count, size))
+        .collect();
+    kinds.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+
+    functions.sort();
+    sizes.sort_by(|a, b| b.cmp(a));
+    sizes.truncate(opts.top() as usize);
+
+    let stats = Stats {
+        kinds,
+        functions,
+        tiny: opts.tiny(),
+        top: sizes.len() as u32,
+        top_size: sizes.iter().sum(),
+    };
+
+    Ok(Box::new(stats) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Namespaces {
+    namespaces: Vec<Namespace>,
+    opts: opt::Namespaces,
+}
+
+/// A namespace, such as a crate, module or type, and the items in it. Items
+/// whose names aren't paths are namespaces of their own.
+#[derive(Debug)]
+struct Namespace {
+    name: String,
+    items: u32,
+    shallow_size: u32,
+    retained_size: u32,
+    children: Vec<Namespace>,
+}
+
+impl Namespace {
+    fn shallow_size_percent(&self, items: &ir::Items) -> f64 {
+        (f64::from(self.shallow_size)) / (f64::from(items.size())) * 100.0
+    }
+
+    fn retained_size_percent(&self, items: &ir::Items) -> f64 {
+        (f64::from(self.retained_size)) / (f64::from(items.size())) * 100.0
+    }
+}
+
+/// Visit the namespaces in the tree depth first, parents before their
+/// children, with their full paths and depths, until `f` returns `false`.
+fn walk_namespaces<F>(namespaces: &[Namespace], path: &str, depth: u32, f: &mut F) -> bool
+where
+    F: FnMut(&Namespace, &str, u32) -> bool,
+{
+    for namespace in namespaces {
+        let path = if path.is_empty() {
+            namespace.name.clone()
+        } else {
+            format!("{}::{}", path, namespace.name)
+        };
+        if !f(namespace, &path, depth) || !walk_namespaces(&namespace.children, &path, depth + 1, f)
+        {
+            return false;
+        }
+    }
+    true
+}
+
+impl traits::Emit for Namespaces {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Retained Bytes".to_string()),
+            (Align::Right, "Retained %".to_string()),
+            (Align::Right, "Shallow Bytes".to_string()),
+            (Align::Right, "Shallow %".to_string()),
+            (Align::Right, "Items".to_string()),
+            (Align::Left, "Namespace".to_string()),
+        ]);
+
+        let mut rows = 0;
+        walk_namespaces(&self.namespaces, "", 1, &mut |namespace, _, depth| {
+            if rows == self.opts.max_rows() {
+                return false;
+            }
+            rows += 1;
+
+            let mut label = String::with_capacity(depth as usize * 4 + namespace.name.len());
+            for _ in 2..depth {
+                label.push_str("    ");
+            }
+            if depth != 1 {
+                label.push_str("  ⤷ ");
+            }
+            label.push_str(&namespace.name);
+
+            table.add_row(vec![
+                namespace.retained_size.to_string(),
+                format!("{:.2}%", namespace.retained_size_percent(items)),
+                namespace.shallow_size.to_string(),
+                format!("{:.2}%", namespace.shallow_size_percent(items)),
+                namespace.items.to_string(),
+                label,
+            ]);
+            true
+        });
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        fn add_namespaces(
+            items: &ir::Items,
+            namespaces: &[Namespace],
+            arr: &mut json::Array,
+        ) -> Result<(), traits::Error> {
+            for namespace in namespaces {
+                let mut obj = arr.object()?;
+                obj.field("name", namespace.name.as_str())?;
+                obj.field("items", namespace.items)?;
+                obj.field("shallow_size", namespace.shallow_size)?;
+                obj.field(
+                    "shallow_size_percent",
+                    namespace.shallow_size_percent(items),
+                )?;
+                obj.field("retained_size", namespace.retained_size)?;
+                obj.field(
+                    "retained_size_percent",
+                    namespace.retained_size_percent(items),
+                )?;
+                if !namespace.children.is_empty() {
+                    let mut children = obj.array("children")?;
+                    add_namespaces(items, &namespace.children, &mut children)?;
+                }
+            }
+            Ok(())
+        }
+
+        let mut arr = json::array(dest)?;
+        add_namespaces(items, &self.namespaces, &mut arr)
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            namespace: String,
+            depth: u32,
+            items: u32,
+            shallow_size: u32,
+            shallow_size_percent: f64,
+            retained_size: u32,
+            retained_size_percent: f64,
+        }
+
+        let mut rows = 0;
+        let mut result = Ok(());
+        walk_namespaces(&self.namespaces, "", 1, &mut |namespace, path, depth| {
+            if rows == self.opts.max_rows() {
+                return false;
+            }
+            rows += 1;
+
+            result = wtr.serialize(CsvRecord {
+                namespace: path.to_string(),
+                depth,
+                items: namespace.items,
+                shallow_size: namespace.shallow_size,
+                shallow_size_percent: namespace.shallow_size_percent(items),
+                retained_size: namespace.retained_size,
+                retained_size_percent: namespace.retained_size_percent(items),
+            });
+            result.is_ok()
+        });
+        result?;
+
+        wtr.flush()?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_svg")]
+    fn emit_svg(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        writeln!(
+            dest,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="11">"#,
+            TREEMAP_WIDTH, TREEMAP_HEIGHT
+        )?;
+
+        let total = self.namespaces.iter().map(|ns| ns.shallow_size).sum();
+        let rect = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: TREEMAP_WIDTH,
+            height: TREEMAP_HEIGHT,
+        };
+        for (i, (namespace, rect)) in layout_treemap(&self.namespaces, total, rect)
+            .into_iter()
+            .enumerate()
+        {
+            // Spread the hues of the top level namespaces around the color
+            // wheel, so that neighbors tend to look different.
+            let hue = i as u32 * 47 % 360;
+            namespace.draw(items, rect, "", 1, hue, dest)?;
+        }
+
+        writeln!(dest, "</svg>")?;
+        Ok(())
+    }
+}
+
+/// The size of the SVG treemap, in pixels.
+#[cfg(feature = "emit_svg")]
+const TREEMAP_WIDTH: f64 = 1200.0;
+#[cfg(feature = "emit_svg")]
+const TREEMAP_HEIGHT: f64 = 800.0;
+
+/// The height of the strip at the top of a namespace's rectangle that holds its
+/// label, above the rectangles of its children.
+#[cfg(feature = "emit_svg")]
+const TREEMAP_LABEL_HEIGHT: f64 = 16.0;
+
+/// A rectangle in the SVG treemap.
+#[cfg(feature = "emit_svg")]
+#[derive(Clone, Copy, Debug)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+#[cfg(feature = "emit_svg")]
+impl Namespace {
+    /// Draw this namespace's rectangle, and its children's inside of it. Every
+    /// namespace under the same top level namespace has the same hue, and gets
+    /// lighter the deeper it is.
+    fn draw(
+        &self,
+        items: &ir::Items,
+        rect: Rect,
+        parent_path: &str,
+        depth: u32,
+        hue: u32,
+        dest: &mut io::Write,
+    ) -> Result<(), traits::Error> {
+        let path = if parent_path.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}::{}", parent_path, self.name)
+        };
+
+        writeln!(
+            dest,
+            "<g><title>{}\n{} shallow bytes ({:.2}%)\n{} retained bytes ({:.2}%)</title>",
+            escape_xml(&path),
+            self.shallow_size,
+            self.shallow_size_percent(items),
+            self.retained_size,
+            self.retained_size_percent(items)
+        )?;
+        writeln!(
+            dest,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="hsl({}, 60%, {}%)" stroke="white"/>"#,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            hue,
+            cmp::min(35 + depth * 10, 90)
+        )?;
+
+        // Only label the rectangles that have room for a few characters, which
+        // are about 6.5 pixels wide.
+        if rect.width >= 30.0 && rect.height >= 14.0 {
+            let room = ((rect.width - 6.0) / 6.5) as usize;
+            let mut label = format!("{} ({})", self.name, self.shallow_size);
+            if label.chars().count() > room {
+                let end = label.char_indices().nth(room - 1).unwrap().0;
+                label.truncate(end);
+                label.push('…');
+            }
+            writeln!(
+                dest,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                rect.x + 3.0,
+                rect.y + 12.0,
+                escape_xml(&label)
+            )?;
+        }
+
+        if rect.width > 8.0 && rect.height > TREEMAP_LABEL_HEIGHT + 8.0 {
+            let inner = Rect {
+                x: rect.x + 2.0,
+                y: rect.y + TREEMAP_LABEL_HEIGHT,
+                width: rect.width - 4.0,
+                height: rect.height - TREEMAP_LABEL_HEIGHT - 2.0,
+            };
+            for (child, rect) in layout_treemap(&self.children, self.shallow_size, inner) {
+                child.draw(items, rect, &path, depth + 1, hue, dest)?;
+            }
+        }
+
+        writeln!(dest, "</g>")?;
+        Ok(())
+    }
+}
+
+/// Lay out the rectangles of the given namespaces inside `rect`, which stands
+/// for `total` bytes, with areas proportional to the namespaces' shallow sizes.
+/// Namespaces without any bytes are left out.
+#[cfg(feature = "emit_svg")]
+fn layout_treemap(namespaces: &[Namespace], total: u32, rect: Rect) -> Vec<(&Namespace, Rect)> {
+    let mut namespaces: Vec<&Namespace> =
+        namespaces.iter().filter(|ns| ns.shallow_size > 0).collect();
+    if namespaces.is_empty() {
+        return vec![];
+    }
+    namespaces.sort_by(|a, b| {
+        b.shallow_size
+            .cmp(&a.shallow_size)
+            .then(a.name.cmp(&b.name))
+    });
+    let sizes: Vec<u32> = namespaces.iter().map(|ns| ns.shallow_size).collect();
+    namespaces
+        .into_iter()
+        .zip(squarify(&sizes, total, rect))
+        .collect()
+}
+
+/// Lay out rectangles for the given sizes, which are sorted from largest to
+/// smallest, inside `rect`, which stands for `total` bytes, keeping them as
+/// close to square as possible. This is the "squarified" treemap layout of
+/// Bruls, Huizing and van Wijk. If `total` is more than the sum of the sizes,
+/// part of `rect` is left empty.
+#[cfg(feature = "emit_svg")]
+fn squarify(sizes: &[u32], total: u32, mut rect: Rect) -> Vec<Rect> {
+    // The worst ratio of a rectangle's longer side to its shorter side, when
+    // the given areas are laid out in a row along a side of the given length.
+    fn worst_aspect_ratio(row: &[f64], side: f64) -> f64 {
+        let sum: f64 = row.iter().sum();
+        let max = row.iter().cloned().fold(0.0, f64::max);
+        let min = row.iter().cloned().fold(f64::INFINITY, f64::min);
+        f64::max(
+            side * side * max / (sum * sum),
+            sum * sum / (side * side * min),
+        )
+    }
+
+    let scale = rect.width * rect.height / f64::from(total);
+    let areas: Vec<f64> = sizes.iter().map(|&size| f64::from(size) * scale).collect();
+    let mut rects = Vec::with_capacity(areas.len());
+
+    let mut start = 0;
+    while start < areas.len() {
+        let side = rect.width.min(rect.height);
+        if side <= 0.0 {
+            break;
+        }
+
+        // Keep adding areas to the row while that makes its worst aspect
+        // ratio better.
+        let mut end = start + 1;
+        while end < areas.len()
+            && worst_aspect_ratio(&areas[start..end + 1], side)
+                <= worst_aspect_ratio(&areas[start..end], side)
+        {
+            end += 1;
+        }
+
+        let row = &areas[start..end];
+        let row_area: f64 = row.iter().sum();
+        if rect.width >= rect.height {
+            let width = row_area / rect.height;
+            let mut y = rect.y;
+            for &area in row {
+                let height = area / width;
+                rects.push(Rect {
+                    x: rect.x,
+                    y,
+                    width,
+                    height,
+                });
+                y += height;
+            }
+            rect.x += width;
+            rect.width -= width;
+        } else {
+            let height = row_area / rect.width;
+            let mut x = rect.x;
+            for &area in row {
+                let width = area / height;
+                rects.push(Rect {
+                    x,
+                    y: rect.y,
+                    width,
+                    height,
+                });
+                x += width;
+            }
+            rect.y += height;
+            rect.height -= height;
+        }
+        start = end;
+    }
+
+    rects
+}
+
+/// Escape text for use in an SVG document.
+#[cfg(feature = "emit_svg")]
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A namespace that is being built by `namespaces`, in an arena whose first
+/// node is the root of the tree.
+#[derive(Debug, Default)]
+struct NamespaceNode {
+    name: String,
+    parent: usize,
+    children: BTreeMap<String, usize>,
+    items: u32,
+    shallow_size: u32,
+    retained_size: u32,
+}
+
+/// Get the given node, and the nodes of the namespaces that it is in, leaving
+/// out the root.
+fn namespace_chain(nodes: &[NamespaceNode], mut node: usize) -> Vec<usize> {
+    let mut chain = vec![];
+    while node != 0 {
+        chain.push(node);
+        node = nodes[node].parent;
+    }
+    chain
+}
+
+/// Turn the children of the given node into namespaces, largest first, down to
+/// the given depth.
+fn finish_namespaces(
+    nodes: &[NamespaceNode],
+    node: usize,
+    depth: u32,
+    max_depth: u32,
+) -> Vec<Namespace> {
+    if depth > max_depth {
+        return vec![];
+    }
+
+    let mut namespaces: Vec<Namespace> = nodes[node]
+        .children
+        .values()
+        .map(|&child| Namespace {
+            name: nodes[child].name.clone(),
+            items: nodes[child].items,
+            shallow_size: nodes[child].shallow_size,
+            retained_size: nodes[child].retained_size,
+            children: finish_namespaces(nodes, child, depth + 1, max_depth),
+        })
+        .collect();
+    namespaces.sort_by(|a, b| {
+        b.retained_size
+            .cmp(&a.retained_size)
+            .then(b.shallow_size.cmp(&a.shallow_size))
+            .then(a.name.cmp(&b.name))
+    });
+    namespaces
+}
+
+/// Group the items by the namespaces in their names into a tree, so that
+/// `wee_alloc::size_classes::SizeClasses::new` is in `wee_alloc`, then
+/// `size_classes`, then `SizeClasses`. Items that aren't code are grouped by
+/// their kind instead, such as `[data]`.
+///
+/// A namespace's shallow size is the sum of its items' sizes, and its retained
+/// size is the size of everything that its items dominate, together.
+pub fn namespaces(
+    items: &mut ir::Items,
+    opts: &opt::Namespaces,
+) -> Result<Box<traits::Emit>, traits::Error> {
+    items.compute_retained_sizes();
+
+    let mut nodes = vec![NamespaceNode::default()];
+    let mut namespace_of = BTreeMap::new();
+    for item in items.iter().filter(|item| item.id() != items.meta_root()) {
+        let mut path: Vec<Cow<str>> = item
+            .path_segments()
+            .into_iter()
+            .map(Cow::Borrowed)
+            .collect();
+        match *item.kind() {
+            ir::ItemKind::Code(_) => {}
+            ref kind => path.insert(0, Cow::Owned(format!("[{}]", kind.as_str()))),
+        }
+
+        let mut node = 0;
+        for segment in path {
+            node = match nodes[node].children.get(&*segment).cloned() {
+                Some(child) => child,
+                None => {
+                    let child = nodes.len();
+                    nodes[node].children.insert(segment.to_string(), child);
+                    nodes.push(NamespaceNode {
+                        name: segment.into_owned(),
+                        parent: node,
+                        ..Default::default()
+                    });
+                    child
+                }
+            };
+        }
+
+        namespace_of.insert(item.id(), node);
+        for n in namespace_chain(&nodes, node) {
+            nodes[n].items += 1;
+            nodes[n].shallow_size += item.size();
+        }
+    }
+
+    // Walk the dominator tree, counting how many of the items on the way down
+    // from the meta root are in each namespace. An item only adds its retained
+    // size to the namespaces that none of its dominators are in, since those
+    // dominators' retained sizes already include it.
+    let mut dominators_in = vec![0; nodes.len()];
+    let mut reached = BTreeSet::new();
+    let mut stack = vec![(items.meta_root(), false)];
+    while let Some((id, children_done)) = stack.pop() {
+        let chain = namespace_of
+            .get(&id)
+            .map_or(vec![], |&node| namespace_chain(&nodes, node));
+        if children_done {
+            for n in chain {
+                dominators_in[n] -= 1;
+            }
+            continue;
+        }
+
+        reached.insert(id);
+        for n in chain {
+            if dominators_in[n] == 0 {
+                nodes[n].retained_size += items.retained_size(id);
+            }
+            dominators_in[n] += 1;
+        }
+        stack.push((id, true));
+        if let Some(children) = items.dominator_tree().get(&id) {
+            stack.extend(children.iter().map(|&child| (child, false)));
+        }
+    }
+
+    // Items that aren't reachable aren't dominated by anything, and only retain
+    // themselves.
+    for (&id, &node) in &namespace_of {
+        if !reached.contains(&id) {
+            for n in namespace_chain(&nodes, node) {
+                nodes[n].retained_size += items[id].size();
+            }
+        }
+    }
+
+    let namespaces = Namespaces {
+        namespaces: finish_namespaces(&nodes, 0, 1, opts.max_depth()),
+        opts: opts.clone(),
+    };
+    Ok(Box::new(namespaces) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Diff {
+    deltas: Vec<DiffEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DiffEntry {
+    name: String,
+    delta: i64,
+}
+
+impl PartialOrd for DiffEntry {
+    fn partial_cmp(&self, rhs: &DiffEntry) -> Option<cmp::Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl Ord for DiffEntry {
+    fn cmp(&self, rhs: &DiffEntry) -> cmp::Ordering {
+        rhs.delta
+            .abs()
+            .cmp(&self.delta.abs())
+            .then(self.name.cmp(&rhs.name))
+    }
+}
+
+impl traits::Emit for Diff {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(
+        &self,
+        _items: &ir::Items,
+        dest: &mut std::io::Write,
+    ) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Delta Bytes".into()),
+            (Align::Left, "Item".to_string()),
+        ]);
+
+        for entry in &self.deltas {
+            table.add_row(vec![format!("{:+}", entry.delta), entry.name.clone()]);
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(
+        &self,
+        _items: &ir::Items,
+        dest: &mut std::io::Write,
+    ) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+
+        for entry in &self.deltas {
+            let mut obj = arr.object()?;
+            obj.field("delta_bytes", entry.delta as f64)?;
+            obj.field("name", entry.name.as_str())?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, _items: &ir::Items, _dest: &mut io::Write) -> Result<(), traits::Error> {
+        unimplemented!();
+    }
+}
+
+/// Compute the diff between two sets of items.
+pub fn diff(
+    old_items: &mut ir::Items,
+    new_items: &mut ir::Items,
+    opts: &opt::Diff,
+) -> Result<Box<traits::Emit>, traits::Error> {
+    // Items are matched up by their displayed names, so that, for example,
+    // Rust functions whose hashes changed match up when hashes are not shown.
+    let old_mode = old_items.name_mode();
+    let old_items_by_name: BTreeMap<Cow<str>, &ir::Item> = old_items
+        .iter()
+        .map(|item| (item.display_name(old_mode), item))
+        .collect();
+    let new_mode = new_items.name_mode();
+    let new_items_by_name: BTreeMap<Cow<str>, &ir::Item> = new_items
+        .iter()
+        .map(|item| (item.display_name(new_mode), item))
+        .collect();
+
+    let mut deltas = vec![];
+
+    for (name, old_item) in &old_items_by_name {
+        match new_items_by_name.get(name) {
+            None => deltas.push(DiffEntry {
+                name: name.to_string(),
+                delta: -(old_item.size() as i64),
+            }),
+            Some(new_item) => {
+                let delta = new_item.size() as i64 - old_item.size() as i64;
+                if delta != 0 {
+                    deltas.push(DiffEntry {
+                        name: name.to_string(),
+                        delta,
+                    });
+                }
+            }
+        }
+    }
+
+    for (name, new_item) in &new_items_by_name {
+        if !old_items_by_name.contains_key(name) {
+            deltas.push(DiffEntry {
+                name: name.to_string(),
+                delta: new_item.size() as i64,
+            });
+        }
+    }
+
+    deltas.push(DiffEntry {
+        name: "<total>".to_string(),
+        delta: new_items.size() as i64 - old_items.size() as i64,
+    });
+
+    deltas.sort();
+    deltas.truncate(opts.max_items() as usize);
+
+    let diff = Diff { deltas };
+    Ok(Box::new(diff) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Garbage {
+    items: Vec<ir::Id>,
+}
+
+impl traits::Emit for Garbage {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "Size %".to_string()),
+            (Align::Left, "Garbage Item".to_string()),
+        ]);
+
+        for &id in &self.items {
+            let item = &items[id];
+            let size = item.size();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            table.add_row(vec![
+                size.to_string(),
+                format!("{:.2}%", size_percent),
+                items.display_name(id).into_owned(),
+            ]);
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+
+        for &id in &self.items {
+            let item = &items[id];
+
             let mut obj = arr.object()?;
-            obj.field("generic", &entry.generic[..])?;
+            obj.field("name", &*items.display_name(id))?;
 
-            obj.field(
-                "approximate_monomorphization_bloat_bytes",
-                entry.approx_potential_savings,
-            )?;
-            let approx_potential_savings_percent =
-                (f64::from(entry.approx_potential_savings)) / (f64::from(items.size())) * 100.0;
-            obj.field(
-                "approximate_monomorphization_bloat_percent",
-                approx_potential_savings_percent,
-            )?;
+            let size = item.size();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            obj.field("bytes", size)?;
+            obj.field("size_percent", size_percent)?;
+        }
 
-            obj.field("total_size", entry.total)?;
-            let total_percent = (f64::from(entry.total)) / (f64::from(items.size())) * 100.0;
-            obj.field("total_size_percent", total_percent)?;
+        Ok(())
+    }
 
-            let mut monos = obj.array("monomorphizations")?;
-            for &id in &entry.insts {
-                let item = &items[id];
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, _items: &ir::Items, _dest: &mut io::Write) -> Result<(), traits::Error> {
+        unimplemented!();
+    }
+}
 
-                let mut obj = monos.object()?;
-                obj.field("name", item.name())?;
+/// Find items that are not transitively referenced by any exports or public functions.
+pub fn garbage(
+    items: &mut ir::Items,
+    opts: &opt::Garbage,
+) -> Result<Box<traits::Emit>, traits::Error> {
+    ignore_edges(items, opts.ignore_edges())?;
+    select_roots(items, opts.roots(), opts.ignore_roots())?;
 
-                let size = item.size();
-                obj.field("shallow_size", size)?;
+    let reachable_items = items.reachable();
+    let mut unreachable_items: Vec<_> = items
+        .iter()
+        .filter(|item| !reachable_items.contains(&item.id()))
+        .collect();
+
+    unreachable_items.sort_by(|a, b| b.size().cmp(&a.size()));
+    unreachable_items.truncate(opts.max_items() as usize);
+
+    let unreachable_items: Vec<_> = unreachable_items.iter().map(|item| item.id()).collect();
+
+    let garbage_items = Garbage {
+        items: unreachable_items,
+    };
+
+    Ok(Box::new(garbage_items) as Box<traits::Emit>)
+}
+
+#[derive(Debug)]
+struct Whatif {
+    freed: Vec<ir::Id>,
+    freed_bytes: u32,
+    survivors: Vec<(ir::Id, Vec<ir::Id>)>,
+    max_items: usize,
+}
+
+impl traits::Emit for Whatif {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "Size %".to_string()),
+            (Align::Left, "Freed Item".to_string()),
+        ]);
+
+        for &id in self.freed.iter().take(self.max_items) {
+            let size = items[id].size();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            table.add_row(vec![
+                size.to_string(),
+                format!("{:.2}%", size_percent),
+                items.display_name(id).into_owned(),
+            ]);
+        }
+
+        if self.freed.len() > self.max_items {
+            let rest = &self.freed[self.max_items..];
+            let size: u32 = rest.iter().map(|&id| items[id].size()).sum();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            table.add_row(vec![
+                size.to_string(),
+                format!("{:.2}%", size_percent),
+                format!("... and {} more", rest.len()),
+            ]);
+        }
+
+        let size_percent = (f64::from(self.freed_bytes)) / (f64::from(items.size())) * 100.0;
+        table.add_row(vec![
+            self.freed_bytes.to_string(),
+            format!("{:.2}%", size_percent),
+            format!("Total of {} freed items", self.freed.len()),
+        ]);
+
+        write!(dest, "{}", &table)?;
+
+        if !self.survivors.is_empty() {
+            let mut table = Table::with_header(vec![
+                (Align::Right, "Bytes".to_string()),
+                (Align::Left, "Surviving Item".to_string()),
+                (Align::Left, "Still Retained By".to_string()),
+            ]);
+
+            for &(id, ref retainers) in &self.survivors {
+                let retainers: Vec<_> = retainers
+                    .iter()
+                    .map(|&retainer| items.display_name(retainer).into_owned())
+                    .collect();
+                table.add_row(vec![
+                    items[id].size().to_string(),
+                    items.display_name(id).into_owned(),
+                    retainers.join(", "),
+                ]);
+            }
 
+            write!(dest, "\n{}", &table)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut obj = json::object(dest)?;
+
+        let size_percent = (f64::from(self.freed_bytes)) / (f64::from(items.size())) * 100.0;
+        obj.field("freed_bytes", self.freed_bytes)?;
+        obj.field("freed_size_percent", size_percent)?;
+        obj.field("freed_items", self.freed.len() as u32)?;
+
+        {
+            let mut arr = obj.array("freed")?;
+            for &id in self.freed.iter().take(self.max_items) {
+                let mut obj = arr.object()?;
+                obj.field("name", &*items.display_name(id))?;
+
+                let size = items[id].size();
                 let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
-                obj.field("shallow_size_percent", size_percent)?;
+                obj.field("bytes", size)?;
+                obj.field("size_percent", size_percent)?;
+            }
+        }
+
+        {
+            let mut arr = obj.array("survivors")?;
+            for &(id, ref retainers) in &self.survivors {
+                let mut obj = arr.object()?;
+                obj.field("name", &*items.display_name(id))?;
+                obj.field("bytes", items[id].size())?;
+
+                let mut arr = obj.array("retained_by")?;
+                for &retainer in retainers {
+                    arr.elem(&*items.display_name(retainer))?;
+                }
             }
         }
 
@@ -914,136 +3995,225 @@ impl traits::Emit for Monos {
 
     #[cfg(feature = "emit_csv")]
     fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
-        #[derive(Debug, Default, Serialize)]
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
         #[serde(rename_all = "PascalCase")]
-        struct Record {
-            generic: Option<String>,
-            approximate_monomorphization_bloat_bytes: Option<u32>,
-            approximate_monomorphization_bloat_percent: Option<f64>,
-            total_size: Option<u32>,
-            total_size_percent: Option<f64>,
-            monomorphizations: Option<String>,
+        struct CsvRecord {
+            kind: &'static str,
+            name: String,
+            bytes: u32,
+            size_percent: f64,
+            retained_by: Option<String>,
         }
 
-        let mut wtr = csv::Writer::from_writer(dest);
-        let mut rc;
-        for entry in &self.monos {
-            let approx_potential_savings_percent =
-                (f64::from(entry.approx_potential_savings)) / (f64::from(items.size())) * 100.0;
-
-            let total_percent = (f64::from(entry.total)) / (f64::from(items.size())) * 100.0;
-            rc = Record {
-                generic: Some(entry.generic[..].to_string()),
-                approximate_monomorphization_bloat_bytes: Some(entry.approx_potential_savings),
-                approximate_monomorphization_bloat_percent: Some(approx_potential_savings_percent),
-                total_size: Some(entry.total),
-                total_size_percent: Some(total_percent),
-                ..Default::default()
-            };
+        for &id in self.freed.iter().take(self.max_items) {
+            let size = items[id].size();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            wtr.serialize(CsvRecord {
+                kind: "Freed",
+                name: items.display_name(id).into_owned(),
+                bytes: size,
+                size_percent: size_percent,
+                retained_by: None,
+            })?;
+        }
 
-            let monos: Vec<&str> = entry.insts.iter().map(|id| items[*id].name()).collect();
-            rc.monomorphizations = Some(monos.join(", "));
-            wtr.serialize(rc)?;
-            wtr.flush()?;
+        for &(id, ref retainers) in &self.survivors {
+            let size = items[id].size();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            let retainers: Vec<_> = retainers
+                .iter()
+                .map(|&retainer| items.display_name(retainer).into_owned())
+                .collect();
+            wtr.serialize(CsvRecord {
+                kind: "Survivor",
+                name: items.display_name(id).into_owned(),
+                bytes: size,
+                size_percent: size_percent,
+                retained_by: Some(retainers.join(", ")),
+            })?;
         }
+
+        wtr.flush()?;
         Ok(())
     }
 }
 
-/// Find bloaty monomorphizations of generic functions.
-pub fn monos(items: &mut ir::Items, opts: &opt::Monos) -> Result<Box<traits::Emit>, traits::Error> {
-    let mut monos = BTreeMap::new();
-    for item in items.iter() {
-        if let Some(generic) = item.monomorphization_of() {
-            monos
-                .entry(generic)
-                .or_insert(BTreeSet::new())
-                .insert(item.id());
+/// Find the items with the given names, or whose names match the given regular
+/// expressions. It is an error for any of them to match nothing.
+fn items_named(
+    items: &ir::Items,
+    names: &[&str],
+    using_regexps: bool,
+) -> Result<Vec<ir::Id>, traits::Error> {
+    let mut ids = vec![];
+    for &name in names {
+        let matching = if using_regexps {
+            items_matching_regexps(items, &regex::RegexSet::new(&[name])?)
+        } else {
+            item_with_name(items, name)?.into_iter().collect()
+        };
+        if matching.is_empty() {
+            return Err(traits::Error::with_msg(format!(
+                "no items match `{}`",
+                name
+            )));
         }
+        ids.extend(matching);
     }
+    Ok(ids)
+}
 
-    let mut monos: Vec<_> = monos
-        .into_iter()
-        .filter_map(|(generic, insts)| {
-            if insts.len() <= 1 {
-                return None;
-            }
+/// Simulate removing the given items and cutting the given edges, and find
+/// every item that would no longer be reachable as a result. Items that are
+/// referenced by something that goes away, but survive anyway, are reported
+/// along with whatever still retains them.
+pub fn whatif(
+    items: &mut ir::Items,
+    opts: &opt::Whatif,
+) -> Result<Box<traits::Emit>, traits::Error> {
+    ignore_edges(items, opts.ignore_edges())?;
 
-            let max = insts.iter().map(|id| items[*id].size()).max().unwrap();
-            let total = insts.iter().map(|id| items[*id].size()).sum();
-            let size_per_inst = total / (insts.len() as u32);
-            let approx_potential_savings =
-                cmp::min(size_per_inst * (insts.len() as u32 - 1), total - max);
+    let names: Vec<_> = opts.items().iter().map(|name| name.as_str()).collect();
+    let removed: BTreeSet<_> = items_named(items, &names, opts.using_regexps())?
+        .into_iter()
+        .collect();
 
-            let generic = generic.to_string();
+    let mut cut = BTreeSet::new();
+    for (from, to) in opts.cut() {
+        let froms = items_named(items, &[from], opts.using_regexps())?;
+        let tos = items_named(items, &[to], opts.using_regexps())?;
+        let edges: Vec<_> = froms
+            .iter()
+            .flat_map(|&from| tos.iter().map(move |&to| (from, to)))
+            .filter(|&(from, to)| !items.edge_kinds(from, to).is_empty())
+            .collect();
+        if edges.is_empty() {
+            return Err(traits::Error::with_msg(format!(
+                "there are no edges from `{}` to `{}`",
+                from, to
+            )));
+        }
+        cut.extend(edges);
+    }
 
-            let mut insts: Vec<_> = insts.into_iter().collect();
-            insts.sort_by(|a, b| {
-                let a = &items[*a];
-                let b = &items[*b];
-                b.size().cmp(&a.size())
-            });
-            insts.truncate(if opts.only_generics() {
-                0
-            } else {
-                opts.max_monos() as usize
-            });
+    if removed.is_empty() && cut.is_empty() {
+        return Err(traits::Error::with_msg(
+            "nothing to remove: give some items, or some edges to `--cut`",
+        ));
+    }
 
-            Some(MonosEntry {
-                generic,
-                insts,
-                total,
-                approx_potential_savings,
-            })
-        })
+    let before = items.reachable();
+    let mut after_items = items
+        .subgraph()
+        .keep_if(|item| !removed.contains(&item.id()))
+        .without_edges(cut.iter().cloned())
+        .finish();
+    let after = after_items.reachable();
+
+    let mut freed: Vec<_> = before
+        .difference(&after)
+        .chain(removed.iter())
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
         .collect();
+    freed.sort_by(|&a, &b| items[b].size().cmp(&items[a].size()).then(a.cmp(&b)));
+    let freed_bytes = freed.iter().map(|&id| items[id].size()).sum();
 
-    monos.sort();
-    monos.truncate(opts.max_generics() as usize);
+    // The survivors worth explaining are those that lost a retainer, either
+    // because it was freed or because the edge from it was cut.
+    items.compute_predecessors();
+    after_items.compute_predecessors();
+    let lost: BTreeSet<_> = freed.iter().cloned().collect();
+    let mut survivors: Vec<_> = after
+        .iter()
+        .cloned()
+        .filter(|&id| id != items.meta_root())
+        .filter(|&id| {
+            items
+                .predecessors(id)
+                .any(|pred| lost.contains(&pred) || cut.contains(&(pred, id)))
+        })
+        .map(|id| {
+            let retainers = after_items
+                .predecessors(id)
+                .filter(|pred| after.contains(pred))
+                .collect();
+            (id, retainers)
+        })
+        .collect();
+    survivors.sort_by(|&(a, _), &(b, _)| items[b].size().cmp(&items[a].size()).then(a.cmp(&b)));
+    survivors.truncate(opts.max_items() as usize);
+
+    let whatif = Whatif {
+        freed,
+        freed_bytes,
+        survivors,
+        max_items: opts.max_items() as usize,
+    };
 
-    Ok(Box::new(Monos { monos }) as Box<traits::Emit>)
+    Ok(Box::new(whatif) as Box<traits::Emit>)
 }
 
 #[derive(Debug)]
-struct Diff {
-    deltas: Vec<DiffEntry>,
-}
-
-#[derive(Clone, Debug, PartialEq, Eq)]
-struct DiffEntry {
-    name: String,
-    delta: i64,
-}
-
-impl PartialOrd for DiffEntry {
-    fn partial_cmp(&self, rhs: &DiffEntry) -> Option<cmp::Ordering> {
-        Some(self.cmp(rhs))
-    }
+struct Cycles {
+    cycles: Vec<Cycle>,
+    max_members: usize,
 }
 
-impl Ord for DiffEntry {
-    fn cmp(&self, rhs: &DiffEntry) -> cmp::Ordering {
-        rhs.delta
-            .abs()
-            .cmp(&self.delta.abs())
-            .then(self.name.cmp(&rhs.name))
-    }
+#[derive(Debug)]
+struct Cycle {
+    size: u32,
+    members: Vec<(ir::Id, Vec<ir::Id>)>,
 }
 
-impl traits::Emit for Diff {
+impl traits::Emit for Cycles {
     #[cfg(feature = "emit_text")]
-    fn emit_text(
-        &self,
-        _items: &ir::Items,
-        dest: &mut std::io::Write,
-    ) -> Result<(), traits::Error> {
+    fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
         let mut table = Table::with_header(vec![
-            (Align::Right, "Delta Bytes".into()),
-            (Align::Left, "Item".to_string()),
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "Size %".to_string()),
+            (Align::Left, "Cycle".to_string()),
+            (Align::Left, "Entered From".to_string()),
         ]);
 
-        for entry in &self.deltas {
-            table.add_row(vec![format!("{:+}", entry.delta), entry.name.clone()]);
+        for cycle in &self.cycles {
+            let size_percent = (f64::from(cycle.size)) / (f64::from(items.size())) * 100.0;
+            table.add_row(vec![
+                cycle.size.to_string(),
+                format!("{:.2}%", size_percent),
+                format!("cycle of {} items", cycle.members.len()),
+                String::new(),
+            ]);
+
+            for &(id, ref entered_from) in cycle.members.iter().take(self.max_members) {
+                let size = items[id].size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                let entered_from: Vec<_> = entered_from
+                    .iter()
+                    .map(|&from| items.display_name(from).into_owned())
+                    .collect();
+                table.add_row(vec![
+                    size.to_string(),
+                    format!("{:.2}%", size_percent),
+                    format!("    {}", items.display_name(id)),
+                    entered_from.join(", "),
+                ]);
+            }
+
+            if cycle.members.len() > self.max_members {
+                let rest = &cycle.members[self.max_members..];
+                let size: u32 = rest.iter().map(|&(id, _)| items[id].size()).sum();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                table.add_row(vec![
+                    size.to_string(),
+                    format!("{:.2}%", size_percent),
+                    format!("    ... and {} more", rest.len()),
+                    String::new(),
+                ]);
+            }
         }
 
         write!(dest, "{}", &table)?;
@@ -1051,159 +4221,359 @@ impl traits::Emit for Diff {
     }
 
     #[cfg(feature = "emit_json")]
-    fn emit_json(
-        &self,
-        _items: &ir::Items,
-        dest: &mut std::io::Write,
-    ) -> Result<(), traits::Error> {
+    fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
         let mut arr = json::array(dest)?;
 
-        for entry in &self.deltas {
+        for cycle in &self.cycles {
             let mut obj = arr.object()?;
-            obj.field("delta_bytes", entry.delta as f64)?;
-            obj.field("name", entry.name.as_str())?;
+
+            let size_percent = (f64::from(cycle.size)) / (f64::from(items.size())) * 100.0;
+            obj.field("size", cycle.size)?;
+            obj.field("size_percent", size_percent)?;
+            obj.field("member_count", cycle.members.len() as u32)?;
+
+            let mut members = obj.array("members")?;
+            for &(id, ref entered_from) in cycle.members.iter().take(self.max_members) {
+                let mut obj = members.object()?;
+                obj.field("name", &*items.display_name(id))?;
+
+                let size = items[id].size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                obj.field("size", size)?;
+                obj.field("size_percent", size_percent)?;
+
+                let mut arr = obj.array("entered_from")?;
+                for &from in entered_from {
+                    arr.elem(&*items.display_name(from))?;
+                }
+            }
         }
 
         Ok(())
     }
 
     #[cfg(feature = "emit_csv")]
-    fn emit_csv(&self, _items: &ir::Items, _dest: &mut io::Write) -> Result<(), traits::Error> {
-        unimplemented!();
-    }
-}
-
-/// Compute the diff between two sets of items.
-pub fn diff(
-    old_items: &mut ir::Items,
-    new_items: &mut ir::Items,
-    opts: &opt::Diff,
-) -> Result<Box<traits::Emit>, traits::Error> {
-    let old_items_by_name: BTreeMap<&str, &ir::Item> =
-        old_items.iter().map(|item| (item.name(), item)).collect();
-    let new_items_by_name: BTreeMap<&str, &ir::Item> =
-        new_items.iter().map(|item| (item.name(), item)).collect();
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
 
-    let mut deltas = vec![];
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            cycle: u32,
+            cycle_size: u32,
+            name: String,
+            size: u32,
+            size_percent: f64,
+            entered_from: String,
+        }
 
-    for (name, old_item) in &old_items_by_name {
-        match new_items_by_name.get(name) {
-            None => deltas.push(DiffEntry {
-                name: name.to_string(),
-                delta: -(old_item.size() as i64),
-            }),
-            Some(new_item) => {
-                let delta = new_item.size() as i64 - old_item.size() as i64;
-                if delta != 0 {
-                    deltas.push(DiffEntry {
-                        name: name.to_string(),
-                        delta,
-                    });
-                }
+        for (i, cycle) in self.cycles.iter().enumerate() {
+            for &(id, ref entered_from) in cycle.members.iter().take(self.max_members) {
+                let size = items[id].size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                let entered_from: Vec<_> = entered_from
+                    .iter()
+                    .map(|&from| items.display_name(from).into_owned())
+                    .collect();
+                wtr.serialize(CsvRecord {
+                    cycle: i as u32 + 1,
+                    cycle_size: cycle.size,
+                    name: items.display_name(id).into_owned(),
+                    size: size,
+                    size_percent: size_percent,
+                    entered_from: entered_from.join(", "),
+                })?;
             }
         }
-    }
 
-    for (name, new_item) in &new_items_by_name {
-        if !old_items_by_name.contains_key(name) {
-            deltas.push(DiffEntry {
-                name: name.to_string(),
-                delta: new_item.size() as i64,
-            });
-        }
+        wtr.flush()?;
+        Ok(())
     }
+}
 
-    deltas.push(DiffEntry {
-        name: "<total>".to_string(),
-        delta: new_items.size() as i64 - old_items.size() as i64,
+/// Find the strongly connected components that are cycles: those with more
+/// than one item, or a single item with an edge to itself. Each member is
+/// listed with the items outside of the cycle that refer to it, which are the
+/// ways into the cycle.
+pub fn cycles(
+    items: &mut ir::Items,
+    opts: &opt::Cycles,
+) -> Result<Box<traits::Emit>, traits::Error> {
+    ignore_edges(items, opts.ignore_edges())?;
+
+    let components = items.strongly_connected_components();
+    let mut cycles: Vec<_> = components
+        .into_iter()
+        .filter(|component| {
+            component.len() > 1 || !items.edge_kinds(component[0], component[0]).is_empty()
+        })
+        .map(|component| {
+            let in_cycle: BTreeSet<_> = component.iter().cloned().collect();
+            let mut members: Vec<_> = component
+                .into_iter()
+                .map(|id| {
+                    let mut entered_from: Vec<_> = items
+                        .predecessors(id)
+                        .filter(|from| !in_cycle.contains(from))
+                        .collect();
+                    entered_from.sort();
+                    (id, entered_from)
+                })
+                .collect();
+            members
+                .sort_by(|&(a, _), &(b, _)| items[b].size().cmp(&items[a].size()).then(a.cmp(&b)));
+            Cycle {
+                size: members.iter().map(|&(id, _)| items[id].size()).sum(),
+                members,
+            }
+        })
+        .collect();
+
+    cycles.sort_by(|a, b| {
+        b.size
+            .cmp(&a.size)
+            .then(a.members[0].0.cmp(&b.members[0].0))
     });
+    cycles.truncate(opts.max_cycles() as usize);
 
-    deltas.sort();
-    deltas.truncate(opts.max_items() as usize);
+    let cycles = Cycles {
+        cycles,
+        max_members: opts.max_members() as usize,
+    };
 
-    let diff = Diff { deltas };
-    Ok(Box::new(diff) as Box<traits::Emit>)
+    Ok(Box::new(cycles) as Box<traits::Emit>)
 }
 
 #[derive(Debug)]
-struct Garbage {
-    items: Vec<ir::Id>,
+struct Info {
+    sections: Vec<ir::Section>,
+    producers: Vec<ir::Producer>,
+    target_features: Vec<ir::TargetFeature>,
+    // The total size of the code attributed to each source file, largest first.
+    source_files: Vec<(String, u32)>,
 }
 
-impl traits::Emit for Garbage {
+impl traits::Emit for Info {
     #[cfg(feature = "emit_text")]
     fn emit_text(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
         let mut table = Table::with_header(vec![
             (Align::Right, "Bytes".to_string()),
             (Align::Right, "Size %".to_string()),
-            (Align::Left, "Garbage Item".to_string()),
+            (Align::Left, "Section".to_string()),
         ]);
 
-        for &id in &self.items {
-            let item = &items[id];
-            let size = item.size();
+        for section in &self.sections {
+            let size = section.size();
             let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
             table.add_row(vec![
                 size.to_string(),
                 format!("{:.2}%", size_percent),
-                item.name().to_string(),
+                section.name().to_string(),
             ]);
         }
 
         write!(dest, "{}", &table)?;
+
+        if !self.producers.is_empty() {
+            let mut table = Table::with_header(vec![
+                (Align::Left, "Producer Field".to_string()),
+                (Align::Left, "Name".to_string()),
+                (Align::Left, "Version".to_string()),
+            ]);
+
+            for producer in &self.producers {
+                table.add_row(vec![
+                    producer.field().to_string(),
+                    producer.name().to_string(),
+                    producer.version().to_string(),
+                ]);
+            }
+
+            write!(dest, "\n{}", &table)?;
+        }
+
+        if !self.target_features.is_empty() {
+            let mut table = Table::with_header(vec![
+                (Align::Left, "Policy".to_string()),
+                (Align::Left, "Target Feature".to_string()),
+            ]);
+
+            for feature in &self.target_features {
+                table.add_row(vec![
+                    feature.policy().as_str().to_string(),
+                    feature.name().to_string(),
+                ]);
+            }
+
+            write!(dest, "\n{}", &table)?;
+        }
+
+        if !self.source_files.is_empty() {
+            let mut table = Table::with_header(vec![
+                (Align::Right, "Bytes".to_string()),
+                (Align::Right, "Size %".to_string()),
+                (Align::Left, "Source File".to_string()),
+            ]);
+
+            for &(ref file, size) in &self.source_files {
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                table.add_row(vec![
+                    size.to_string(),
+                    format!("{:.2}%", size_percent),
+                    file.clone(),
+                ]);
+            }
+
+            write!(dest, "\n{}", &table)?;
+        }
+
         Ok(())
     }
 
     #[cfg(feature = "emit_json")]
     fn emit_json(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
-        let mut arr = json::array(dest)?;
+        let mut obj = json::object(dest)?;
+        obj.field("size", items.size())?;
 
-        for &id in &self.items {
-            let item = &items[id];
+        {
+            let mut arr = obj.array("sections")?;
+            for section in &self.sections {
+                let mut obj = arr.object()?;
+                obj.field("name", section.name())?;
 
-            let mut obj = arr.object()?;
-            obj.field("name", item.name())?;
+                let size = section.size();
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                obj.field("bytes", size)?;
+                obj.field("size_percent", size_percent)?;
+            }
+        }
 
-            let size = item.size();
-            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
-            obj.field("bytes", size)?;
-            obj.field("size_percent", size_percent)?;
+        {
+            let mut arr = obj.array("producers")?;
+            for producer in &self.producers {
+                let mut obj = arr.object()?;
+                obj.field("field", producer.field())?;
+                obj.field("name", producer.name())?;
+                obj.field("version", producer.version())?;
+            }
+        }
+
+        {
+            let mut arr = obj.array("target_features")?;
+            for feature in &self.target_features {
+                let mut obj = arr.object()?;
+                obj.field("policy", feature.policy().as_str())?;
+                obj.field("name", feature.name())?;
+            }
+        }
+
+        {
+            let mut arr = obj.array("source_files")?;
+            for &(ref file, size) in &self.source_files {
+                let mut obj = arr.object()?;
+                obj.field("name", file.as_str())?;
+
+                let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+                obj.field("bytes", size)?;
+                obj.field("size_percent", size_percent)?;
+            }
         }
 
         Ok(())
     }
 
     #[cfg(feature = "emit_csv")]
-    fn emit_csv(&self, _items: &ir::Items, _dest: &mut io::Write) -> Result<(), traits::Error> {
-        unimplemented!();
-    }
-}
+    fn emit_csv(&self, items: &ir::Items, dest: &mut io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
 
-/// Find items that are not transitively referenced by any exports or public functions.
-pub fn garbage(items: &ir::Items, opts: &opt::Garbage) -> Result<Box<traits::Emit>, traits::Error> {
-    fn get_reachable_items(items: &ir::Items) -> BTreeSet<ir::Id> {
-        let mut reachable_items: BTreeSet<ir::Id> = BTreeSet::new();
-        let mut dfs = petgraph::visit::Dfs::new(items, items.meta_root());
-        while let Some(id) = dfs.next(&items) {
-            reachable_items.insert(id);
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord {
+            kind: &'static str,
+            name: String,
+            bytes: Option<u32>,
+            size_percent: Option<f64>,
+            field: Option<String>,
+            version: Option<String>,
+            policy: Option<&'static str>,
         }
-        reachable_items
-    }
 
-    let reachable_items = get_reachable_items(&items);
-    let mut unreachable_items: Vec<_> = items
-        .iter()
-        .filter(|item| !reachable_items.contains(&item.id()))
-        .collect();
+        for section in &self.sections {
+            let size = section.size();
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            wtr.serialize(CsvRecord {
+                kind: "Section",
+                name: section.name().to_string(),
+                bytes: Some(size),
+                size_percent: Some(size_percent),
+                field: None,
+                version: None,
+                policy: None,
+            })?;
+        }
 
-    unreachable_items.sort_by(|a, b| b.size().cmp(&a.size()));
-    unreachable_items.truncate(opts.max_items() as usize);
+        for producer in &self.producers {
+            wtr.serialize(CsvRecord {
+                kind: "Producer",
+                name: producer.name().to_string(),
+                bytes: None,
+                size_percent: None,
+                field: Some(producer.field().to_string()),
+                version: Some(producer.version().to_string()),
+                policy: None,
+            })?;
+        }
 
-    let unreachable_items: Vec<_> = unreachable_items.iter().map(|item| item.id()).collect();
+        for feature in &self.target_features {
+            wtr.serialize(CsvRecord {
+                kind: "TargetFeature",
+                name: feature.name().to_string(),
+                bytes: None,
+                size_percent: None,
+                field: None,
+                version: None,
+                policy: Some(feature.policy().as_str()),
+            })?;
+        }
 
-    let garbage_items = Garbage {
-        items: unreachable_items,
+        for &(ref file, size) in &self.source_files {
+            let size_percent = (f64::from(size)) / (f64::from(items.size())) * 100.0;
+            wtr.serialize(CsvRecord {
+                kind: "SourceFile",
+                name: file.clone(),
+                bytes: Some(size),
+                size_percent: Some(size_percent),
+                field: None,
+                version: None,
+                policy: None,
+            })?;
+        }
+
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Summarize a binary's sections, the toolchain and target features used to
+/// produce it, and how much of its code came from each source file.
+pub fn info(items: &ir::Items, _opts: &opt::Info) -> Result<Box<traits::Emit>, traits::Error> {
+    let mut source_files = BTreeMap::new();
+    for item in items.iter() {
+        if let Some(location) = item.location() {
+            *source_files.entry(location.file().to_string()).or_insert(0) += item.size();
+        }
+    }
+    let mut source_files: Vec<_> = source_files.into_iter().collect();
+    source_files.sort_by(|&(ref a_file, a_size), &(ref b_file, b_size)| {
+        b_size.cmp(&a_size).then_with(|| a_file.cmp(b_file))
+    });
+
+    let info = Info {
+        sections: items.sections().to_vec(),
+        producers: items.producers().to_vec(),
+        target_features: items.target_features().to_vec(),
+        source_files,
     };
 
-    Ok(Box::new(garbage_items) as Box<traits::Emit>)
+    Ok(Box::new(info) as Box<traits::Emit>)
 }
@@ -0,0 +1,93 @@
+//! Benchmarks the dominator and retained size computations on a synthetic graph
+//! with a million items.
+//!
+//! Run with `cargo bench -p twiggy-ir`. This doesn't use the unstable `test`
+//! crate, so that it builds on stable Rust.
+
+extern crate twiggy_ir as ir;
+
+use std::time::{Duration, Instant};
+
+const NUM_ITEMS: usize = 1_000_000;
+
+// The first items form a single long chain, which makes an equally deep
+// dominator tree.
+const CHAIN_LENGTH: usize = 100_000;
+
+/// A small, deterministic pseudo-random number generator, so that every run
+/// builds the same graph.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) as usize
+    }
+}
+
+fn build_items() -> ir::Items {
+    let mut items = ir::ItemsBuilder::new(0);
+    let id = |i: usize| ir::Id::entry(0, i);
+
+    for i in 0..NUM_ITEMS {
+        let item = ir::Item::new(
+            id(i),
+            format!("item[{}]", i),
+            (i % 100) as u32 + 1,
+            ir::Misc::new(),
+        );
+        if i == 0 || i == CHAIN_LENGTH {
+            items.add_root(item);
+        } else {
+            items.add_item(item);
+        }
+    }
+
+    for i in 0..CHAIN_LENGTH - 1 {
+        items.add_edge(id(i), id(i + 1), ir::EdgeKind::Call);
+    }
+
+    // The rest of the items form a DAG where every item calls up to three
+    // later items, with some calls back into the chain.
+    let mut rng = Lcg(0x7769_6767_79);
+    for i in CHAIN_LENGTH..NUM_ITEMS - 1 {
+        for _ in 0..rng.next() % 3 + 1 {
+            let remaining = NUM_ITEMS - i - 1;
+            let to = i + 1 + rng.next() % remaining.min(1000);
+            items.add_edge(id(i), id(to), ir::EdgeKind::Call);
+        }
+        if rng.next() % 100 == 0 {
+            let to = rng.next() % CHAIN_LENGTH;
+            items.add_edge(id(i), id(to), ir::EdgeKind::Reference);
+        }
+    }
+
+    items.finish()
+}
+
+fn time<T, F: FnOnce() -> T>(what: &str, f: F) -> T {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    println!("{:<24} {:>10.3} ms", what, millis(elapsed));
+    result
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs() as f64 * 1000.0 + f64::from(d.subsec_nanos()) / 1_000_000.0
+}
+
+fn main() {
+    let mut items = time("build", build_items);
+    time("compute_dominators", || items.compute_dominators());
+    time("compute_retained_sizes", || items.compute_retained_sizes());
+
+    let meta_root = items.meta_root();
+    println!(
+        "meta root retained size: {}",
+        items.retained_size(meta_root)
+    );
+}
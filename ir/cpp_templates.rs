@@ -0,0 +1,114 @@
+//! Finding the template behind a mangled C++ symbol.
+//!
+//! Rather than searching the demangled text for `<` and `>`, which trips over
+//! names like `operator<`, this follows the structure that `cpp_demangle`
+//! reports while it demangles the symbol.
+
+use cpp_demangle::{DemangleNodeType, DemangleOptions, DemangleWrite, Symbol};
+use std::fmt;
+
+/// Split a mangled C++ symbol into the name of the template it instantiates
+/// and its template arguments. Returns `None` if the symbol isn't a template
+/// instantiation.
+///
+/// For example, `_ZN5TableIidE6insertERKiRKd`, which demangles to
+/// `Table<int, double>::insert(int const&, double const&)`, is split into
+/// `Table::insert` and `["int", "double"]`. The arguments of class templates
+/// that the function is a member of are included.
+pub(crate) fn split_template_arguments<T>(symbol: &Symbol<T>) -> Option<(String, Vec<String>)>
+where
+    T: AsRef<[u8]>,
+{
+    // The return type and parameters can contain template arguments of their
+    // own, which say nothing about this instantiation.
+    let options = DemangleOptions::new().no_params().no_return_type();
+    let mut splitter = TemplateSplitter::default();
+    symbol.structured_demangle(&mut splitter, &options).ok()?;
+
+    if splitter.arguments.is_empty() {
+        None
+    } else {
+        Some((splitter.template.trim().to_string(), splitter.arguments))
+    }
+}
+
+#[derive(Default)]
+struct TemplateSplitter {
+    // The demangled name, without the outermost template argument lists.
+    template: String,
+    arguments: Vec<String>,
+    nodes: Vec<DemangleNodeType>,
+    // The depth in `nodes` of the outermost template argument list that is
+    // being written, if any.
+    arguments_depth: Option<usize>,
+    argument: String,
+    // How deeply nested in parentheses the argument being written is, so that
+    // the commas between function type parameters are not taken for commas
+    // between template arguments.
+    parens: usize,
+    // The `>` that closes the outermost template argument list is written
+    // right after it is popped, and should not be part of the template name.
+    closing: bool,
+}
+
+impl TemplateSplitter {
+    fn finish_argument(&mut self) {
+        let argument = self.argument.trim();
+        if !argument.is_empty() {
+            self.arguments.push(argument.to_string());
+        }
+        self.argument.clear();
+    }
+}
+
+impl DemangleWrite for TemplateSplitter {
+    fn push_demangle_node(&mut self, node: DemangleNodeType) {
+        if node == DemangleNodeType::TemplateArgs && self.arguments_depth.is_none() {
+            // The `<` opening the list has already been written, along with a
+            // space if the name before it ends in `<`, as `operator<` does.
+            self.template.pop();
+            let len = self.template.trim_end().len();
+            self.template.truncate(len);
+            self.arguments_depth = Some(self.nodes.len());
+            self.parens = 0;
+        }
+        self.nodes.push(node);
+    }
+
+    fn pop_demangle_node(&mut self) {
+        self.nodes.pop();
+        if self.arguments_depth == Some(self.nodes.len()) {
+            self.finish_argument();
+            self.arguments_depth = None;
+            self.closing = true;
+        }
+    }
+
+    fn write_string(&mut self, s: &str) -> fmt::Result {
+        let s = if self.closing && s.starts_with('>') {
+            self.closing = false;
+            &s[1..]
+        } else {
+            s
+        };
+
+        match self.arguments_depth {
+            None => self.template.push_str(s),
+            Some(depth) => {
+                if s == ", " && self.nodes.len() == depth + 1 && self.parens == 0 {
+                    self.finish_argument();
+                } else {
+                    for c in s.chars() {
+                        match c {
+                            '(' => self.parens += 1,
+                            ')' => self.parens = self.parens.saturating_sub(1),
+                            _ => {}
+                        }
+                    }
+                    self.argument.push_str(s);
+                }
+            }
+        }
+        Ok(())
+    }
+}
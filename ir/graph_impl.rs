@@ -1,6 +1,9 @@
-use super::{Id, Items, Neighbors};
+use super::{Id, Item, Items, Neighbors};
 use petgraph::visit;
+use petgraph::Direction;
+use std::collections::btree_map;
 use std::collections::HashSet;
+use std::iter;
 
 impl visit::GraphBase for Items {
     type EdgeId = ();
@@ -30,6 +33,33 @@ impl<'a> visit::IntoNeighbors for &'a Items {
     }
 }
 
+impl<'a> visit::IntoNeighborsDirected for &'a Items {
+    type NeighborsDirected = Neighbors<'a>;
+
+    #[inline]
+    fn neighbors_directed(self, id: Id, direction: Direction) -> Self::NeighborsDirected {
+        match direction {
+            Direction::Outgoing => self.neighbors(id),
+            Direction::Incoming => {
+                let predecessors = self.predecessors(id);
+                Neighbors {
+                    inner: predecessors.inner,
+                    followed: predecessors.followed,
+                }
+            }
+        }
+    }
+}
+
+impl<'a> visit::IntoNodeIdentifiers for &'a Items {
+    type NodeIdentifiers = iter::Cloned<btree_map::Keys<'a, Id, Item>>;
+
+    #[inline]
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        self.items.keys().cloned()
+    }
+}
+
 impl visit::NodeCount for Items {
     #[inline]
     fn node_count(&self) -> usize {
@@ -7,15 +7,30 @@ extern crate cpp_demangle;
 extern crate frozen;
 extern crate petgraph;
 extern crate rustc_demangle;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 
+mod cpp_templates;
 mod graph_impl;
+mod rust_v0;
+mod snapshot;
+mod subgraph;
+
+pub use snapshot::{SNAPSHOT_FORMAT, SNAPSHOT_VERSION};
+pub use subgraph::Subgraph;
 
 use frozen::Frozen;
+use std::borrow::Cow;
 use std::cmp;
 use std::collections::btree_map;
 use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::iter;
 use std::ops;
 use std::slice;
+use std::str::FromStr;
 use std::u32;
 
 /// Build up a a set of `Items`.
@@ -24,12 +39,26 @@ pub struct ItemsBuilder {
     size: u32,
     parsed: BTreeSet<Id>,
     items: BTreeMap<Id, Item>,
-    edges: BTreeMap<Id, BTreeSet<Id>>,
+    edges: BTreeMap<Id, BTreeMap<Id, EdgeKinds>>,
     roots: BTreeSet<Id>,
 
     // Maps the offset some data begins at to its IR item's identifier, and the
     // byte length of the data.
     data: BTreeMap<u32, (Id, u32)>,
+
+    strings: Vec<StringLiteral>,
+    // Maps the address a string literal begins at to its index in `strings`.
+    string_addresses: BTreeMap<u32, usize>,
+
+    sections: Vec<Section>,
+    producers: Vec<Producer>,
+    target_features: Vec<TargetFeature>,
+    warnings: Vec<String>,
+
+    // The module and field names of imported items, and the names of exported
+    // ones, used to link modules together.
+    imports: Vec<(Id, String, String)>,
+    exports: Vec<(Id, String)>,
 }
 
 impl ItemsBuilder {
@@ -42,6 +71,14 @@ impl ItemsBuilder {
             edges: Default::default(),
             roots: Default::default(),
             data: Default::default(),
+            strings: Default::default(),
+            string_addresses: Default::default(),
+            sections: Default::default(),
+            producers: Default::default(),
+            target_features: Default::default(),
+            warnings: Default::default(),
+            imports: Default::default(),
+            exports: Default::default(),
         }
     }
 
@@ -68,13 +105,34 @@ impl ItemsBuilder {
         id
     }
 
-    /// Add an edge between the given keys that have already been parsed into
-    /// items.
-    pub fn add_edge(&mut self, from: Id, to: Id) {
+    /// Add an edge of the given kind between the given keys that have already
+    /// been parsed into items. Adding more than one kind of edge between the
+    /// same items records all of their kinds on a single edge.
+    pub fn add_edge(&mut self, from: Id, to: Id, kind: EdgeKind) {
         debug_assert!(self.items.contains_key(&from), "`from` is not known");
         debug_assert!(self.items.contains_key(&to), "`to` is not known");
 
-        self.edges.entry(from).or_insert(BTreeSet::new()).insert(to);
+        self.edges
+            .entry(from)
+            .or_insert(BTreeMap::new())
+            .entry(to)
+            .or_insert(EdgeKinds::empty())
+            .insert(kind);
+    }
+
+    /// Record that the given item is imported from the module with the given
+    /// name, under the given field name.
+    pub fn add_import<M, N>(&mut self, id: Id, module: M, name: N)
+    where
+        M: Into<String>,
+        N: Into<String>,
+    {
+        self.imports.push((id, module.into(), name.into()));
+    }
+
+    /// Record that the given item is exported under the given name.
+    pub fn add_export<S: Into<String>>(&mut self, id: Id, name: S) {
+        self.exports.push((id, name.into()));
     }
 
     /// Add a range of static data and the `Id` that defines it.
@@ -101,12 +159,198 @@ impl ItemsBuilder {
             )
     }
 
+    /// Record a string literal found in the binary's static data.
+    pub fn add_string(&mut self, string: StringLiteral) {
+        if let Some(address) = string.address {
+            self.string_addresses.insert(address, self.strings.len());
+        }
+        self.strings.push(string);
+    }
+
+    /// Record that the given item refers to the string literal at the given
+    /// address, which may be partway through the string, if there is one.
+    pub fn reference_string(&mut self, from: Id, address: u32) {
+        let found = self
+            .string_addresses
+            .range(..=address)
+            .next_back()
+            .map(|(&start, &i)| (start, i));
+        if let Some((start, i)) = found {
+            let string = &mut self.strings[i];
+            let offset = address - start;
+            if (offset as usize) < string.value.len() {
+                string.add_reference(offset, from);
+            }
+        }
+    }
+
+    /// Record one of the binary's top-level sections.
+    pub fn add_section(&mut self, section: Section) {
+        self.sections.push(section);
+    }
+
+    /// Record a tool or language that was used to produce the binary.
+    pub fn add_producer(&mut self, producer: Producer) {
+        self.producers.push(producer);
+    }
+
+    /// Record a target feature that the binary was compiled with.
+    pub fn add_target_feature(&mut self, feature: TargetFeature) {
+        self.target_features.push(feature);
+    }
+
+    /// Record a problem encountered while parsing, such as part of the binary
+    /// that could not be decoded.
+    pub fn add_warning<S: Into<String>>(&mut self, warning: S) {
+        self.warnings.push(warning.into());
+    }
+
+    /// Link several modules, such as a wasm module and the modules it loads
+    /// lazily, into a single graph. Each module is given as its name and the
+    /// builder its items were parsed into.
+    ///
+    /// Every module gets a `module "<name>"` scope item, which is a root and
+    /// has edges to the module's own roots and imports. Each import gets an
+    /// `Import` edge to the exports of the same name in the module it names,
+    /// such as `lazy.wasm` for an import from `./lazy.js`. Imports from modules
+    /// that aren't given, such as `env`, aren't linked to anything, even if
+    /// another module exports the same name. The first module is the entry
+    /// point, and its exports stay roots. The exports of the other modules are
+    /// only reachable through the imports that resolve to them, so the ones
+    /// that nothing imports are garbage.
+    pub fn link<S: Into<String>>(modules: Vec<(S, ItemsBuilder)>) -> ItemsBuilder {
+        let mut linked = ItemsBuilder::new(0);
+        let mut names = vec![];
+        let mut imports = vec![];
+        // Every exported name, and the modules and items that export it.
+        let mut exports = BTreeMap::new();
+
+        // Each module's items are moved to their own range of sections, after
+        // the section of the module's scope item.
+        let mut next_section = 0;
+
+        for (i, (name, module)) in modules.into_iter().enumerate() {
+            let name = name.into();
+            let scope = Id(next_section, u32::MAX);
+            let offset = next_section + 1;
+            next_section = offset + module.items.keys().map(|id| id.0 + 1).max().unwrap_or(0);
+            let relocate = |id: Id| Id(id.0 + offset, id.1);
+
+            linked.size += module.size;
+            linked.add_root(Item::new(
+                scope,
+                format!("module \"{}\"", name),
+                0,
+                Scope::new(),
+            ));
+
+            for (id, mut item) in module.items {
+                item.id = relocate(id);
+                linked.add_item(item);
+            }
+            for (from, tos) in module.edges {
+                let edges = linked
+                    .edges
+                    .entry(relocate(from))
+                    .or_insert_with(BTreeMap::new);
+                edges.extend(tos.into_iter().map(|(to, kinds)| (relocate(to), kinds)));
+            }
+
+            let exported: BTreeSet<Id> = module.exports.iter().map(|&(id, _)| id).collect();
+            for root in module.roots {
+                if i == 0 || !exported.contains(&root) {
+                    linked.add_edge(scope, relocate(root), EdgeKind::Root);
+                }
+            }
+            for (id, export) in module.exports {
+                exports
+                    .entry(export)
+                    .or_insert_with(Vec::new)
+                    .push((i, relocate(id)));
+            }
+            for (id, from, field) in module.imports {
+                let id = relocate(id);
+                linked.add_edge(scope, id, EdgeKind::Reference);
+                imports.push((i, id, from, field));
+            }
+
+            for mut string in module.strings {
+                string.data = relocate(string.data);
+                for reference in &mut string.references {
+                    reference.1 = relocate(reference.1);
+                }
+                linked.add_string(string);
+            }
+            for section in module.sections {
+                let section_name = format!("{}: {}", name, section.name);
+                linked.add_section(Section::new(section_name, section.size));
+            }
+            for producer in module.producers {
+                if !linked.producers.contains(&producer) {
+                    linked.add_producer(producer);
+                }
+            }
+            for feature in module.target_features {
+                if !linked.target_features.contains(&feature) {
+                    linked.add_target_feature(feature);
+                }
+            }
+            for warning in module.warnings {
+                linked.add_warning(format!("{}: {}", name, warning));
+            }
+
+            names.push(name);
+        }
+
+        for (i, import, from, field) in imports {
+            let targets = exports
+                .get(&field)
+                .map_or(&[][..], |ids: &Vec<_>| ids.as_slice())
+                .iter()
+                .filter(|&&(j, _)| j != i && module_stem(&names[j]) == module_stem(&from));
+            for &(_, export) in targets {
+                linked.add_edge(import, export, EdgeKind::Import);
+            }
+        }
+
+        linked
+    }
+
     /// Finish building the IR graph and return the resulting `Items`.
     pub fn finish(mut self) -> Items {
         let meta_root_id = Id::root();
+        let mut names = BTreeMap::new();
+        let mut aliases = BTreeMap::new();
+        for item in self.items.values() {
+            let name = item.name();
+            names
+                .entry(name.to_string())
+                .or_insert_with(Vec::new)
+                .push(item.id);
+
+            let mut add_alias = |alias: &str| {
+                if alias != name {
+                    let ids = aliases.entry(alias.to_string()).or_insert_with(Vec::new);
+                    if ids.last() != Some(&item.id) {
+                        ids.push(item.id);
+                    }
+                }
+            };
+            add_alias(&item.name);
+            if let Some(stripped) = strip_hash(name) {
+                add_alias(stripped);
+            }
+        }
+
         let meta_root = Item::new(meta_root_id, "<meta root>", 0, Misc::new());
         self.items.insert(meta_root_id, meta_root);
-        self.edges.insert(meta_root_id, self.roots.clone());
+        self.edges.insert(
+            meta_root_id,
+            self.roots
+                .iter()
+                .map(|&root| (root, EdgeKind::Root.into()))
+                .collect(),
+        );
 
         Items {
             size: self.size,
@@ -114,6 +358,8 @@ impl ItemsBuilder {
             retained_sizes: None,
             predecessors: None,
             immediate_dominators: None,
+            followed_edge_kinds: EdgeKinds::all(),
+            name_mode: NameMode::default(),
             items: Frozen::freeze(self.items),
             edges: Frozen::freeze(
                 self.edges
@@ -122,7 +368,14 @@ impl ItemsBuilder {
                     .collect(),
             ),
             roots: Frozen::freeze(self.roots),
+            names: Frozen::freeze(names),
+            aliases: Frozen::freeze(aliases),
             meta_root: meta_root_id,
+            strings: self.strings,
+            sections: self.sections,
+            producers: self.producers,
+            target_features: self.target_features,
+            warnings: self.warnings,
         }
     }
 }
@@ -137,11 +390,23 @@ pub struct Items {
     dominator_tree: Option<BTreeMap<Id, Vec<Id>>>,
     immediate_dominators: Option<BTreeMap<Id, Id>>,
     retained_sizes: Option<BTreeMap<Id, u32>>,
-    predecessors: Option<BTreeMap<Id, Vec<Id>>>,
+    predecessors: Option<BTreeMap<Id, Vec<(Id, EdgeKinds)>>>,
+    followed_edge_kinds: EdgeKinds,
+    name_mode: NameMode,
     items: Frozen<BTreeMap<Id, Item>>,
-    edges: Frozen<BTreeMap<Id, Vec<Id>>>,
+    edges: Frozen<BTreeMap<Id, Vec<(Id, EdgeKinds)>>>,
     roots: Frozen<BTreeSet<Id>>,
+    // Every item's name, and the items that have it, sorted by id.
+    names: Frozen<BTreeMap<String, Vec<Id>>>,
+    // Other names that items can be looked up by: their raw symbol names, and
+    // their names without the trailing hash that Rust symbols have.
+    aliases: Frozen<BTreeMap<String, Vec<Id>>>,
     meta_root: Id,
+    strings: Vec<StringLiteral>,
+    sections: Vec<Section>,
+    producers: Vec<Producer>,
+    target_features: Vec<TargetFeature>,
+    warnings: Vec<String>,
 }
 
 impl ops::Index<Id> for Items {
@@ -160,17 +425,20 @@ impl Items {
         }
     }
 
-    /// Iterate over an item's neighbors.
+    /// Iterate over an item's neighbors, following only the edges whose kinds
+    /// are in `followed_edge_kinds`.
     pub fn neighbors(&self, id: Id) -> Neighbors {
         Neighbors {
             inner: self
                 .edges
                 .get(&id)
                 .map_or_else(|| [].iter(), |edges| edges.iter()),
+            followed: self.followed_edge_kinds,
         }
     }
 
-    /// Iterate over an item's predecessors.
+    /// Iterate over an item's predecessors, following only the edges whose
+    /// kinds are in `followed_edge_kinds`.
     pub fn predecessors(&self, id: Id) -> Predecessors {
         Predecessors {
             inner: self
@@ -179,7 +447,64 @@ impl Items {
                 .expect("To access predecessors, must have already called compute_predecessors")
                 .get(&id)
                 .map_or_else(|| [].iter(), |edges| edges.iter()),
+            followed: self.followed_edge_kinds,
+        }
+    }
+
+    /// Get the kinds of the edge from one item to another, out of those that
+    /// are followed. This is empty if there is no such edge.
+    pub fn edge_kinds(&self, from: Id, to: Id) -> EdgeKinds {
+        self.edges
+            .get(&from)
+            .and_then(|edges| {
+                edges
+                    .binary_search_by_key(&to, |&(id, _)| id)
+                    .ok()
+                    .map(|i| EdgeKinds(edges[i].1 .0 & self.followed_edge_kinds.0))
+            })
+            .unwrap_or(EdgeKinds::empty())
+    }
+
+    /// The kinds of edges that `neighbors`, `predecessors`, and the dominator
+    /// and retained size computations follow. Defaults to all of them.
+    pub fn followed_edge_kinds(&self) -> EdgeKinds {
+        self.followed_edge_kinds
+    }
+
+    /// Only follow edges of the given kinds from now on. For example, ignoring
+    /// type references makes functions that share a signature independent of
+    /// each other in the dominator tree. An edge with several kinds is followed
+    /// if any of them is. The meta root's edges are always followed.
+    ///
+    /// This throws away any dominators and retained sizes that were already
+    /// computed, since they depend on which edges are followed.
+    pub fn follow_edge_kinds(&mut self, mut kinds: EdgeKinds) {
+        kinds.insert(EdgeKind::Root);
+        if kinds == self.followed_edge_kinds {
+            return;
         }
+
+        self.followed_edge_kinds = kinds;
+        self.dominator_tree = None;
+        self.immediate_dominators = None;
+        self.retained_sizes = None;
+    }
+
+    /// How `display_name` displays item names. Defaults to
+    /// `NameMode::Demangled`.
+    pub fn name_mode(&self) -> NameMode {
+        self.name_mode
+    }
+
+    /// Display item names in the given mode from now on.
+    pub fn set_name_mode(&mut self, mode: NameMode) {
+        self.name_mode = mode;
+    }
+
+    /// Get the name of the item with the given id, displayed in the current
+    /// `name_mode`.
+    pub fn display_name(&self, id: Id) -> Cow<str> {
+        self.items[&id].display_name(self.name_mode)
     }
 
     /// The size of the total binary, containing all items.
@@ -193,6 +518,37 @@ impl Items {
         self.meta_root
     }
 
+    /// Get the real roots, which the meta root has edges to.
+    pub fn roots(&self) -> &BTreeSet<Id> {
+        &self.roots
+    }
+
+    /// The binary's top-level sections, in the order they appear.
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// The string literals found in the binary's static data.
+    pub fn strings(&self) -> &[StringLiteral] {
+        &self.strings
+    }
+
+    /// The tools and languages that were used to produce the binary, if known.
+    pub fn producers(&self) -> &[Producer] {
+        &self.producers
+    }
+
+    /// The target features that the binary was compiled with, if known.
+    pub fn target_features(&self) -> &[TargetFeature] {
+        &self.target_features
+    }
+
+    /// Problems encountered while parsing the binary. If there are any, the
+    /// items may be incomplete.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     /// Force computation of predecessors.
     pub fn compute_predecessors(&mut self) {
         if self.predecessors.is_some() {
@@ -202,11 +558,11 @@ impl Items {
         let mut predecessors = BTreeMap::new();
 
         for (from, tos) in self.edges.iter() {
-            for to in tos {
+            for &(to, kinds) in tos {
                 predecessors
-                    .entry(*to)
-                    .or_insert_with(|| BTreeSet::new())
-                    .insert(*from);
+                    .entry(to)
+                    .or_insert_with(|| BTreeMap::new())
+                    .insert(*from, kinds);
             }
         }
 
@@ -218,22 +574,38 @@ impl Items {
         );
     }
 
-    /// Compute dominators for each item.
+    /// Find the strongly connected components of the graph, following only the
+    /// edges whose kinds are in `followed_edge_kinds`. The items in each
+    /// component can all reach each other, such as a group of mutually
+    /// recursive functions. Most items are in a component of their own.
+    pub fn strongly_connected_components(&mut self) -> Vec<Vec<Id>> {
+        self.compute_predecessors();
+        petgraph::algo::kosaraju_scc(&*self)
+    }
+
+    /// Compute dominators for each item, along with the dominator tree.
     pub fn compute_dominators(&mut self) {
         if self.immediate_dominators.is_some() {
             return;
         }
 
         let mut immediate_dominators = BTreeMap::new();
-        let dominators = petgraph::algo::dominators::simple_fast(&*self, self.meta_root);
-
-        for item in self.iter() {
-            if let Some(idom) = dominators.immediate_dominator(item.id()) {
-                immediate_dominators.insert(item.id(), idom);
+        let mut dominator_tree = BTreeMap::new();
+        {
+            let dominators = petgraph::algo::dominators::simple_fast(&*self, self.meta_root);
+            for item in self.iter() {
+                if let Some(idom) = dominators.immediate_dominator(item.id()) {
+                    immediate_dominators.insert(item.id(), idom);
+                    dominator_tree
+                        .entry(idom)
+                        .or_insert_with(Vec::new)
+                        .push(item.id());
+                }
             }
         }
 
         self.immediate_dominators = Some(immediate_dominators);
+        self.dominator_tree = Some(dominator_tree);
     }
 
     /// Get a refercence to immediate dominators
@@ -243,29 +615,10 @@ impl Items {
             .expect("must call compute_immediate_dominators before calling immediate_dominators")
     }
 
-    /// Force computation of the dominator tree.
+    /// Force computation of the dominator tree. This is computed along with
+    /// the immediate dominators, so it is the same as `compute_dominators`.
     pub fn compute_dominator_tree(&mut self) {
-        if self.dominator_tree.is_some() {
-            return;
-        }
-
-        let mut dominator_tree = BTreeMap::new();
-        let dominators = petgraph::algo::dominators::simple_fast(&*self, self.meta_root);
-        for item in self.iter() {
-            if let Some(idom) = dominators.immediate_dominator(item.id()) {
-                dominator_tree
-                    .entry(idom)
-                    .or_insert(BTreeSet::new())
-                    .insert(item.id());
-            }
-        }
-
-        self.dominator_tree = Some(
-            dominator_tree
-                .into_iter()
-                .map(|(k, v)| (k, v.into_iter().collect()))
-                .collect(),
-        );
+        self.compute_dominators();
     }
 
     /// Get a reference to the dominator tree.
@@ -284,44 +637,32 @@ impl Items {
         }
         self.compute_dominator_tree();
 
-        fn recursive_retained_size(
-            retained_sizes: &mut BTreeMap<Id, u32>,
-            items: &Items,
-            item: &Item,
-            dominator_tree: &BTreeMap<Id, Vec<Id>>,
-        ) -> u32 {
-            // Although the dominator tree cannot have cycles, because we
-            // compute retained sizes in item iteration order, rather than from
-            // the bottom of the dominator tree up, it is possible we have
-            // already computed the retained sizes for subtrees.
-            if let Some(rsize) = retained_sizes.get(&item.id()) {
-                return *rsize;
-            }
+        let mut retained_sizes = BTreeMap::new();
+        {
+            let dominator_tree = self.dominator_tree.as_ref().unwrap();
 
-            let mut rsize = item.size();
-            if let Some(children) = dominator_tree.get(&item.id()) {
-                for child in children {
-                    rsize += recursive_retained_size(
-                        retained_sizes,
-                        items,
-                        &items[*child],
-                        dominator_tree,
-                    );
+            // Walk the dominator tree in post-order, so that every item's
+            // children have their retained sizes computed before it. This uses
+            // an explicit stack rather than recursion, since dominator chains
+            // can be very long.
+            let mut stack = vec![(self.meta_root, false)];
+            while let Some((id, children_done)) = stack.pop() {
+                let children = dominator_tree.get(&id).map_or(&[][..], |c| c.as_slice());
+                if children_done {
+                    let rsize = children.iter().fold(self[id].size(), |rsize, child| {
+                        rsize + retained_sizes[child]
+                    });
+                    retained_sizes.insert(id, rsize);
+                } else {
+                    stack.push((id, true));
+                    stack.extend(children.iter().map(|&child| (child, false)));
                 }
             }
 
-            let old_value = retained_sizes.insert(item.id(), rsize);
-            // The dominator tree is a proper tree, so there shouldn't be
-            // any cycles.
-            assert!(old_value.is_none());
-            rsize
-        }
-
-        let mut retained_sizes = BTreeMap::new();
-        {
-            let dominator_tree = self.dominator_tree.as_ref().unwrap();
+            // Items that are not reachable from the meta root aren't in the
+            // dominator tree, and only retain themselves.
             for item in self.iter() {
-                recursive_retained_size(&mut retained_sizes, self, item, dominator_tree);
+                retained_sizes.entry(item.id()).or_insert(item.size());
             }
         }
         self.retained_sizes = Some(retained_sizes);
@@ -340,22 +681,44 @@ impl Items {
             .unwrap()
     }
 
-    /// Get an item with the given name.
-    pub fn get_item_by_name(&self, name: &str) -> Option<&Item> {
-        for item in self.iter() {
-            if item.name() == name {
-                return Some(item);
-            }
+    /// Get the items with the given name, sorted by id. If no item's name is
+    /// exactly `name`, this falls back to the items whose raw symbol name, or
+    /// whose name without its trailing hash, is `name`.
+    pub fn get_items_by_name(&self, name: &str) -> &[Id] {
+        self.names
+            .get(name)
+            .or_else(|| self.aliases.get(name))
+            .map_or(&[], |ids| ids.as_slice())
+    }
+
+    /// Get the single item with the given name, looked up the same way as with
+    /// `get_items_by_name`. If several items have the name, which is common
+    /// with generic instantiations, this returns an error listing all of them.
+    pub fn get_item_by_name(&self, name: &str) -> Result<Option<&Item>, AmbiguousName> {
+        match self.get_items_by_name(name) {
+            [] => Ok(None),
+            [id] => Ok(Some(&self[*id])),
+            ids => Err(AmbiguousName {
+                name: name.to_string(),
+                candidates: ids.to_vec(),
+            }),
         }
+    }
 
-        None // Return `None` if `name` did not match any items.
+    /// Iterate over every distinct item name, along with the items that have
+    /// it, sorted by id.
+    pub fn names(&self) -> Names {
+        Names {
+            inner: self.names.iter(),
+        }
     }
 }
 
 /// An iterator over an item's neighbors.
 #[derive(Debug)]
 pub struct Neighbors<'a> {
-    inner: slice::Iter<'a, Id>,
+    inner: slice::Iter<'a, (Id, EdgeKinds)>,
+    followed: EdgeKinds,
 }
 
 impl<'a> Iterator for Neighbors<'a> {
@@ -363,14 +726,19 @@ impl<'a> Iterator for Neighbors<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Id> {
-        self.inner.next().cloned()
+        let followed = self.followed;
+        self.inner
+            .by_ref()
+            .find(|&&(_, kinds)| kinds.intersects(followed))
+            .map(|&(id, _)| id)
     }
 }
 
 /// An iterator over an item's predecessors.
 #[derive(Debug)]
 pub struct Predecessors<'a> {
-    inner: slice::Iter<'a, Id>,
+    inner: slice::Iter<'a, (Id, EdgeKinds)>,
+    followed: EdgeKinds,
 }
 
 impl<'a> Iterator for Predecessors<'a> {
@@ -378,7 +746,378 @@ impl<'a> Iterator for Predecessors<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Id> {
-        self.inner.next().cloned()
+        let followed = self.followed;
+        self.inner
+            .by_ref()
+            .find(|&&(_, kinds)| kinds.intersects(followed))
+            .map(|&(id, _)| id)
+    }
+}
+
+/// What an edge between two items represents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EdgeKind {
+    /// An edge from the meta root to one of the real roots, or from a linked
+    /// module's scope to one of its own roots.
+    Root,
+
+    /// A direct call, or a function that is run on start up.
+    Call,
+
+    /// A function in a table that may be called indirectly.
+    IndirectCall,
+
+    /// A reference to a function's type signature.
+    Type,
+
+    /// A load from, or initialization with, a data segment.
+    Data,
+
+    /// An item containing another, such as a function containing its body.
+    Contains,
+
+    /// Any other reference, such as taking a function reference, accessing a
+    /// global, or exporting an item.
+    Reference,
+
+    /// An import in one linked module that resolves to an export of another.
+    Import,
+}
+
+impl EdgeKind {
+    /// All of the edge kinds.
+    pub fn all() -> &'static [EdgeKind] {
+        &[
+            EdgeKind::Root,
+            EdgeKind::Call,
+            EdgeKind::IndirectCall,
+            EdgeKind::Type,
+            EdgeKind::Data,
+            EdgeKind::Contains,
+            EdgeKind::Reference,
+            EdgeKind::Import,
+        ]
+    }
+
+    /// The name of this edge kind.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            EdgeKind::Root => "root",
+            EdgeKind::Call => "call",
+            EdgeKind::IndirectCall => "indirect-call",
+            EdgeKind::Type => "type",
+            EdgeKind::Data => "data",
+            EdgeKind::Contains => "contains",
+            EdgeKind::Reference => "reference",
+            EdgeKind::Import => "import",
+        }
+    }
+
+    fn bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+impl fmt::Display for EdgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EdgeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EdgeKind::all()
+            .iter()
+            .find(|kind| kind.as_str() == s)
+            .cloned()
+            .ok_or_else(|| format!("unknown edge kind: {}", s))
+    }
+}
+
+/// A set of `EdgeKind`s.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeKinds(u8);
+
+impl EdgeKinds {
+    /// The empty set.
+    pub fn empty() -> EdgeKinds {
+        EdgeKinds(0)
+    }
+
+    /// The set of every edge kind.
+    pub fn all() -> EdgeKinds {
+        EdgeKind::all().iter().cloned().collect()
+    }
+
+    /// Add the given kind to this set.
+    pub fn insert(&mut self, kind: EdgeKind) {
+        self.0 |= kind.bit();
+    }
+
+    /// Remove the given kind from this set.
+    pub fn remove(&mut self, kind: EdgeKind) {
+        self.0 &= !kind.bit();
+    }
+
+    /// Does this set contain the given kind?
+    pub fn contains(&self, kind: EdgeKind) -> bool {
+        self.0 & kind.bit() != 0
+    }
+
+    /// Do this set and the other have any kinds in common?
+    pub fn intersects(&self, other: EdgeKinds) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Is this set empty?
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterate over the kinds in this set.
+    pub fn iter(&self) -> EdgeKindsIter {
+        EdgeKindsIter {
+            set: *self,
+            inner: EdgeKind::all().iter(),
+        }
+    }
+}
+
+impl From<EdgeKind> for EdgeKinds {
+    fn from(kind: EdgeKind) -> EdgeKinds {
+        EdgeKinds(kind.bit())
+    }
+}
+
+impl iter::FromIterator<EdgeKind> for EdgeKinds {
+    fn from_iter<I: IntoIterator<Item = EdgeKind>>(iter: I) -> EdgeKinds {
+        let mut kinds = EdgeKinds::empty();
+        for kind in iter {
+            kinds.insert(kind);
+        }
+        kinds
+    }
+}
+
+impl fmt::Display for EdgeKinds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, kind) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(kind.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the kinds in an `EdgeKinds` set. Created by
+/// `EdgeKinds::iter`.
+#[derive(Clone, Debug)]
+pub struct EdgeKindsIter {
+    set: EdgeKinds,
+    inner: slice::Iter<'static, EdgeKind>,
+}
+
+impl Iterator for EdgeKindsIter {
+    type Item = EdgeKind;
+
+    fn next(&mut self) -> Option<EdgeKind> {
+        let set = self.set;
+        self.inner
+            .by_ref()
+            .find(|kind| set.contains(**kind))
+            .cloned()
+    }
+}
+
+/// An iterator over item names and the items that have them. Created by
+/// `Items::names`.
+#[derive(Clone, Debug)]
+pub struct Names<'a> {
+    inner: btree_map::Iter<'a, String, Vec<Id>>,
+}
+
+impl<'a> Iterator for Names<'a> {
+    type Item = (&'a str, &'a [Id]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(name, ids)| (name.as_str(), ids.as_slice()))
+    }
+}
+
+/// The error returned when looking up a single item by a name that several
+/// items share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AmbiguousName {
+    name: String,
+    candidates: Vec<Id>,
+}
+
+impl AmbiguousName {
+    /// The name that was looked up.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The items that have the name, sorted by id.
+    pub fn candidates(&self) -> &[Id] {
+        &self.candidates
+    }
+}
+
+impl fmt::Display for AmbiguousName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the name '{}' is ambiguous, {} items have it",
+            self.name,
+            self.candidates.len()
+        )
+    }
+}
+
+/// Strip the "::h1234567890abcdef" hash from the end of a demangled Rust symbol.
+fn strip_hash(name: &str) -> Option<&str> {
+    let idx = name.rfind("::h")?;
+    let hash = &name[idx + 3..];
+    if hash.len() == 16 && hash.chars().all(|c| c.is_digit(16)) {
+        Some(&name[..idx])
+    } else {
+        None
+    }
+}
+
+/// The part of a module's name or path that imports are likely to refer to it
+/// by, so that `./lazy.js`, `lazy` and `lazy.wasm` are all `lazy`.
+fn module_stem(name: &str) -> &str {
+    let file = name
+        .rsplit(|c| c == '/' || c == '\\')
+        .next()
+        .unwrap_or(name);
+    file.split('.').next().unwrap_or(file)
+}
+
+/// Shorten every path in a demangled name to its last segment. For example,
+/// `<alloc::vec::Vec<u8> as core::ops::Drop>::drop` becomes
+/// `<Vec<u8> as Drop>::drop`. Segments like `{{closure}}` mean little on
+/// their own, so the segment before them is kept.
+fn shorten_paths(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    // Where the identifier that was most recently written to `short` starts.
+    let mut ident_start = 0;
+    let mut rest = name;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("::") {
+            rest = &rest[2..];
+            if ident_start < short.len() && !rest.starts_with('{') {
+                short.truncate(ident_start);
+            } else {
+                short.push_str("::");
+                ident_start = short.len();
+            }
+            continue;
+        }
+
+        short.push(c);
+        rest = &rest[c.len_utf8()..];
+        if !(c.is_alphanumeric() || c == '_') {
+            ident_start = short.len();
+        }
+    }
+    short
+}
+
+/// Split a demangled name into the segments of its path, leaving out the hash
+/// at the end of Rust symbols and any generic arguments or parameters. For
+/// example, `<alloc::raw_vec::RawVec<T, A>>::double::h28f86621ee2a10aa`
+/// becomes `["alloc", "raw_vec", "RawVec", "double"]`.
+///
+/// Trait methods like `<T as Trait>::method` are put under the type's path, or
+/// under the trait's path when the type has none, as with `<&'a T as
+/// core::fmt::Debug>::fmt`, which becomes `["core", "fmt", "Debug", "fmt"]`.
+fn split_path(name: &str) -> Vec<&str> {
+    let name = strip_hash(name).unwrap_or(name);
+    let mut segments = vec![];
+    for (i, segment) in split_top_level(name, "::").into_iter().enumerate() {
+        if i == 0 && segment.len() > 2 && segment.starts_with('<') && segment.ends_with('>') {
+            let qualified = &segment[1..segment.len() - 1];
+            let (ty, trait_) = match split_top_level(qualified, " as ").as_slice() {
+                [ty, trait_] => (*ty, Some(*trait_)),
+                _ => (qualified, None),
+            };
+            let ty = split_path(strip_reference(ty));
+            match trait_ {
+                Some(trait_) if ty.len() < 2 => segments.extend(split_path(trait_)),
+                _ => segments.extend(ty),
+            }
+            continue;
+        }
+
+        // Leave out the generic arguments of turbofish segments, as in
+        // `core::mem::drop::<u8>`, and of C++ templates and parameter lists.
+        if segment.starts_with('<') {
+            continue;
+        }
+        let segment = match segment.find(&['<', '('][..]) {
+            Some(idx) => &segment[..idx],
+            None => segment,
+        };
+        if !segment.is_empty() {
+            segments.push(segment);
+        }
+    }
+    segments
+}
+
+/// Split `name` at each `separator` that isn't inside of brackets, such as the
+/// `::` in the generic arguments of `Vec<alloc::string::String>`.
+fn split_top_level<'a>(name: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    let mut previous = None;
+    for (idx, c) in name.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            // The `>` of a `->` in a function type doesn't close a bracket.
+            '>' if previous == Some('-') => {}
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        previous = Some(c);
+        if depth == 0 && idx >= start && name[idx..].starts_with(separator) {
+            parts.push(&name[start..idx]);
+            start = idx + separator.len();
+        }
+    }
+    parts.push(&name[start..]);
+    parts
+}
+
+/// Strip the references, pointers and lifetimes from the front of a type, so
+/// that `&'a mut T` becomes `T`.
+fn strip_reference(mut ty: &str) -> &str {
+    loop {
+        let stripped = ty.trim_start_matches(&['&', ' '][..]);
+        let stripped = if stripped.starts_with('\'') {
+            stripped.find(' ').map_or(stripped, |idx| &stripped[idx..])
+        } else {
+            ["*const ", "*mut ", "mut "]
+                .iter()
+                .filter_map(|prefix| stripped.strip_prefix(prefix))
+                .next()
+                .unwrap_or(stripped)
+        };
+        if stripped.len() == ty.len() {
+            return ty;
+        }
+        ty = stripped;
     }
 }
 
@@ -398,7 +1137,7 @@ impl<'a> Iterator for Iter<'a> {
 
 /// An item's unique identifier.
 /// (section index, item within that section index)
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Id(u32, u32);
 
 impl Id {
@@ -427,8 +1166,188 @@ impl Id {
     }
 }
 
+/// A top-level section of the binary.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Section {
+    name: String,
+    size: u32,
+}
+
+impl Section {
+    /// Construct a new `Section` with the given name, whose size includes its
+    /// header.
+    pub fn new<S: Into<String>>(name: S, size: u32) -> Section {
+        Section {
+            name: name.into(),
+            size,
+        }
+    }
+
+    /// Get this section's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get this section's size, including its header.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+/// A tool or language that was used to produce the binary, such as those listed
+/// in a wasm `producers` custom section.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Producer {
+    field: String,
+    name: String,
+    version: String,
+}
+
+impl Producer {
+    /// Construct a new `Producer`. The `field` is the kind of producer, for
+    /// example "language", "processed-by" or "sdk".
+    pub fn new<F, N, V>(field: F, name: N, version: V) -> Producer
+    where
+        F: Into<String>,
+        N: Into<String>,
+        V: Into<String>,
+    {
+        Producer {
+            field: field.into(),
+            name: name.into(),
+            version: version.into(),
+        }
+    }
+
+    /// Get the kind of producer this is, for example "language".
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Get this producer's name, for example "Rust".
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get this producer's version. May be empty.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+/// A string literal found in a binary's static data, such as a panic message or
+/// a source file path. Literals are found by looking for runs of printable
+/// UTF-8, so several literals that are laid out back to back, as Rust lays them
+/// out, are found as one. The offsets that items refer to it at tell them apart.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringLiteral {
+    data: Id,
+    address: Option<u32>,
+    value: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    references: Vec<(u32, Id)>,
+}
+
+impl StringLiteral {
+    /// Construct a new `StringLiteral` that is part of the given data item, and
+    /// is placed at the given address in memory, if known.
+    pub fn new<S: Into<String>>(data: Id, address: Option<u32>, value: S) -> StringLiteral {
+        StringLiteral {
+            data,
+            address,
+            value: value.into(),
+            references: vec![],
+        }
+    }
+
+    /// Get the data item that this string is part of.
+    pub fn data(&self) -> Id {
+        self.data
+    }
+
+    /// Get the address that this string is placed at in memory, if known.
+    pub fn address(&self) -> Option<u32> {
+        self.address
+    }
+
+    /// Get the string itself.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Get the items that refer to this string, along with the byte offset
+    /// into the string that each refers to, sorted by offset.
+    pub fn references(&self) -> &[(u32, Id)] {
+        &self.references
+    }
+
+    /// Record that the given item refers to this string at the given byte
+    /// offset into it.
+    pub fn add_reference(&mut self, offset: u32, from: Id) {
+        if let Err(i) = self.references.binary_search(&(offset, from)) {
+            self.references.insert(i, (offset, from));
+        }
+    }
+}
+
+/// A target feature that the binary was compiled with, such as those listed in
+/// a wasm `target_features` custom section.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetFeature {
+    policy: TargetFeaturePolicy,
+    name: String,
+}
+
+impl TargetFeature {
+    /// Construct a new `TargetFeature`.
+    pub fn new<S: Into<String>>(policy: TargetFeaturePolicy, name: S) -> TargetFeature {
+        TargetFeature {
+            policy,
+            name: name.into(),
+        }
+    }
+
+    /// Get how this feature is used by the binary.
+    pub fn policy(&self) -> TargetFeaturePolicy {
+        self.policy
+    }
+
+    /// Get this feature's name, for example "simd128".
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// How a binary uses a target feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetFeaturePolicy {
+    /// The feature is used by the binary, and may be used when linking it with
+    /// others.
+    Used,
+
+    /// The feature is required by the binary, and must be used by everything it
+    /// is linked with.
+    Required,
+
+    /// The feature is not used by the binary, and must not be used by anything
+    /// it is linked with.
+    Disallowed,
+}
+
+impl TargetFeaturePolicy {
+    /// Get a short, human readable description of this policy.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            TargetFeaturePolicy::Used => "used",
+            TargetFeaturePolicy::Required => "required",
+            TargetFeaturePolicy::Disallowed => "disallowed",
+        }
+    }
+}
+
 /// An item in the binary.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Item {
     id: Id,
     name: String,
@@ -464,7 +1383,13 @@ impl Item {
         self.size
     }
 
-    /// Get this item's name.
+    /// Get this item's kind.
+    #[inline]
+    pub fn kind(&self) -> &ItemKind {
+        &self.kind
+    }
+
+    /// Get this item's name, demangled if it is a mangled symbol.
     #[inline]
     pub fn name(&self) -> &str {
         if let ItemKind::Code(ref code) = self.kind {
@@ -474,6 +1399,44 @@ impl Item {
         }
     }
 
+    /// Get this item's raw name, as it appears in the binary. For code, this is
+    /// usually a mangled symbol.
+    #[inline]
+    pub fn raw_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get this item's name, displayed in the given mode.
+    pub fn display_name(&self, mode: NameMode) -> Cow<str> {
+        let name = self.name();
+        match mode {
+            NameMode::Demangled => Cow::Borrowed(name),
+            NameMode::NoHash => Cow::Borrowed(strip_hash(name).unwrap_or(name)),
+            NameMode::Raw => Cow::Borrowed(self.raw_name()),
+            NameMode::Short => match self.kind {
+                ItemKind::Code(_) => Cow::Owned(shorten_paths(strip_hash(name).unwrap_or(name))),
+                _ => Cow::Borrowed(name),
+            },
+        }
+    }
+
+    /// The segments of the path in this item's name, such as `["wee_alloc",
+    /// "alloc_first_fit"]` for `wee_alloc::alloc_first_fit::h9a72de3af77ef93f`.
+    /// Items that aren't code, and code whose name isn't a path, have a single
+    /// segment: their name.
+    pub fn path_segments(&self) -> Vec<&str> {
+        let name = self.name();
+        let segments = match self.kind {
+            ItemKind::Code(_) => split_path(name),
+            _ => vec![],
+        };
+        if segments.is_empty() {
+            vec![name]
+        } else {
+            segments
+        }
+    }
+
     /// The the name of the generic function that this is a monomorphization of
     /// (if any).
     #[inline]
@@ -484,6 +1447,41 @@ impl Item {
             None
         }
     }
+
+    /// The generic arguments that this item was instantiated with (if known).
+    #[inline]
+    pub fn type_arguments(&self) -> &[String] {
+        if let ItemKind::Code(ref code) = self.kind {
+            code.type_arguments()
+        } else {
+            &[]
+        }
+    }
+
+    /// The location in the original source code that this item was compiled
+    /// from (if known).
+    #[inline]
+    pub fn location(&self) -> Option<&Location> {
+        if let ItemKind::Code(ref code) = self.kind {
+            code.location()
+        } else {
+            None
+        }
+    }
+
+    /// A hash of this item's body, if it is code whose body was hashed. With
+    /// `normalized`, the hash of a wasm function ignores which functions the
+    /// body calls and how its locals are numbered. Native machine code isn't
+    /// normalized, so both hashes of a subroutine are the same.
+    #[inline]
+    pub fn body_hash(&self, normalized: bool) -> Option<u64> {
+        match self.kind {
+            ItemKind::Code(ref code) if normalized => code.normalized_body_hash(),
+            ItemKind::Code(ref code) => code.body_hash(),
+            ItemKind::Subroutine(ref subroutine) => subroutine.body_hash(),
+            _ => None,
+        }
+    }
 }
 
 impl PartialOrd for Item {
@@ -498,8 +1496,75 @@ impl Ord for Item {
     }
 }
 
+/// How to display item names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NameMode {
+    /// The demangled name, such as
+    /// `wee_alloc::alloc_first_fit::h9a72de3af77ef93f`.
+    Demangled,
+
+    /// The demangled name without the hash at the end of Rust symbols, such as
+    /// `wee_alloc::alloc_first_fit`.
+    NoHash,
+
+    /// The name as it appears in the binary, such as the mangled symbol
+    /// `_ZN9wee_alloc15alloc_first_fit17h9a72de3af77ef93fE`.
+    Raw,
+
+    /// Only the last segment of each path in the demangled name, without the
+    /// hash, such as `alloc_first_fit`.
+    Short,
+}
+
+impl NameMode {
+    /// Every name mode.
+    pub fn all() -> &'static [NameMode] {
+        &[
+            NameMode::Demangled,
+            NameMode::NoHash,
+            NameMode::Raw,
+            NameMode::Short,
+        ]
+    }
+
+    /// The name of this mode, as accepted by `FromStr`.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            NameMode::Demangled => "demangled",
+            NameMode::NoHash => "no-hash",
+            NameMode::Raw => "raw",
+            NameMode::Short => "short",
+        }
+    }
+}
+
+impl Default for NameMode {
+    fn default() -> NameMode {
+        NameMode::Demangled
+    }
+}
+
+impl fmt::Display for NameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NameMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NameMode::all()
+            .iter()
+            .find(|mode| mode.as_str() == s)
+            .cloned()
+            .ok_or_else(|| format!("unknown name mode: {}", s))
+    }
+}
+
 /// The kind of item in the binary.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ItemKind {
     /// Executable code. Function bodies.
     Code(Code),
@@ -524,6 +1589,21 @@ pub enum ItemKind {
     Type(Type),
 }
 
+impl ItemKind {
+    /// Get a short, human readable name for this kind of item.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ItemKind::Code(_) => "code",
+            ItemKind::Data(_) => "data",
+            ItemKind::Debug(_) => "debug info",
+            ItemKind::Misc(_) => "misc",
+            ItemKind::Scope(_) => "scope",
+            ItemKind::Subroutine(_) => "subroutine",
+            ItemKind::Type(_) => "type",
+        }
+    }
+}
+
 impl From<Code> for ItemKind {
     fn from(c: Code) -> ItemKind {
         ItemKind::Code(c)
@@ -567,21 +1647,35 @@ impl From<Type> for ItemKind {
 }
 
 /// Executable code. Function bodies.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Code {
     demangled: Option<String>,
     monomorphization_of: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    type_arguments: Vec<String>,
+    location: Option<Location>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_hash: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    normalized_body_hash: Option<u64>,
 }
 
 impl Code {
     /// Construct a new IR item for executable code.
     pub fn new(name: &str) -> Code {
-        let demangled = Self::demangle(&name);
-        let monomorphization_of =
-            Self::extract_generic_function(demangled.as_ref().map(|s| s.as_str()).unwrap_or(name));
+        let (demangled, generic) = Self::demangle(name);
+        let (monomorphization_of, type_arguments) = match generic {
+            Some((generic, arguments)) => (Some(generic), arguments),
+            None => (None, vec![]),
+        };
+
         Code {
             demangled,
             monomorphization_of,
+            type_arguments,
+            location: None,
+            body_hash: None,
+            normalized_body_hash: None,
         }
     }
 
@@ -596,19 +1690,71 @@ impl Code {
         self.monomorphization_of.as_ref().map(|s| s.as_str())
     }
 
-    fn demangle(s: &str) -> Option<String> {
+    /// Get the generic arguments that this function was instantiated with, if
+    /// they are known. Legacy Rust symbols don't include them.
+    pub fn type_arguments(&self) -> &[String] {
+        &self.type_arguments
+    }
+
+    /// Get the location in the original source code that this function was
+    /// compiled from, if known.
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// Set the location in the original source code that this function was
+    /// compiled from.
+    pub fn set_location(&mut self, location: Location) {
+        self.location = Some(location);
+    }
+
+    /// Get a hash of this function's body, if known. Functions with the same
+    /// body hash are almost certainly identical.
+    pub fn body_hash(&self) -> Option<u64> {
+        self.body_hash
+    }
+
+    /// Get a hash of this function's body after normalizing it, if known.
+    /// Functions that only differ in which functions they call, or in how
+    /// their locals are numbered, have the same normalized body hash.
+    pub fn normalized_body_hash(&self) -> Option<u64> {
+        self.normalized_body_hash
+    }
+
+    /// Set the hashes of this function's body, as it is and after normalizing
+    /// it.
+    pub fn set_body_hashes(&mut self, body_hash: u64, normalized_body_hash: u64) {
+        self.body_hash = Some(body_hash);
+        self.normalized_body_hash = Some(normalized_body_hash);
+    }
+
+    /// Demangle the given symbol, and find the generic function it is an
+    /// instantiation of along with its generic arguments, if any.
+    fn demangle(s: &str) -> (Option<String>, Option<(String, Vec<String>)>) {
         if let Ok(sym) = rustc_demangle::try_demangle(s) {
-            return Some(sym.to_string());
+            if rust_v0::is_v0_symbol(s) {
+                // The alternate format leaves out the hashes that v0 symbols
+                // have after every crate name, as in `core[c1f1a4ba060b9bfa]`.
+                let demangled = format!("{:#}", sym);
+                let generic = rust_v0::split_generic_arguments(&demangled);
+                return (Some(demangled), generic);
+            }
+            let demangled = sym.to_string();
+            let generic = Self::extract_generic_function(&demangled);
+            return (Some(demangled), generic);
         }
 
         if let Ok(sym) = cpp_demangle::Symbol::new(s) {
-            return Some(sym.to_string());
+            let generic = cpp_templates::split_template_arguments(&sym);
+            return (Some(sym.to_string()), generic);
         }
 
-        None
+        // Names that are not mangled symbols, such as already demangled names
+        // in a wasm "name" section, have no structure to go by.
+        (None, Self::extract_generic_function(s))
     }
 
-    fn extract_generic_function(demangled: &str) -> Option<String> {
+    fn extract_generic_function(demangled: &str) -> Option<(String, Vec<String>)> {
         // XXX: This is some hacky, ad-hoc parsing shit! This should
         // approximately work for Rust and C++ symbols, but who knows for other
         // languages. Also, it almost definitely has bugs!
@@ -617,22 +1763,24 @@ impl Code {
         // "::h1234567890" hash from the end of the symbol. If it's there, the
         // generic function is just the symbol without that hash, so remove it.
         //
-        // I know what you're thinking, and it's true: mangled (and therefore
-        // also demangled) Rust symbols don't include the concrete type(s) used
-        // to instantiate the generic function, which gives us much less to work
-        // with than we have with C++ demangled symbols. It would sure be nice
-        // if we could tell the user more about the monomorphization, but
-        // alas... :(
+        // I know what you're thinking, and it's true: legacy mangled (and
+        // therefore also demangled) Rust symbols don't include the concrete
+        // type(s) used to instantiate the generic function, which gives us much
+        // less to work with than we have with C++ demangled symbols. Symbols
+        // using the v0 mangling scheme do include them, and are handled in
+        // `rust_v0` instead.
         if let Some(idx) = demangled.rfind("::h") {
             let idx2 = demangled.rfind("::").unwrap();
             assert!(idx2 >= idx);
             if idx2 == idx {
                 let mut generic = demangled[..idx].to_string();
-                return Some(generic);
+                return Some((generic, vec![]));
             }
         }
 
-        // From here on out, we assume we are dealing with C++ symbols.
+        // From here on out, we assume we are dealing with C++ names that were
+        // already demangled. Mangled C++ symbols are split up properly in
+        // `cpp_templates` instead.
         //
         // Find the '<' and '>' that hug the generic type(s).
         let open_bracket = match demangled.char_indices().find(|&(_, ch)| ch == '<') {
@@ -655,13 +1803,92 @@ impl Code {
 
         // And now we say that the generic function is the thing proceeding the
         // '<'. Good enough!
-        Some(demangled[..open_bracket].to_string())
+        let generic = demangled[..open_bracket].to_string();
+        let arguments = Self::split_template_arguments(&demangled[open_bracket..]);
+        Some((generic, arguments))
+    }
+
+    /// Split the template argument list at the start of the given text, such as
+    /// `<int, std::pair<int, int> >::insert(int)`, into its arguments. The
+    /// list ends at the `>` matching its `<`, and commas nested in other
+    /// brackets don't separate arguments. Returns no arguments if the list is
+    /// never closed.
+    fn split_template_arguments(list: &str) -> Vec<String> {
+        let mut arguments = vec![];
+        let mut depth = 0;
+        let mut start = 1;
+        for (idx, ch) in list.char_indices() {
+            match ch {
+                '<' | '(' | '[' => depth += 1,
+                '>' | ')' | ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        arguments.push(list[start..idx].trim().to_string());
+                        arguments.retain(|argument| !argument.is_empty());
+                        return arguments;
+                    }
+                }
+                ',' if depth == 1 => {
+                    arguments.push(list[start..idx].trim().to_string());
+                    start = idx + 1;
+                }
+                _ => {}
+            }
+        }
+        vec![]
+    }
+}
+
+/// A location in the original source code, such as one found in a source map.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    file: String,
+    line: u32,
+    column: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function: Option<String>,
+}
+
+impl Location {
+    /// Construct a new `Location`. Lines and columns start at 1.
+    pub fn new<S: Into<String>>(file: S, line: u32, column: u32) -> Location {
+        Location {
+            file: file.into(),
+            line,
+            column,
+            function: None,
+        }
+    }
+
+    /// Get the path of the source file.
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// Get the line within the source file, starting at 1.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Get the column within the line, starting at 1.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// Get the name of the original function, if known.
+    pub fn function(&self) -> Option<&str> {
+        self.function.as_deref()
+    }
+
+    /// Set the name of the original function.
+    pub fn set_function<S: Into<String>>(&mut self, function: S) {
+        self.function = Some(function.into());
     }
 }
 
 /// Data inside the binary that may or may not end up loaded into memory
 /// with the executable code.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Data {
     ty: Option<String>,
 }
@@ -674,7 +1901,7 @@ impl Data {
 }
 
 /// Debugging symbols and information, such as DWARF sections.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DebugInfo;
 
 impl DebugInfo {
@@ -685,7 +1912,7 @@ impl DebugInfo {
 }
 
 /// Miscellaneous item. Perhaps metadata. Perhaps something else.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Misc;
 
 impl Misc {
@@ -696,7 +1923,7 @@ impl Misc {
 }
 
 /// Program scope. Modules, namespaces, imported declarations.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Scope;
 
 impl Scope {
@@ -707,18 +1934,32 @@ impl Scope {
 }
 
 /// Subroutine.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Subroutine;
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subroutine {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_hash: Option<u64>,
+}
 
 impl Subroutine {
     /// Construct a new subroutine item.
     pub fn new() -> Subroutine {
-        Subroutine
+        Subroutine { body_hash: None }
+    }
+
+    /// Get a hash of this subroutine's machine code, if known. Subroutines
+    /// with the same body hash are almost certainly identical.
+    pub fn body_hash(&self) -> Option<u64> {
+        self.body_hash
+    }
+
+    /// Set the hash of this subroutine's machine code.
+    pub fn set_body_hash(&mut self, body_hash: u64) {
+        self.body_hash = Some(body_hash);
     }
 }
 
 /// Type entry.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Type;
 
 impl Type {
@@ -0,0 +1,177 @@
+//! Finding the generic function behind a demangled Rust v0 symbol.
+//!
+//! Unlike legacy Rust symbols, v0 symbols include the generic arguments of
+//! every instantiation. Once demangled, they look like
+//! `core::ptr::drop_in_place::<alloc::vec::Vec<u8>>` or
+//! `<alloc::vec::Vec<u8> as core::ops::drop::Drop>::drop`.
+
+/// Is the given (mangled) symbol a Rust v0 symbol?
+pub(crate) fn is_v0_symbol(symbol: &str) -> bool {
+    // Some platforms add an extra leading underscore, and some drop it.
+    symbol.starts_with("_R") || symbol.starts_with("__R") || symbol.starts_with('R')
+}
+
+/// Split a demangled v0 symbol into the path of the generic function it
+/// instantiates and the generic arguments it was instantiated with. Returns
+/// `None` if the symbol isn't an instantiation of a generic function.
+///
+/// For example, `<alloc::vec::Vec<u8>>::push` is split into
+/// `<alloc::vec::Vec>::push` and `["u8"]`. Lifetimes are not generic arguments
+/// as far as code size is concerned, so they are left out.
+pub(crate) fn split_generic_arguments(demangled: &str) -> Option<(String, Vec<String>)> {
+    let mut generic = String::with_capacity(demangled.len());
+    let mut arguments = vec![];
+
+    let mut rest = demangled;
+    while let Some(c) = rest.chars().next() {
+        if c != '<' {
+            generic.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let is_turbofish = generic.ends_with("::");
+        let follows_ident = generic
+            .chars()
+            .next_back()
+            .map_or(false, |c| c.is_alphanumeric() || c == '_');
+
+        if is_turbofish || follows_ident {
+            // A list of generic arguments, such as the `<u8>` in
+            // `Vec<u8>` or `drop_in_place::<u8>`.
+            let end = matching_close_bracket(rest)?;
+            arguments.extend(
+                split_top_level(&rest[1..end], ',')
+                    .into_iter()
+                    .map(|arg| arg.trim())
+                    .filter(|arg| !arg.starts_with('\''))
+                    .map(|arg| arg.to_string()),
+            );
+            if is_turbofish {
+                let len = generic.len() - "::".len();
+                generic.truncate(len);
+            }
+            rest = &rest[end + 1..];
+        } else {
+            // A qualified path, such as `<[u8]>::iter` or `<&T as Trait>`.
+            generic.push('<');
+            rest = &rest[1..];
+            if let Some((prefix, element, len)) = split_builtin_self_type(rest) {
+                generic.push_str(prefix);
+                generic.push('_');
+                arguments.push(element.to_string());
+                rest = &rest[len..];
+            }
+        }
+    }
+
+    if arguments.is_empty() {
+        None
+    } else {
+        Some((generic, arguments))
+    }
+}
+
+/// Find the index of the `>` matching the `<` that `s` starts with. The `>` of
+/// `->` in function types doesn't count.
+fn matching_close_bracket(s: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut prev = None;
+    for (idx, c) in s.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if prev != Some('-') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    None
+}
+
+/// Split `s` at every `sep` that isn't nested inside brackets.
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut depth = 0;
+    let mut prev = None;
+    let mut start = 0;
+    let mut parts = vec![];
+    for (idx, c) in s.char_indices() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' if prev == Some('-') => {}
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ if c == sep && depth == 0 => {
+                parts.push(&s[start..idx]);
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// If `s` starts with a reference, pointer, slice or array self type, like the
+/// `&u8` in `<&u8 as core::fmt::Debug>::fmt`, split it into everything before
+/// the element type and the element type itself. Their implementations are
+/// generic over the element type, so it is treated as a generic argument.
+/// Also returns the length of the self type up to the element type's end.
+fn split_builtin_self_type(s: &str) -> Option<(&str, &str, usize)> {
+    // The self type ends at the ` as ` of a trait implementation, or at the
+    // `>` that closes the qualified path.
+    let self_type = {
+        let mut depth = 0;
+        let mut prev = None;
+        let mut end = s.len();
+        for (idx, c) in s.char_indices() {
+            match c {
+                '<' | '(' | '[' => depth += 1,
+                '>' if prev == Some('-') => {}
+                '>' if depth == 0 => {
+                    end = idx;
+                    break;
+                }
+                '>' | ')' | ']' => depth -= 1,
+                ' ' if depth == 0 && s[idx..].starts_with(" as ") => {
+                    end = idx;
+                    break;
+                }
+                _ => {}
+            }
+            prev = Some(c);
+        }
+        &s[..end]
+    };
+
+    let (prefix_len, element_end) = if self_type.starts_with('&') {
+        let mut prefix = 1;
+        if self_type[prefix..].starts_with('\'') {
+            prefix += self_type[prefix..].find(' ')? + 1;
+        }
+        if self_type[prefix..].starts_with("mut ") {
+            prefix += "mut ".len();
+        }
+        (prefix, self_type.len())
+    } else if self_type.starts_with("*const ") {
+        ("*const ".len(), self_type.len())
+    } else if self_type.starts_with("*mut ") {
+        ("*mut ".len(), self_type.len())
+    } else if self_type.starts_with('[') && self_type.ends_with(']') {
+        let inner = &self_type[1..self_type.len() - 1];
+        let element = split_top_level(inner, ';')[0];
+        (1, 1 + element.len())
+    } else {
+        return None;
+    };
+
+    let element = &self_type[prefix_len..element_end];
+    if element.is_empty() || element == "_" {
+        return None;
+    }
+    Some((&self_type[..prefix_len], element, element_end))
+}
@@ -0,0 +1,170 @@
+//! Saving `Items` to, and loading them from, snapshots on disk.
+//!
+//! A snapshot is a JSON document holding every item, root and edge, along with
+//! the binary's string literals, sections, producers, target features and parse
+//! warnings. It does not hold the meta root, which is recreated when loading,
+//! or anything computed by analyses, such as dominators.
+
+use super::{
+    EdgeKind, EdgeKinds, Id, Item, Items, ItemsBuilder, Producer, Section, StringLiteral,
+    TargetFeature,
+};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use serde_json;
+use std::io;
+
+/// The value of the `format` field that every snapshot starts with.
+pub const SNAPSHOT_FORMAT: &str = "twiggy-ir-snapshot";
+
+/// The version of the snapshot format. This is bumped whenever the format
+/// changes in a way that older versions of `twiggy` can't read.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+}
+
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    format: &'static str,
+    version: u32,
+    size: u32,
+    items: Vec<&'a Item>,
+    roots: Vec<Id>,
+    edges: Vec<(Id, Id, EdgeKinds)>,
+    strings: &'a [StringLiteral],
+    sections: &'a [Section],
+    producers: &'a [Producer],
+    target_features: &'a [TargetFeature],
+    warnings: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    size: u32,
+    items: Vec<Item>,
+    roots: Vec<Id>,
+    edges: Vec<(Id, Id, EdgeKinds)>,
+    strings: Vec<StringLiteral>,
+    sections: Vec<Section>,
+    producers: Vec<Producer>,
+    target_features: Vec<TargetFeature>,
+    warnings: Vec<String>,
+}
+
+impl Serialize for EdgeKinds {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for EdgeKinds {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let kinds = Vec::<EdgeKind>::deserialize(deserializer)?;
+        Ok(kinds.into_iter().collect())
+    }
+}
+
+impl Items {
+    /// Write a snapshot of these items to the given destination, which can be
+    /// loaded again with `Items::read_snapshot`.
+    pub fn write_snapshot<W: io::Write>(&self, dest: W) -> Result<(), serde_json::Error> {
+        let meta_root = self.meta_root;
+        let snapshot = SnapshotRef {
+            format: SNAPSHOT_FORMAT,
+            version: SNAPSHOT_VERSION,
+            size: self.size,
+            items: self
+                .items
+                .values()
+                .filter(|item| item.id != meta_root)
+                .collect(),
+            roots: self.roots.iter().cloned().collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|&(&from, _)| from != meta_root)
+                .flat_map(|(&from, tos)| tos.iter().map(move |&(to, kinds)| (from, to, kinds)))
+                .collect(),
+            strings: &self.strings,
+            sections: &self.sections,
+            producers: &self.producers,
+            target_features: &self.target_features,
+            warnings: &self.warnings,
+        };
+        serde_json::to_writer(dest, &snapshot)
+    }
+
+    /// Load items from a snapshot written by `Items::write_snapshot`.
+    pub fn read_snapshot(data: &[u8]) -> Result<Items, serde_json::Error> {
+        let header: Header = serde_json::from_slice(data)?;
+        if header.format != SNAPSHOT_FORMAT {
+            return Err(de::Error::custom(format!(
+                "not a twiggy snapshot: unknown format '{}'",
+                header.format
+            )));
+        }
+        if header.version != SNAPSHOT_VERSION {
+            return Err(de::Error::custom(format!(
+                "unsupported snapshot version {}, expected version {}",
+                header.version, SNAPSHOT_VERSION
+            )));
+        }
+
+        let snapshot: Snapshot = serde_json::from_slice(data)?;
+
+        let mut builder = ItemsBuilder::new(snapshot.size);
+        for item in snapshot.items {
+            if item.id == Id::root() || builder.items.contains_key(&item.id) {
+                return Err(de::Error::custom(
+                    "snapshot has a duplicate or reserved item id",
+                ));
+            }
+            builder.add_item(item);
+        }
+        for root in snapshot.roots {
+            if !builder.items.contains_key(&root) {
+                return Err(de::Error::custom("snapshot has a root that is not an item"));
+            }
+            builder.roots.insert(root);
+        }
+        for (from, to, kinds) in snapshot.edges {
+            if !builder.items.contains_key(&from) || !builder.items.contains_key(&to) {
+                return Err(de::Error::custom(
+                    "snapshot has an edge between unknown items",
+                ));
+            }
+            for kind in kinds.iter() {
+                builder.add_edge(from, to, kind);
+            }
+        }
+        for string in snapshot.strings {
+            let known = builder.items.contains_key(&string.data)
+                && string
+                    .references
+                    .iter()
+                    .all(|&(_, from)| builder.items.contains_key(&from));
+            if !known {
+                return Err(de::Error::custom("snapshot has a string of unknown items"));
+            }
+            builder.add_string(string);
+        }
+        for section in snapshot.sections {
+            builder.add_section(section);
+        }
+        for producer in snapshot.producers {
+            builder.add_producer(producer);
+        }
+        for feature in snapshot.target_features {
+            builder.add_target_feature(feature);
+        }
+        for warning in snapshot.warnings {
+            builder.add_warning(warning);
+        }
+
+        Ok(builder.finish())
+    }
+}
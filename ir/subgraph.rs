@@ -0,0 +1,417 @@
+//! Restricting `Items` to a subgraph, such as the items that a single export
+//! retains.
+
+use super::{EdgeKinds, Id, Item, Items, ItemsBuilder};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+impl Items {
+    /// Start building a subgraph of these items. Every item, edge and root is
+    /// kept unless the returned `Subgraph` is told otherwise.
+    ///
+    /// For example, to find out what the `greet` export retains on its own:
+    ///
+    /// ```
+    /// # extern crate twiggy_ir as ir;
+    /// # fn main() {
+    /// # let mut builder = ir::ItemsBuilder::new(7);
+    /// # let id = |i| ir::Id::entry(0, i);
+    /// # builder.add_root(ir::Item::new(id(0), "export \"greet\"", 1, ir::Misc::new()));
+    /// # builder.add_root(ir::Item::new(id(1), "export \"main\"", 2, ir::Misc::new()));
+    /// # builder.add_item(ir::Item::new(id(2), "greet", 4, ir::Misc::new()));
+    /// # builder.add_edge(id(0), id(2), ir::EdgeKind::Reference);
+    /// # builder.add_edge(id(1), id(2), ir::EdgeKind::Reference);
+    /// # let items = builder.finish();
+    /// let greet = items.get_items_by_name("export \"greet\"").to_vec();
+    /// let mut subgraph = items.subgraph().with_roots(greet.clone()).reachable_only().finish();
+    /// subgraph.compute_retained_sizes();
+    /// assert_eq!(subgraph.retained_size(greet[0]), 5);
+    /// # }
+    /// ```
+    pub fn subgraph(&self) -> Subgraph {
+        Subgraph {
+            items: self,
+            roots: None,
+            kept: None,
+            predicates: vec![],
+            excluded: BTreeSet::new(),
+            cut: BTreeSet::new(),
+            reachable_only: false,
+        }
+    }
+
+    /// Get the items that are reachable from the meta root, following only the
+    /// edges whose kinds are in `followed_edge_kinds`. Everything else is dead
+    /// code.
+    pub fn reachable(&self) -> BTreeSet<Id> {
+        self.reachable_from(self.meta_root, &BTreeSet::new())
+    }
+
+    /// Get the items that are reachable from `start` without passing through
+    /// any of the `avoided` items.
+    fn reachable_from(&self, start: Id, avoided: &BTreeSet<Id>) -> BTreeSet<Id> {
+        let mut reachable = BTreeSet::new();
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            if avoided.contains(&id) || !reachable.insert(id) {
+                continue;
+            }
+            stack.extend(self.neighbors(id));
+        }
+        reachable
+    }
+}
+
+type Predicate<'a> = Box<Fn(&Item) -> bool + 'a>;
+
+/// A subgraph of some `Items` that is being built. Created with
+/// `Items::subgraph`, and turned into new `Items` with `finish`.
+///
+/// The new items have the same size, sections and name mode as the originals,
+/// and follow the same kinds of edges, but their dominators and retained sizes
+/// are computed afresh.
+pub struct Subgraph<'a> {
+    items: &'a Items,
+    roots: Option<BTreeSet<Id>>,
+    kept: Option<BTreeSet<Id>>,
+    predicates: Vec<Predicate<'a>>,
+    excluded: BTreeSet<Id>,
+    cut: BTreeSet<(Id, Id)>,
+    reachable_only: bool,
+}
+
+impl<'a> fmt::Debug for Subgraph<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Subgraph")
+            .field("roots", &self.roots)
+            .field("kept", &self.kept)
+            .field("predicates", &self.predicates.len())
+            .field("excluded", &self.excluded)
+            .field("cut", &self.cut)
+            .field("reachable_only", &self.reachable_only)
+            .finish()
+    }
+}
+
+impl<'a> Subgraph<'a> {
+    /// Use the given items as the roots of the subgraph, instead of the
+    /// original roots.
+    pub fn with_roots<I: IntoIterator<Item = Id>>(mut self, roots: I) -> Subgraph<'a> {
+        self.roots = Some(roots.into_iter().collect());
+        self
+    }
+
+    /// Only keep the given items, such as those found with
+    /// `Items::get_items_by_name`. May be combined with `keep_if`, in which
+    /// case items must pass both.
+    pub fn keep_items<I: IntoIterator<Item = Id>>(mut self, ids: I) -> Subgraph<'a> {
+        let ids: BTreeSet<Id> = ids.into_iter().collect();
+        self.kept = Some(match self.kept {
+            Some(kept) => kept.intersection(&ids).cloned().collect(),
+            None => ids,
+        });
+        self
+    }
+
+    /// Only keep the items for which the given predicate is true, such as the
+    /// items of a certain kind, or whose names match a regular expression. May
+    /// be called more than once, in which case items must pass every predicate.
+    pub fn keep_if<F>(mut self, predicate: F) -> Subgraph<'a>
+    where
+        F: Fn(&Item) -> bool + 'a,
+    {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    /// Leave out the given items, along with everything that they dominate:
+    /// the items that are reachable from the roots, but not without passing
+    /// through one of them.
+    pub fn exclude_subtrees<I: IntoIterator<Item = Id>>(mut self, ids: I) -> Subgraph<'a> {
+        self.excluded.extend(ids);
+        self
+    }
+
+    /// Leave out the edges between the given pairs of items, whatever their
+    /// kinds. The items themselves are kept.
+    pub fn without_edges<I: IntoIterator<Item = (Id, Id)>>(mut self, edges: I) -> Subgraph<'a> {
+        self.cut.extend(edges);
+        self
+    }
+
+    /// Leave out every item that isn't reachable from the roots.
+    pub fn reachable_only(mut self) -> Subgraph<'a> {
+        self.reachable_only = true;
+        self
+    }
+
+    /// Finish building the subgraph and return its items.
+    pub fn finish(self) -> Items {
+        let items = self.items;
+        let meta_root = items.meta_root;
+
+        let mut kept: BTreeSet<Id> = items
+            .iter()
+            .map(|item| item.id())
+            .filter(|&id| id != meta_root)
+            .filter(|id| self.kept.as_ref().map_or(true, |kept| kept.contains(id)))
+            .filter(|&id| self.predicates.iter().all(|keep| keep(&items[id])))
+            .collect();
+
+        let roots: BTreeSet<Id> = match self.roots {
+            Some(ref roots) => roots.intersection(&kept).cloned().collect(),
+            None => items.roots.intersection(&kept).cloned().collect(),
+        };
+
+        let mut builder = ItemsBuilder::new(items.size);
+        for &id in &kept {
+            builder.add_item(items[id].clone());
+        }
+        for &root in &roots {
+            builder.roots.insert(root);
+        }
+        for (from, tos) in items.edges.iter() {
+            if !kept.contains(from) {
+                continue;
+            }
+            let tos: BTreeMap<Id, EdgeKinds> = tos
+                .iter()
+                .filter(|&&(to, _)| kept.contains(&to) && !self.cut.contains(&(*from, to)))
+                .cloned()
+                .collect();
+            if !tos.is_empty() {
+                builder.edges.insert(*from, tos);
+            }
+        }
+        for string in items.strings.iter().filter(|s| kept.contains(&s.data)) {
+            let mut string = string.clone();
+            string.references.retain(|&(_, from)| kept.contains(&from));
+            builder.add_string(string);
+        }
+        for section in &items.sections {
+            builder.add_section(section.clone());
+        }
+        for producer in &items.producers {
+            builder.add_producer(producer.clone());
+        }
+        for feature in &items.target_features {
+            builder.add_target_feature(feature.clone());
+        }
+        for warning in &items.warnings {
+            builder.add_warning(warning.as_str());
+        }
+
+        let mut subgraph = builder.finish();
+        subgraph.followed_edge_kinds = items.followed_edge_kinds;
+        subgraph.name_mode = items.name_mode;
+
+        if self.excluded.is_empty() && !self.reachable_only {
+            return subgraph;
+        }
+
+        // Work out what else to leave out on the new graph, where only the kept
+        // edges and roots count, then start over with fewer items.
+        let reachable = subgraph.reachable();
+        if self.reachable_only {
+            kept.retain(|id| reachable.contains(id));
+        }
+        if !self.excluded.is_empty() {
+            let still_reachable = subgraph.reachable_from(meta_root, &self.excluded);
+            kept.retain(|id| {
+                !self.excluded.contains(id)
+                    && (still_reachable.contains(id) || !reachable.contains(id))
+            });
+        }
+
+        Subgraph {
+            items,
+            roots: Some(roots),
+            kept: Some(kept),
+            predicates: vec![],
+            excluded: BTreeSet::new(),
+            cut: self.cut,
+            reachable_only: false,
+        }
+        .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{EdgeKind, Id, Item, Items, ItemsBuilder, Misc};
+
+    fn id(i: usize) -> Id {
+        Id::entry(0, i)
+    }
+
+    /// Build this graph, where `a` is the only root, `u` is unreachable, and
+    /// item `i` is `1 << i` bytes large:
+    ///
+    /// ```text
+    /// a ---> b ---> c
+    /// |
+    /// +----> d ---> e ---> g
+    ///        |             ^
+    ///        +----> f -----+
+    ///
+    /// u
+    /// ```
+    fn items() -> Items {
+        let names = ["a", "b", "c", "d", "e", "f", "g", "u"];
+        let mut builder = ItemsBuilder::new(255);
+        for (i, name) in names.iter().enumerate() {
+            let item = Item::new(id(i), *name, 1 << i, Misc::new());
+            if i == 0 {
+                builder.add_root(item);
+            } else {
+                builder.add_item(item);
+            }
+        }
+        let edges = [(0, 1), (1, 2), (0, 3), (3, 4), (3, 5), (4, 6), (5, 6)];
+        for &(from, to) in &edges {
+            builder.add_edge(id(from), id(to), EdgeKind::Call);
+        }
+        builder.finish()
+    }
+
+    /// The names of the items in the subgraph, without the meta root.
+    fn names(items: &Items) -> Vec<&str> {
+        let meta_root = items.meta_root();
+        items
+            .iter()
+            .filter(|item| item.id() != meta_root)
+            .map(|item| item.name())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_everything_by_default() {
+        let items = items();
+        let subgraph = items.subgraph().finish();
+        assert_eq!(
+            names(&subgraph),
+            vec!["a", "b", "c", "d", "e", "f", "g", "u"]
+        );
+        assert_eq!(
+            subgraph.roots().iter().cloned().collect::<Vec<_>>(),
+            vec![id(0)]
+        );
+    }
+
+    #[test]
+    fn keep_items_and_keep_if() {
+        let items = items();
+        let subgraph = items
+            .subgraph()
+            .keep_items(vec![id(0), id(1), id(2), id(3)])
+            .keep_if(|item| item.size() >= 2)
+            .finish();
+        assert_eq!(names(&subgraph), vec!["b", "c", "d"]);
+        // The root didn't pass the predicate, so there are none left.
+        assert!(subgraph.roots().is_empty());
+        assert_eq!(subgraph.neighbors(id(1)).collect::<Vec<_>>(), vec![id(2)]);
+        assert!(subgraph.neighbors(id(3)).next().is_none());
+    }
+
+    #[test]
+    fn keep_items_twice_intersects() {
+        let items = items();
+        let subgraph = items
+            .subgraph()
+            .keep_items(vec![id(0), id(1), id(2)])
+            .keep_items(vec![id(1), id(2), id(3)])
+            .finish();
+        assert_eq!(names(&subgraph), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn with_roots() {
+        let items = items();
+        let mut subgraph = items.subgraph().with_roots(vec![id(3)]).finish();
+        assert_eq!(
+            subgraph.roots().iter().cloned().collect::<Vec<_>>(),
+            vec![id(3)]
+        );
+        // Without `reachable_only`, everything else is still there.
+        assert_eq!(subgraph.iter().count(), items.iter().count());
+        subgraph.compute_retained_sizes();
+        assert_eq!(subgraph.retained_size(id(3)), 8 + 16 + 32 + 64);
+        assert_eq!(subgraph.retained_size(id(0)), 1);
+    }
+
+    #[test]
+    fn reachable_only() {
+        let items = items();
+        let subgraph = items.subgraph().reachable_only().finish();
+        assert_eq!(names(&subgraph), vec!["a", "b", "c", "d", "e", "f", "g"]);
+
+        let subgraph = items
+            .subgraph()
+            .with_roots(vec![id(3)])
+            .reachable_only()
+            .finish();
+        assert_eq!(names(&subgraph), vec!["d", "e", "f", "g"]);
+    }
+
+    #[test]
+    fn exclude_subtrees_of_a_chain() {
+        let items = items();
+        let subgraph = items.subgraph().exclude_subtrees(vec![id(1)]).finish();
+        // Unreachable items aren't dominated by anything, so they stay.
+        assert_eq!(names(&subgraph), vec!["a", "d", "e", "f", "g", "u"]);
+    }
+
+    #[test]
+    fn exclude_subtrees_of_a_diamond() {
+        let items = items();
+
+        // `g` is still reachable through `f`.
+        let subgraph = items.subgraph().exclude_subtrees(vec![id(4)]).finish();
+        assert_eq!(names(&subgraph), vec!["a", "b", "c", "d", "f", "g", "u"]);
+
+        // Both sides of the diamond, and where they meet, are dominated by `d`.
+        let subgraph = items.subgraph().exclude_subtrees(vec![id(3)]).finish();
+        assert_eq!(names(&subgraph), vec!["a", "b", "c", "u"]);
+
+        // Excluding both sides leaves nothing to reach `g` through.
+        let subgraph = items
+            .subgraph()
+            .exclude_subtrees(vec![id(4), id(5)])
+            .finish();
+        assert_eq!(names(&subgraph), vec!["a", "b", "c", "d", "u"]);
+    }
+
+    #[test]
+    fn without_edges() {
+        let items = items();
+        let mut subgraph = items
+            .subgraph()
+            .without_edges(vec![(id(3), id(4))])
+            .finish();
+        // The items on both ends of the edge are kept.
+        assert_eq!(subgraph.iter().count(), items.iter().count());
+        assert_eq!(subgraph.neighbors(id(3)).collect::<Vec<_>>(), vec![id(5)]);
+        subgraph.compute_retained_sizes();
+        assert_eq!(subgraph.retained_size(id(3)), 8 + 32 + 64);
+        assert_eq!(subgraph.retained_size(id(4)), 16);
+
+        let subgraph = items
+            .subgraph()
+            .without_edges(vec![(id(3), id(4))])
+            .reachable_only()
+            .finish();
+        assert_eq!(names(&subgraph), vec!["a", "b", "c", "d", "f", "g"]);
+    }
+
+    #[test]
+    fn without_edges_before_exclude_subtrees() {
+        let items = items();
+        // With the edge to `f` cut, excluding `e` takes `g` with it.
+        let subgraph = items
+            .subgraph()
+            .without_edges(vec![(id(3), id(5))])
+            .exclude_subtrees(vec![id(4)])
+            .finish();
+        assert_eq!(names(&subgraph), vec!["a", "b", "c", "d", "f", "u"]);
+    }
+}
@@ -27,11 +27,41 @@ pub enum Options {
     #[structopt(name = "paths")]
     Paths(Paths),
 
+    /// Show the immediate callers and callees of some items, with their sizes.
+    #[structopt(name = "neighbors")]
+    Neighbors(Neighbors),
+
     /// List the generic function monomorphizations that are contributing to
     /// code bloat.
     #[structopt(name = "monos")]
     Monos(Monos),
 
+    /// Find functions with identical, or nearly identical, bodies that could be
+    /// deduplicated.
+    #[structopt(name = "dups")]
+    Dups(Dups),
+
+    /// Find the string literals in a binary's static data, such as panic
+    /// messages and source file paths, and the items that refer to them.
+    #[structopt(name = "strings")]
+    Strings(Strings),
+
+    /// Measure the well-known subsystems of the Rust standard library that
+    /// bloat binaries, such as panicking and formatting, and what pulls them
+    /// in.
+    #[structopt(name = "rust-bloat")]
+    RustBloat(RustBloat),
+
+    /// Summarize the sizes of a binary's items: totals for each kind of item,
+    /// and the distribution of function sizes.
+    #[structopt(name = "stats")]
+    Stats(Stats),
+
+    /// Group items by the namespaces in their names, such as crates, modules
+    /// and types, into a tree with the size of each namespace.
+    #[structopt(name = "namespaces")]
+    Namespaces(Namespaces),
+
     /// Diff the old and new versions of a binary to see what sizes changed.
     #[structopt(name = "diff")]
     Diff(Diff),
@@ -39,7 +69,27 @@ pub enum Options {
     /// Find and display code and data that is not transitively referenced by
     /// any exports or public functions.
     #[structopt(name = "garbage")]
-    Garbage(Garbage)
+    Garbage(Garbage),
+
+    /// Simulate removing some items, or cutting some edges, and report exactly
+    /// which items would become garbage as a result.
+    #[structopt(name = "whatif")]
+    Whatif(Whatif),
+
+    /// Find groups of mutually recursive items, which the dominator tree
+    /// attributes to whichever member it reaches first.
+    #[structopt(name = "cycles")]
+    Cycles(Cycles),
+
+    /// Summarize a binary's sections, and the toolchain and target features
+    /// used to produce it.
+    #[structopt(name = "info")]
+    Info(Info),
+
+    /// Save a snapshot of a binary's IR, which every other sub-command accepts
+    /// in place of the binary.
+    #[structopt(name = "snapshot")]
+    Snapshot(Snapshot)
 }
 
 /// List the top code size offenders in a binary.
@@ -52,6 +102,20 @@ pub struct Top {
     #[structopt(parse(from_os_str))]
     input: path::PathBuf,
 
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
@@ -62,6 +126,14 @@ pub struct Top {
     #[structopt(short = "f", long = "format", default_value = "text")]
     output_format: traits::OutputFormat,
 
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
     /// The maximum number of items to display.
     #[structopt(short = "n")]
     number: Option<u32>,
@@ -73,6 +145,41 @@ pub struct Top {
     /// Sort list by retained size, rather than shallow size.
     #[structopt(long = "retained")]
     retained: bool,
+
+    /// Ignore edges of the given kind, such as `type` or `data`. May be given
+    /// more than once.
+    #[structopt(long = "ignore-edges", raw(number_of_values = "1"))]
+    ignore_edges: Vec<String>,
+
+    /// Analyze as if the items whose names match this regular expression, such
+    /// as `export "greet"`, were the only roots. May be given more than once.
+    #[structopt(long = "root", raw(number_of_values = "1"))]
+    roots: Vec<String>,
+
+    /// Stop treating the roots whose names match this regular expression, such
+    /// as debugging custom sections, as roots. May be given more than once.
+    #[structopt(long = "ignore-root", raw(number_of_values = "1"))]
+    ignore_roots: Vec<String>,
+}
+
+impl Top {
+    // TODO: wasm-bindgen does not support sending Vec<String> across
+    // the wasm ABI boundary yet.
+
+    /// The kinds of edges to ignore.
+    pub fn ignore_edges(&self) -> &[String] {
+        &self.ignore_edges
+    }
+
+    /// The regular expressions matching the items to use as roots, if any.
+    pub fn roots(&self) -> &[String] {
+        &self.roots
+    }
+
+    /// The regular expressions matching the roots to ignore.
+    pub fn ignore_roots(&self) -> &[String] {
+        &self.ignore_roots
+    }
 }
 
 #[wasm_bindgen]
@@ -111,6 +218,21 @@ impl Top {
     pub fn set_retained(&mut self, do_it: bool) {
         self.retained = do_it;
     }
+
+    /// Ignore edges of the given kind.
+    pub fn add_ignored_edge_kind(&mut self, kind: String) {
+        self.ignore_edges.push(kind);
+    }
+
+    /// Use the items whose names match the given regular expression as roots.
+    pub fn add_root(&mut self, regex: String) {
+        self.roots.push(regex);
+    }
+
+    /// Ignore the roots whose names match the given regular expression.
+    pub fn add_ignored_root(&mut self, regex: String) {
+        self.ignore_roots.push(regex);
+    }
 }
 
 /// Compute and display the dominator tree for a binary's call graph.
@@ -123,16 +245,39 @@ pub struct Dominators {
     #[structopt(parse(from_os_str))]
     input: path::PathBuf,
 
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
     output_destination: OutputDestination,
 
-    /// The format the output should be written in.
+    /// The format the output should be written in: `text`, `json`, `csv`, or
+    /// `folded` stacks for flamegraph tools.
     #[cfg(feature = "cli")]
     #[structopt(short = "f", long = "format", default_value = "text")]
     output_format: traits::OutputFormat,
 
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
     /// The name of the function whose dominator subtree should be printed.
     items: Vec<String>,
 
@@ -147,6 +292,21 @@ pub struct Dominators {
     /// Whether or not `items` should be treated as regular expressions.
     #[structopt(long = "regex")]
     using_regexps: bool,
+
+    /// Ignore edges of the given kind, such as `type` or `data`. May be given
+    /// more than once.
+    #[structopt(long = "ignore-edges", raw(number_of_values = "1"))]
+    ignore_edges: Vec<String>,
+
+    /// Analyze as if the items whose names match this regular expression, such
+    /// as `export "greet"`, were the only roots. May be given more than once.
+    #[structopt(long = "root", raw(number_of_values = "1"))]
+    roots: Vec<String>,
+
+    /// Stop treating the roots whose names match this regular expression, such
+    /// as debugging custom sections, as roots. May be given more than once.
+    #[structopt(long = "ignore-root", raw(number_of_values = "1"))]
+    ignore_roots: Vec<String>,
 }
 
 impl Dominators {
@@ -157,6 +317,21 @@ impl Dominators {
     pub fn items(&self) -> &[String] {
         &self.items
     }
+
+    /// The kinds of edges to ignore.
+    pub fn ignore_edges(&self) -> &[String] {
+        &self.ignore_edges
+    }
+
+    /// The regular expressions matching the items to use as roots, if any.
+    pub fn roots(&self) -> &[String] {
+        &self.roots
+    }
+
+    /// The regular expressions matching the roots to ignore.
+    pub fn ignore_roots(&self) -> &[String] {
+        &self.ignore_roots
+    }
 }
 
 #[wasm_bindgen]
@@ -195,6 +370,21 @@ impl Dominators {
     pub fn set_using_regexps(&mut self, using_regexps: bool) {
         self.using_regexps = using_regexps;
     }
+
+    /// Ignore edges of the given kind.
+    pub fn add_ignored_edge_kind(&mut self, kind: String) {
+        self.ignore_edges.push(kind);
+    }
+
+    /// Use the items whose names match the given regular expression as roots.
+    pub fn add_root(&mut self, regex: String) {
+        self.roots.push(regex);
+    }
+
+    /// Ignore the roots whose names match the given regular expression.
+    pub fn add_ignored_root(&mut self, regex: String) {
+        self.ignore_roots.push(regex);
+    }
 }
 
 /// Find and display the call paths to a function in the given binary's call
@@ -208,16 +398,39 @@ pub struct Paths {
     #[structopt(parse(from_os_str))]
     input: path::PathBuf,
 
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
     output_destination: OutputDestination,
 
-    /// The format the output should be written in.
+    /// The format the output should be written in: `text`, `json`, `csv`, or
+    /// `folded` stacks for flamegraph tools.
     #[cfg(feature = "cli")]
     #[structopt(short = "f", long = "format", default_value = "text")]
     output_format: traits::OutputFormat,
 
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
     /// The functions to find call paths to.
     functions: Vec<String>,
 
@@ -236,6 +449,26 @@ pub struct Paths {
     /// Whether or not `functions` should be treated as regular expressions.
     #[structopt(long = "regex")]
     using_regexps: bool,
+
+    /// Show the kinds of the edges along each path, such as `call` or
+    /// `reference`.
+    #[structopt(long = "edge-kinds")]
+    show_edge_kinds: bool,
+
+    /// Only show the given number of shortest retaining paths from any root to
+    /// each function, rather than every path up to the maximum depth.
+    #[structopt(long = "shortest")]
+    shortest: Option<u32>,
+
+    /// How to measure the length of a path with `--shortest`: by the number of
+    /// `hops` along it, or by the total shallow `size` of its items.
+    #[structopt(long = "weight", default_value = "hops", raw(possible_values = r#"&["hops", "size"]"#))]
+    weight: String,
+
+    /// Ignore edges of the given kind, such as `type` or `data`. May be given
+    /// more than once.
+    #[structopt(long = "ignore-edges", raw(number_of_values = "1"))]
+    ignore_edges: Vec<String>,
 }
 
 impl Default for Paths {
@@ -244,15 +477,25 @@ impl Default for Paths {
             #[cfg(feature = "cli")]
             input: Default::default(),
             #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
             output_destination: Default::default(),
             #[cfg(feature = "cli")]
             output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
 
             functions: Default::default(),
             max_depth: 10,
             max_paths: 10,
             descending: false,
             using_regexps: false,
+            show_edge_kinds: false,
+            shortest: None,
+            weight: "hops".to_string(),
+            ignore_edges: Default::default(),
         }
     }
 }
@@ -265,6 +508,27 @@ impl Paths {
     pub fn functions(&self) -> &[String] {
         &self.functions
     }
+
+    /// The kinds of edges to ignore.
+    pub fn ignore_edges(&self) -> &[String] {
+        &self.ignore_edges
+    }
+}
+
+impl Paths {
+    // TODO: wasm-bindgen does not support returning borrowed strings, or
+    // `Option`s, across the wasm ABI boundary yet.
+
+    /// The number of shortest retaining paths to show, if only those should be
+    /// shown.
+    pub fn shortest(&self) -> Option<u32> {
+        self.shortest
+    }
+
+    /// How to measure the length of a path: `hops` or `size`.
+    pub fn weight(&self) -> &str {
+        &self.weight
+    }
 }
 
 #[wasm_bindgen]
@@ -318,6 +582,168 @@ impl Paths {
     pub fn set_using_regexps(&mut self, using_regexps: bool) {
         self.using_regexps = using_regexps;
     }
+
+    /// Whether or not to show the kinds of the edges along each path.
+    pub fn show_edge_kinds(&self) -> bool {
+        self.show_edge_kinds
+    }
+
+    /// Set whether or not to show the kinds of the edges along each path.
+    pub fn set_show_edge_kinds(&mut self, show_edge_kinds: bool) {
+        self.show_edge_kinds = show_edge_kinds;
+    }
+
+    /// Only show the given number of shortest retaining paths.
+    pub fn set_shortest(&mut self, shortest: u32) {
+        self.shortest = Some(shortest);
+    }
+
+    /// Set how to measure the length of a path: `hops` or `size`.
+    pub fn set_weight(&mut self, weight: &str) {
+        self.weight = weight.to_string();
+    }
+
+    /// Ignore edges of the given kind.
+    pub fn add_ignored_edge_kind(&mut self, kind: String) {
+        self.ignore_edges.push(kind);
+    }
+}
+
+/// Show the immediate callers and callees of some items, with their sizes.
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Neighbors {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The items whose callers and callees to show.
+    items: Vec<String>,
+
+    /// Whether or not `items` should be treated as regular expressions.
+    #[structopt(long = "regex")]
+    using_regexps: bool,
+
+    /// The maximum number of callers, and of callees, to display for each item.
+    #[structopt(short = "n", default_value = "10")]
+    max_neighbors: u32,
+
+    /// Ignore edges of the given kind, such as `type` or `data`. May be given
+    /// more than once.
+    #[structopt(long = "ignore-edges", raw(number_of_values = "1"))]
+    ignore_edges: Vec<String>,
+}
+
+impl Default for Neighbors {
+    fn default() -> Neighbors {
+        Neighbors {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
+
+            items: Default::default(),
+            using_regexps: false,
+            max_neighbors: 10,
+            ignore_edges: Default::default(),
+        }
+    }
+}
+
+impl Neighbors {
+    // TODO: wasm-bindgen does not support sending Vec<String> across
+    // the wasm ABI boundary yet.
+
+    /// The items whose callers and callees to show.
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// The kinds of edges to ignore.
+    pub fn ignore_edges(&self) -> &[String] {
+        &self.ignore_edges
+    }
+}
+
+#[wasm_bindgen]
+impl Neighbors {
+    /// Construct a new, default `Neighbors`.
+    pub fn new() -> Neighbors {
+        Neighbors::default()
+    }
+
+    /// Add an item whose callers and callees to show.
+    pub fn add_item(&mut self, item: String) {
+        self.items.push(item);
+    }
+
+    /// Whether or not `items` should be treated as regular expressions.
+    pub fn using_regexps(&self) -> bool {
+        self.using_regexps
+    }
+
+    /// Set whether or not `items` should be treated as regular expressions.
+    pub fn set_using_regexps(&mut self, using_regexps: bool) {
+        self.using_regexps = using_regexps;
+    }
+
+    /// The maximum number of callers, and of callees, to display for each item.
+    pub fn max_neighbors(&self) -> u32 {
+        self.max_neighbors
+    }
+
+    /// Set the maximum number of callers, and of callees, to display for each
+    /// item.
+    pub fn set_max_neighbors(&mut self, max: u32) {
+        self.max_neighbors = max;
+    }
+
+    /// Ignore edges of the given kind.
+    pub fn add_ignored_edge_kind(&mut self, kind: String) {
+        self.ignore_edges.push(kind);
+    }
 }
 
 /// List the generic function monomorphizations that are contributing to
@@ -331,6 +757,20 @@ pub struct Monos {
     #[structopt(parse(from_os_str))]
     input: path::PathBuf,
 
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
@@ -341,6 +781,14 @@ pub struct Monos {
     #[structopt(short = "f", long = "format", default_value = "text")]
     output_format: traits::OutputFormat,
 
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
     /// Hide individual monomorphizations and only show the generic functions.
     #[structopt(short = "g", long = "only-generics")]
     only_generics: bool,
@@ -353,6 +801,12 @@ pub struct Monos {
     /// generic function.
     #[structopt(short = "n", long = "max-monos", default_value = "10")]
     max_monos: u32,
+
+    /// How to group monomorphizations: by the `template` (generic function)
+    /// they instantiate, by each type `argument` they were instantiated with,
+    /// or by `both`.
+    #[structopt(long = "group-by", default_value = "template", raw(possible_values = r#"&["template", "argument", "both"]"#))]
+    group_by: String,
 }
 
 impl Default for Monos {
@@ -361,17 +815,34 @@ impl Default for Monos {
             #[cfg(feature = "cli")]
             input: Default::default(),
             #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
             output_destination: Default::default(),
             #[cfg(feature = "cli")]
             output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
 
             only_generics: false,
             max_generics: 10,
             max_monos: 10,
+            group_by: "template".to_string(),
         }
     }
 }
 
+impl Monos {
+    // TODO: wasm-bindgen does not support returning borrowed strings across
+    // the wasm ABI boundary yet.
+
+    /// How to group monomorphizations: `template`, `argument` or `both`.
+    pub fn group_by(&self) -> &str {
+        &self.group_by
+    }
+}
+
 #[wasm_bindgen]
 impl Monos {
     /// Construct a new, default `Monos`.
@@ -411,22 +882,37 @@ impl Monos {
     pub fn set_max_monos(&mut self, max: u32) {
         self.max_monos = max;
     }
+
+    /// Set how to group monomorphizations: `template`, `argument` or `both`.
+    pub fn set_group_by(&mut self, group_by: &str) {
+        self.group_by = group_by.to_string();
+    }
 }
 
-/// Diff the old and new versions of a binary to see what sizes changed.
+/// Find functions with identical, or nearly identical, bodies that could be
+/// deduplicated.
 #[derive(Clone, Debug)]
 #[derive(StructOpt)]
 #[wasm_bindgen]
-pub struct Diff {
-    /// The path to the old version of the input binary.
+pub struct Dups {
+    /// The path to the input binary to size profile.
     #[cfg(feature = "cli")]
     #[structopt(parse(from_os_str))]
-    old_input: path::PathBuf,
+    input: path::PathBuf,
 
-    /// The path to the new version of the input binary.
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
     #[cfg(feature = "cli")]
-    #[structopt(parse(from_os_str))]
-    new_input: path::PathBuf,
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
 
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
@@ -438,52 +924,116 @@ pub struct Diff {
     #[structopt(short = "f", long = "format", default_value = "text")]
     output_format: traits::OutputFormat,
 
-    /// The maximum number of items to display.
-    #[structopt(short = "n", default_value = "20")]
-    max_items: u32,
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// Also group wasm functions that only differ in which functions they
+    /// call, or in how their locals are numbered.
+    #[structopt(long = "near")]
+    near: bool,
+
+    /// The maximum number of groups of duplicates to list.
+    #[structopt(short = "n", long = "max-groups", default_value = "10")]
+    max_groups: u32,
+
+    /// The maximum number of functions to list in each group.
+    #[structopt(short = "m", long = "max-members", default_value = "10")]
+    max_members: u32,
 }
 
-impl Default for Diff {
-    fn default() -> Diff {
-        Diff {
+impl Default for Dups {
+    fn default() -> Dups {
+        Dups {
             #[cfg(feature = "cli")]
-            old_input: Default::default(),
+            input: Default::default(),
             #[cfg(feature = "cli")]
-            new_input: Default::default(),
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
             #[cfg(feature = "cli")]
             output_destination: Default::default(),
             #[cfg(feature = "cli")]
             output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
 
-            max_items: 20,
+            near: false,
+            max_groups: 10,
+            max_members: 10,
         }
     }
 }
 
 #[wasm_bindgen]
-impl Diff {
-    /// The maximum number of items to display.
-    pub fn max_items(&self) -> u32 {
-        self.max_items
+impl Dups {
+    /// Construct a new, default `Dups`.
+    pub fn new() -> Dups {
+        Dups::default()
     }
 
-    /// Set the maximum number of items to display.
-    pub fn set_max_items(&mut self, n: u32) {
-        self.max_items = n;
+    /// Whether to also group functions that only differ in which functions
+    /// they call, or in how their locals are numbered.
+    pub fn near(&self) -> bool {
+        self.near
+    }
+
+    /// The maximum number of groups of duplicates to list.
+    pub fn max_groups(&self) -> u32 {
+        self.max_groups
+    }
+
+    /// The maximum number of functions to list in each group.
+    pub fn max_members(&self) -> u32 {
+        self.max_members
+    }
+
+    /// Set whether to also group functions that only differ in which functions
+    /// they call, or in how their locals are numbered.
+    pub fn set_near(&mut self, near: bool) {
+        self.near = near;
+    }
+
+    /// Set the maximum number of groups of duplicates to list.
+    pub fn set_max_groups(&mut self, max: u32) {
+        self.max_groups = max;
+    }
+
+    /// Set the maximum number of functions to list in each group.
+    pub fn set_max_members(&mut self, max: u32) {
+        self.max_members = max;
     }
 }
 
-/// Find and display code and data that is not transitively referenced by any
-/// exports or public functions.
+/// Find the string literals in a binary's static data, such as panic messages
+/// and source file paths, and the items that refer to them.
 #[derive(Clone, Debug)]
 #[derive(StructOpt)]
 #[wasm_bindgen]
-pub struct Garbage {
+pub struct Strings {
     /// The path to the input binary to size profile.
     #[cfg(feature = "cli")]
     #[structopt(parse(from_os_str))]
     input: path::PathBuf,
 
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
@@ -494,40 +1044,946 @@ pub struct Garbage {
     #[structopt(short = "f", long = "format", default_value = "text")]
     output_format: traits::OutputFormat,
 
-    /// The maximum number of items to display.
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The maximum number of strings, and of duplicated strings, to list.
     #[structopt(short = "n", default_value = "10")]
-    max_items: u32,
+    max_strings: u32,
 }
 
-impl Default for Garbage {
-    fn default() -> Garbage {
-        Garbage {
+impl Default for Strings {
+    fn default() -> Strings {
+        Strings {
             #[cfg(feature = "cli")]
             input: Default::default(),
             #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
             output_destination: Default::default(),
             #[cfg(feature = "cli")]
             output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
 
-            max_items: 10,
+            max_strings: 10,
         }
     }
 }
 
 #[wasm_bindgen]
-impl Garbage {
-    /// Construct a new, default `Garbage`
-    pub fn new() -> Garbage {
-        Garbage::default()
+impl Strings {
+    /// Construct a new, default `Strings`.
+    pub fn new() -> Strings {
+        Strings::default()
     }
 
-    /// The maximum number of items to display.
-    pub fn max_items(&self) -> u32 {
-        self.max_items
+    /// The maximum number of strings, and of duplicated strings, to list.
+    pub fn max_strings(&self) -> u32 {
+        self.max_strings
     }
 
-    /// Set the maximum number of items to display.
-    pub fn set_max_items(&mut self, max: u32) {
-        self.max_items = max;
+    /// Set the maximum number of strings, and of duplicated strings, to list.
+    pub fn set_max_strings(&mut self, max: u32) {
+        self.max_strings = max;
+    }
+}
+
+/// Measure the well-known subsystems of the Rust standard library that bloat
+/// binaries, such as panicking and formatting, and what pulls them in.
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct RustBloat {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The maximum number of entry points to list for each subsystem.
+    #[structopt(short = "n", default_value = "5")]
+    max_entry_points: u32,
+}
+
+impl Default for RustBloat {
+    fn default() -> RustBloat {
+        RustBloat {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
+
+            max_entry_points: 5,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl RustBloat {
+    /// Construct a new, default `RustBloat`.
+    pub fn new() -> RustBloat {
+        RustBloat::default()
+    }
+
+    /// The maximum number of entry points to list for each subsystem.
+    pub fn max_entry_points(&self) -> u32 {
+        self.max_entry_points
+    }
+
+    /// Set the maximum number of entry points to list for each subsystem.
+    pub fn set_max_entry_points(&mut self, max: u32) {
+        self.max_entry_points = max;
+    }
+}
+
+/// Summarize the sizes of a binary's items: totals for each kind of item, and
+/// the distribution of function sizes.
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Stats {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The number of largest items to report the share of the binary of.
+    #[structopt(short = "n", default_value = "10")]
+    top: u32,
+
+    /// The size in bytes at or below which a function counts as tiny, and is
+    /// likely worth inlining.
+    #[structopt(long = "tiny", default_value = "8")]
+    tiny: u32,
+}
+
+impl Default for Stats {
+    fn default() -> Stats {
+        Stats {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
+
+            top: 10,
+            tiny: 8,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Stats {
+    /// Construct a new, default `Stats`.
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    /// The number of largest items to report the share of the binary of.
+    pub fn top(&self) -> u32 {
+        self.top
+    }
+
+    /// The size in bytes at or below which a function counts as tiny.
+    pub fn tiny(&self) -> u32 {
+        self.tiny
+    }
+
+    /// Set the number of largest items to report the share of the binary of.
+    pub fn set_top(&mut self, top: u32) {
+        self.top = top;
+    }
+
+    /// Set the size in bytes at or below which a function counts as tiny.
+    pub fn set_tiny(&mut self, tiny: u32) {
+        self.tiny = tiny;
+    }
+}
+
+/// Group items by the namespaces in their names, such as crates, modules and
+/// types, into a tree with the size of each namespace.
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Namespaces {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in: `text`, `json`, `csv`, or
+    /// `svg` for a treemap of the namespaces.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The maximum depth of namespaces to display.
+    #[structopt(short = "d")]
+    max_depth: Option<u32>,
+
+    /// The maximum number of rows, regardless of depth in the tree, to display.
+    #[structopt(short = "r")]
+    max_rows: Option<u32>,
+}
+
+impl Default for Namespaces {
+    fn default() -> Namespaces {
+        Namespaces {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
+
+            max_depth: None,
+            max_rows: None,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Namespaces {
+    /// Construct a new, default `Namespaces`.
+    pub fn new() -> Namespaces {
+        Namespaces::default()
+    }
+
+    /// The maximum depth of namespaces to display.
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth.unwrap_or(u32::MAX)
+    }
+
+    /// The maximum number of rows, regardless of depth in the tree, to display.
+    pub fn max_rows(&self) -> u32 {
+        self.max_rows.unwrap_or(u32::MAX)
+    }
+
+    /// Set the maximum depth of namespaces to display.
+    pub fn set_max_depth(&mut self, max_depth: u32) {
+        self.max_depth = Some(max_depth);
+    }
+
+    /// Set the maximum number of rows, regardless of depth in the tree, to display.
+    pub fn set_max_rows(&mut self, max_rows: u32) {
+        self.max_rows = Some(max_rows);
+    }
+}
+
+/// Diff the old and new versions of a binary to see what sizes changed.
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Diff {
+    /// The path to the old version of the input binary.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    old_input: path::PathBuf,
+
+    /// The path to the new version of the input binary.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    new_input: path::PathBuf,
+
+    /// The path to a source map for the old version of the input binary.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    old_source_map: Option<path::PathBuf>,
+
+    /// The path to a source map for the new version of the input binary.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "new-source-map", parse(from_os_str))]
+    new_source_map: Option<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The maximum number of items to display.
+    #[structopt(short = "n", default_value = "20")]
+    max_items: u32,
+}
+
+impl Default for Diff {
+    fn default() -> Diff {
+        Diff {
+            #[cfg(feature = "cli")]
+            old_input: Default::default(),
+            #[cfg(feature = "cli")]
+            new_input: Default::default(),
+            #[cfg(feature = "cli")]
+            old_source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            new_source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
+
+            max_items: 20,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Diff {
+    /// The maximum number of items to display.
+    pub fn max_items(&self) -> u32 {
+        self.max_items
+    }
+
+    /// Set the maximum number of items to display.
+    pub fn set_max_items(&mut self, n: u32) {
+        self.max_items = n;
+    }
+}
+
+/// Find and display code and data that is not transitively referenced by any
+/// exports or public functions.
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Garbage {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The maximum number of items to display.
+    #[structopt(short = "n", default_value = "10")]
+    max_items: u32,
+
+    /// Ignore edges of the given kind, such as `type` or `data`. May be given
+    /// more than once.
+    #[structopt(long = "ignore-edges", raw(number_of_values = "1"))]
+    ignore_edges: Vec<String>,
+
+    /// Analyze as if the items whose names match this regular expression, such
+    /// as `export "greet"`, were the only roots. May be given more than once.
+    #[structopt(long = "root", raw(number_of_values = "1"))]
+    roots: Vec<String>,
+
+    /// Stop treating the roots whose names match this regular expression, such
+    /// as debugging custom sections, as roots. May be given more than once.
+    #[structopt(long = "ignore-root", raw(number_of_values = "1"))]
+    ignore_roots: Vec<String>,
+}
+
+impl Default for Garbage {
+    fn default() -> Garbage {
+        Garbage {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
+
+            max_items: 10,
+            ignore_edges: Default::default(),
+            roots: Default::default(),
+            ignore_roots: Default::default(),
+        }
+    }
+}
+
+impl Garbage {
+    // TODO: wasm-bindgen does not support sending Vec<String> across
+    // the wasm ABI boundary yet.
+
+    /// The kinds of edges to ignore.
+    pub fn ignore_edges(&self) -> &[String] {
+        &self.ignore_edges
+    }
+
+    /// The regular expressions matching the items to use as roots, if any.
+    pub fn roots(&self) -> &[String] {
+        &self.roots
+    }
+
+    /// The regular expressions matching the roots to ignore.
+    pub fn ignore_roots(&self) -> &[String] {
+        &self.ignore_roots
+    }
+}
+
+#[wasm_bindgen]
+impl Garbage {
+    /// Construct a new, default `Garbage`
+    pub fn new() -> Garbage {
+        Garbage::default()
+    }
+
+    /// The maximum number of items to display.
+    pub fn max_items(&self) -> u32 {
+        self.max_items
+    }
+
+    /// Set the maximum number of items to display.
+    pub fn set_max_items(&mut self, max: u32) {
+        self.max_items = max;
+    }
+
+    /// Ignore edges of the given kind.
+    pub fn add_ignored_edge_kind(&mut self, kind: String) {
+        self.ignore_edges.push(kind);
+    }
+
+    /// Use the items whose names match the given regular expression as roots.
+    pub fn add_root(&mut self, regex: String) {
+        self.roots.push(regex);
+    }
+
+    /// Ignore the roots whose names match the given regular expression.
+    pub fn add_ignored_root(&mut self, regex: String) {
+        self.ignore_roots.push(regex);
+    }
+}
+
+/// Simulate removing some items, or cutting some edges, and report exactly
+/// which items would become garbage as a result.
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Whatif {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The items to remove, such as the exports that make up a feature.
+    items: Vec<String>,
+
+    /// Cut the edges from the items named by the first value to the items
+    /// named by the second, such as `--cut 'export "greet"' greet`. May be
+    /// given more than once.
+    #[structopt(long = "cut", raw(number_of_values = "2"), raw(value_names = r#"&["FROM", "TO"]"#))]
+    cut: Vec<String>,
+
+    /// Whether or not the names of the items to remove, and of the ends of the
+    /// edges to cut, should be treated as regular expressions.
+    #[structopt(long = "regex")]
+    using_regexps: bool,
+
+    /// The maximum number of freed items to display.
+    #[structopt(short = "n", default_value = "10")]
+    max_items: u32,
+
+    /// Ignore edges of the given kind, such as `type` or `data`. May be given
+    /// more than once.
+    #[structopt(long = "ignore-edges", raw(number_of_values = "1"))]
+    ignore_edges: Vec<String>,
+}
+
+impl Default for Whatif {
+    fn default() -> Whatif {
+        Whatif {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
+
+            items: Default::default(),
+            cut: Default::default(),
+            using_regexps: false,
+            max_items: 10,
+            ignore_edges: Default::default(),
+        }
+    }
+}
+
+impl Whatif {
+    // TODO: wasm-bindgen does not support sending Vec<String> across
+    // the wasm ABI boundary yet.
+
+    /// The items to remove.
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    /// The edges to cut, as pairs of the names of the items at either end.
+    pub fn cut(&self) -> Vec<(&str, &str)> {
+        self.cut
+            .chunks(2)
+            .filter(|pair| pair.len() == 2)
+            .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+            .collect()
+    }
+
+    /// The kinds of edges to ignore.
+    pub fn ignore_edges(&self) -> &[String] {
+        &self.ignore_edges
+    }
+}
+
+#[wasm_bindgen]
+impl Whatif {
+    /// Construct a new, default `Whatif`.
+    pub fn new() -> Whatif {
+        Whatif::default()
+    }
+
+    /// Add an item to remove.
+    pub fn add_item(&mut self, item: String) {
+        self.items.push(item);
+    }
+
+    /// Cut the edges from the items named `from` to the items named `to`.
+    pub fn add_cut(&mut self, from: String, to: String) {
+        self.cut.push(from);
+        self.cut.push(to);
+    }
+
+    /// Whether or not the item names should be treated as regular expressions.
+    pub fn using_regexps(&self) -> bool {
+        self.using_regexps
+    }
+
+    /// Set whether or not the item names should be treated as regular
+    /// expressions.
+    pub fn set_using_regexps(&mut self, using_regexps: bool) {
+        self.using_regexps = using_regexps;
+    }
+
+    /// The maximum number of freed items to display.
+    pub fn max_items(&self) -> u32 {
+        self.max_items
+    }
+
+    /// Set the maximum number of freed items to display.
+    pub fn set_max_items(&mut self, max: u32) {
+        self.max_items = max;
+    }
+
+    /// Ignore edges of the given kind.
+    pub fn add_ignored_edge_kind(&mut self, kind: String) {
+        self.ignore_edges.push(kind);
+    }
+}
+
+/// Find groups of mutually recursive items, which the dominator tree attributes
+/// to whichever member it reaches first.
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Cycles {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+
+    /// The maximum number of cycles to display.
+    #[structopt(short = "n", default_value = "10")]
+    max_cycles: u32,
+
+    /// The maximum number of members to display for each cycle.
+    #[structopt(short = "m", default_value = "10")]
+    max_members: u32,
+
+    /// Ignore edges of the given kind, such as `type` or `data`. May be given
+    /// more than once.
+    #[structopt(long = "ignore-edges", raw(number_of_values = "1"))]
+    ignore_edges: Vec<String>,
+}
+
+impl Default for Cycles {
+    fn default() -> Cycles {
+        Cycles {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            source_map: Default::default(),
+            #[cfg(feature = "cli")]
+            modules: Default::default(),
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            names: "demangled".to_string(),
+
+            max_cycles: 10,
+            max_members: 10,
+            ignore_edges: Default::default(),
+        }
+    }
+}
+
+impl Cycles {
+    // TODO: wasm-bindgen does not support sending Vec<String> across
+    // the wasm ABI boundary yet.
+
+    /// The kinds of edges to ignore.
+    pub fn ignore_edges(&self) -> &[String] {
+        &self.ignore_edges
+    }
+}
+
+#[wasm_bindgen]
+impl Cycles {
+    /// Construct a new, default `Cycles`.
+    pub fn new() -> Cycles {
+        Cycles::default()
+    }
+
+    /// The maximum number of cycles to display.
+    pub fn max_cycles(&self) -> u32 {
+        self.max_cycles
+    }
+
+    /// Set the maximum number of cycles to display.
+    pub fn set_max_cycles(&mut self, max: u32) {
+        self.max_cycles = max;
+    }
+
+    /// The maximum number of members to display for each cycle.
+    pub fn max_members(&self) -> u32 {
+        self.max_members
+    }
+
+    /// Set the maximum number of members to display for each cycle.
+    pub fn set_max_members(&mut self, max: u32) {
+        self.max_members = max;
+    }
+
+    /// Ignore edges of the given kind.
+    pub fn add_ignored_edge_kind(&mut self, kind: String) {
+        self.ignore_edges.push(kind);
+    }
+}
+
+/// Summarize a binary's sections, and the toolchain and target features used to
+/// produce it.
+#[derive(Clone, Debug, Default)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Info {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// How to display item names: `demangled`, `no-hash` (demangled, without
+    /// the hash at the end of Rust symbols), `raw` (as they appear in the
+    /// binary, such as mangled symbols) or `short` (only the last segment of
+    /// each path).
+    #[cfg(feature = "cli")]
+    #[structopt(long = "names", default_value = "demangled", raw(possible_values = r#"&["demangled", "no-hash", "raw", "short"]"#))]
+    names: String,
+}
+
+#[wasm_bindgen]
+impl Info {
+    /// Construct a new, default `Info`.
+    pub fn new() -> Info {
+        Info::default()
+    }
+}
+
+/// Save a snapshot of a binary's IR, which every other sub-command accepts in
+/// place of the binary.
+#[derive(Clone, Debug, Default)]
+#[derive(StructOpt)]
+#[wasm_bindgen]
+pub struct Snapshot {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The path to a source map for the input binary, used to attribute code
+    /// to source files. Defaults to the map named by the binary's
+    /// `sourceMappingURL` custom section, if any.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "source-map", parse(from_os_str))]
+    source_map: Option<path::PathBuf>,
+
+    /// Another module to link with the input, such as a wasm module that it
+    /// loads lazily. Imports are linked to the exports of the same name in the
+    /// other modules. May be given more than once.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "module", parse(from_os_str), raw(number_of_values = "1"))]
+    modules: Vec<path::PathBuf>,
+
+    /// The destination to write the snapshot to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+}
+
+#[wasm_bindgen]
+impl Snapshot {
+    /// Construct a new, default `Snapshot`.
+    pub fn new() -> Snapshot {
+        Snapshot::default()
     }
 }
@@ -36,11 +36,21 @@ cfg_if! {
             /// Get the input file path.
             fn input(&self) -> &path::Path;
 
+            /// Get the path to the input's source map, if one was given.
+            fn source_map(&self) -> Option<&path::Path>;
+
+            /// Get the paths of the other modules to link with the input.
+            fn modules(&self) -> &[path::PathBuf];
+
             /// Get the output destination.
             fn output_destination(&self) -> &OutputDestination;
 
             /// Get the output format.
             fn output_format(&self) -> traits::OutputFormat;
+
+            /// Get the mode to display item names in, such as `demangled` or
+            /// `raw`.
+            fn names(&self) -> &str;
         }
 
         impl CommonCliOptions for Options {
@@ -49,9 +59,61 @@ cfg_if! {
                     Options::Top(ref top) => top.input(),
                     Options::Dominators(ref doms) => doms.input(),
                     Options::Paths(ref paths) => paths.input(),
+                    Options::Neighbors(ref neighbors) => neighbors.input(),
                     Options::Monos(ref monos) => monos.input(),
+                    Options::Dups(ref dups) => dups.input(),
+                    Options::Strings(ref strings) => strings.input(),
+                    Options::RustBloat(ref bloat) => bloat.input(),
+                    Options::Stats(ref stats) => stats.input(),
+                    Options::Namespaces(ref namespaces) => namespaces.input(),
                     Options::Diff(ref diff) => diff.input(),
                     Options::Garbage(ref garbo) => garbo.input(),
+                    Options::Whatif(ref whatif) => whatif.input(),
+                    Options::Cycles(ref cycles) => cycles.input(),
+                    Options::Info(ref info) => info.input(),
+                    Options::Snapshot(ref snapshot) => snapshot.input(),
+                }
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                match *self {
+                    Options::Top(ref top) => top.source_map(),
+                    Options::Dominators(ref doms) => doms.source_map(),
+                    Options::Paths(ref paths) => paths.source_map(),
+                    Options::Neighbors(ref neighbors) => neighbors.source_map(),
+                    Options::Monos(ref monos) => monos.source_map(),
+                    Options::Dups(ref dups) => dups.source_map(),
+                    Options::Strings(ref strings) => strings.source_map(),
+                    Options::RustBloat(ref bloat) => bloat.source_map(),
+                    Options::Stats(ref stats) => stats.source_map(),
+                    Options::Namespaces(ref namespaces) => namespaces.source_map(),
+                    Options::Diff(ref diff) => diff.source_map(),
+                    Options::Garbage(ref garbo) => garbo.source_map(),
+                    Options::Whatif(ref whatif) => whatif.source_map(),
+                    Options::Cycles(ref cycles) => cycles.source_map(),
+                    Options::Info(ref info) => info.source_map(),
+                    Options::Snapshot(ref snapshot) => snapshot.source_map(),
+                }
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                match *self {
+                    Options::Top(ref top) => top.modules(),
+                    Options::Dominators(ref doms) => doms.modules(),
+                    Options::Paths(ref paths) => paths.modules(),
+                    Options::Neighbors(ref neighbors) => neighbors.modules(),
+                    Options::Monos(ref monos) => monos.modules(),
+                    Options::Dups(ref dups) => dups.modules(),
+                    Options::Strings(ref strings) => strings.modules(),
+                    Options::RustBloat(ref bloat) => bloat.modules(),
+                    Options::Stats(ref stats) => stats.modules(),
+                    Options::Namespaces(ref namespaces) => namespaces.modules(),
+                    Options::Diff(ref diff) => diff.modules(),
+                    Options::Garbage(ref garbo) => garbo.modules(),
+                    Options::Whatif(ref whatif) => whatif.modules(),
+                    Options::Cycles(ref cycles) => cycles.modules(),
+                    Options::Info(ref info) => info.modules(),
+                    Options::Snapshot(ref snapshot) => snapshot.modules(),
                 }
             }
 
@@ -60,9 +122,19 @@ cfg_if! {
                     Options::Top(ref top) => top.output_destination(),
                     Options::Dominators(ref doms) => doms.output_destination(),
                     Options::Paths(ref paths) => paths.output_destination(),
+                    Options::Neighbors(ref neighbors) => neighbors.output_destination(),
                     Options::Monos(ref monos) => monos.output_destination(),
+                    Options::Dups(ref dups) => dups.output_destination(),
+                    Options::Strings(ref strings) => strings.output_destination(),
+                    Options::RustBloat(ref bloat) => bloat.output_destination(),
+                    Options::Stats(ref stats) => stats.output_destination(),
+                    Options::Namespaces(ref namespaces) => namespaces.output_destination(),
                     Options::Diff(ref diff) => diff.output_destination(),
                     Options::Garbage(ref garbo) => garbo.output_destination(),
+                    Options::Whatif(ref whatif) => whatif.output_destination(),
+                    Options::Cycles(ref cycles) => cycles.output_destination(),
+                    Options::Info(ref info) => info.output_destination(),
+                    Options::Snapshot(ref snapshot) => snapshot.output_destination(),
                 }
             }
 
@@ -71,9 +143,40 @@ cfg_if! {
                     Options::Top(ref top) => top.output_format(),
                     Options::Dominators(ref doms) => doms.output_format(),
                     Options::Paths(ref paths) => paths.output_format(),
+                    Options::Neighbors(ref neighbors) => neighbors.output_format(),
                     Options::Monos(ref monos) => monos.output_format(),
+                    Options::Dups(ref dups) => dups.output_format(),
+                    Options::Strings(ref strings) => strings.output_format(),
+                    Options::RustBloat(ref bloat) => bloat.output_format(),
+                    Options::Stats(ref stats) => stats.output_format(),
+                    Options::Namespaces(ref namespaces) => namespaces.output_format(),
                     Options::Diff(ref diff) => diff.output_format(),
                     Options::Garbage(ref garbo) => garbo.output_format(),
+                    Options::Whatif(ref whatif) => whatif.output_format(),
+                    Options::Cycles(ref cycles) => cycles.output_format(),
+                    Options::Info(ref info) => info.output_format(),
+                    Options::Snapshot(ref snapshot) => snapshot.output_format(),
+                }
+            }
+
+            fn names(&self) -> &str {
+                match *self {
+                    Options::Top(ref top) => top.names(),
+                    Options::Dominators(ref doms) => doms.names(),
+                    Options::Paths(ref paths) => paths.names(),
+                    Options::Neighbors(ref neighbors) => neighbors.names(),
+                    Options::Monos(ref monos) => monos.names(),
+                    Options::Dups(ref dups) => dups.names(),
+                    Options::Strings(ref strings) => strings.names(),
+                    Options::RustBloat(ref bloat) => bloat.names(),
+                    Options::Stats(ref stats) => stats.names(),
+                    Options::Namespaces(ref namespaces) => namespaces.names(),
+                    Options::Diff(ref diff) => diff.names(),
+                    Options::Garbage(ref garbo) => garbo.names(),
+                    Options::Whatif(ref whatif) => whatif.names(),
+                    Options::Cycles(ref cycles) => cycles.names(),
+                    Options::Info(ref info) => info.names(),
+                    Options::Snapshot(ref snapshot) => snapshot.names(),
                 }
             }
         }
@@ -83,6 +186,14 @@ cfg_if! {
                 &self.input
             }
 
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -90,6 +201,10 @@ cfg_if! {
             fn output_format(&self) -> traits::OutputFormat {
                 self.output_format
             }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
         }
 
         impl CommonCliOptions for Dominators {
@@ -97,6 +212,14 @@ cfg_if! {
                 &self.input
             }
 
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -104,6 +227,10 @@ cfg_if! {
             fn output_format(&self) -> traits::OutputFormat {
                 self.output_format
             }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
         }
 
         impl CommonCliOptions for Paths {
@@ -111,6 +238,14 @@ cfg_if! {
                 &self.input
             }
 
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -118,6 +253,36 @@ cfg_if! {
             fn output_format(&self) -> traits::OutputFormat {
                 self.output_format
             }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Neighbors {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
         }
 
         impl CommonCliOptions for Monos {
@@ -125,6 +290,118 @@ cfg_if! {
                 &self.input
             }
 
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Dups {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Strings {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for RustBloat {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Stats {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -132,6 +409,36 @@ cfg_if! {
             fn output_format(&self) -> traits::OutputFormat {
                 self.output_format
             }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Namespaces {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
         }
 
         impl CommonCliOptions for Diff {
@@ -139,6 +446,16 @@ cfg_if! {
                 &self.old_input
             }
 
+            fn source_map(&self) -> Option<&path::Path> {
+                self.old_source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                // Linking the old and new versions with other modules isn't
+                // supported yet.
+                &[]
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -146,6 +463,10 @@ cfg_if! {
             fn output_format(&self) -> traits::OutputFormat {
                 self.output_format
             }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
         }
 
         impl Diff {
@@ -153,6 +474,12 @@ cfg_if! {
             pub fn new_input(&self) -> &path::Path {
                 &self.new_input
             }
+
+            /// The path to the new version of the input binary's source map, if
+            /// one was given.
+            pub fn new_source_map(&self) -> Option<&path::Path> {
+                self.new_source_map.as_ref().map(|p| p.as_path())
+            }
         }
 
         impl CommonCliOptions for Garbage {
@@ -160,6 +487,14 @@ cfg_if! {
                 &self.input
             }
 
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -167,6 +502,117 @@ cfg_if! {
             fn output_format(&self) -> traits::OutputFormat {
                 self.output_format
             }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Whatif {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Cycles {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Info {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+
+            fn names(&self) -> &str {
+                &self.names
+            }
+        }
+
+        impl CommonCliOptions for Snapshot {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn source_map(&self) -> Option<&path::Path> {
+                self.source_map.as_ref().map(|p| p.as_path())
+            }
+
+            fn modules(&self) -> &[path::PathBuf] {
+                &self.modules
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                // Snapshots are always JSON.
+                traits::OutputFormat::Json
+            }
+
+            fn names(&self) -> &str {
+                // Snapshots keep every item's raw name, whatever mode it is
+                // displayed in later.
+                "raw"
+            }
         }
 
         /// Where to output results.
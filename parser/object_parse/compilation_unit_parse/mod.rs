@@ -1,5 +1,6 @@
 use gimli;
 use ir;
+use std::collections::BTreeMap;
 use traits;
 
 use super::die_parse::DIEItemsExtra;
@@ -14,6 +15,7 @@ where
     pub debug_str: gimli::DebugStr<R>,
     pub debug_types: gimli::DebugTypes<R>,
     pub rnglists: &'input gimli::RangeLists<R>,
+    pub body_hashes: &'input BTreeMap<u64, u64>,
 }
 
 pub struct CompUnitEdgesExtra<R>
@@ -42,6 +44,7 @@ where
             debug_str,
             debug_types,
             rnglists,
+            body_hashes,
         } = extra;
 
         // Get the size of addresses in this type-unit, initialize an entry ID counter.
@@ -76,6 +79,7 @@ where
                 debug_str: &debug_str,
                 debug_types: &debug_types,
                 rnglists,
+                body_hashes,
                 comp_unit: self,
             };
             entry.parse_items(items, die_extra)?;
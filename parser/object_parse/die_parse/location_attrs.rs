@@ -34,6 +34,12 @@ impl<R: gimli::Reader> DieLocationAttributes<R> {
         })
     }
 
+    /// The address that the entity described by this DIE starts at, if it is
+    /// a definition.
+    pub fn low_pc(&self) -> FallilbleOption<u64> {
+        self.dw_at_low_pc()
+    }
+
     /// Compute the size of a subprogram described by this DIE.
     pub fn entity_size(
         &self,
@@ -1,5 +1,6 @@
 use gimli;
 use ir;
+use std::collections::BTreeMap;
 use traits;
 
 use super::Parse;
@@ -30,6 +31,7 @@ where
     pub debug_str: &'unit gimli::DebugStr<R>,
     pub debug_types: &'unit gimli::DebugTypes<R>,
     pub rnglists: &'unit gimli::RangeLists<R>,
+    pub body_hashes: &'unit BTreeMap<u64, u64>,
     pub comp_unit: &'unit gimli::CompilationUnitHeader<R, <R as gimli::Reader>::Offset>,
 }
 
@@ -53,17 +55,24 @@ where
             debug_str,
             debug_types,
             rnglists,
+            body_hashes,
             comp_unit,
         } = extra;
 
         let item = match item_kind(self, debug_types, comp_unit)? {
-            Some(kind @ ir::ItemKind::Subroutine(_)) => {
+            Some(ir::ItemKind::Subroutine(mut subroutine)) => {
                 let name = item_name(self, debug_str)?
                     .unwrap_or(format!("Subroutine[{}][{}]", unit_id, entry_id));
                 let id = ir::Id::entry(unit_id, entry_id);
-                DieLocationAttributes::try_from(self)?
+                let location = DieLocationAttributes::try_from(self)?;
+                if let Some(low_pc) = location.low_pc()? {
+                    if let Some(&hash) = body_hashes.get(&low_pc) {
+                        subroutine.set_body_hash(hash);
+                    }
+                }
+                location
                     .entity_size(addr_size, dwarf_version, rnglists)?
-                    .map(|size| ir::Item::new(id, name, size as u32, kind))
+                    .map(|size| ir::Item::new(id, name, size as u32, subroutine))
             }
             _ => None,
         };
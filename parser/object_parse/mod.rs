@@ -1,13 +1,16 @@
 use std::borrow::{Borrow, Cow};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::hash::Hasher;
 
 use fallible_iterator::FallibleIterator;
 use gimli;
 use ir;
-use object::{self, Object};
+use object::{self, Object, ObjectSection};
 use traits;
 use typed_arena::Arena;
 
-use super::Parse;
+use super::{is_printable, printable_strings, Fnv, Parse, MIN_STRING_LEN};
 
 mod compilation_unit_parse;
 mod die_parse;
@@ -33,6 +36,231 @@ where
     Sect::from(gimli::EndianSlice::new(data_ref, endian))
 }
 
+/// Hash the machine code of every function symbol, keyed by the symbol's
+/// address, so that subroutines with identical bodies can be found.
+fn symbol_body_hashes(file: &object::File) -> BTreeMap<u64, u64> {
+    let code: Vec<(u64, Cow<[u8]>)> = file
+        .sections()
+        .filter(|section| section.kind() == object::SectionKind::Text)
+        .map(|section| (section.address(), section.data()))
+        .collect();
+
+    let mut hashes = BTreeMap::new();
+    for symbol in file.symbols() {
+        if symbol.kind() != object::SymbolKind::Text || symbol.is_undefined() {
+            continue;
+        }
+        let start = symbol.address();
+        let end = start + symbol.size();
+        let body = code
+            .iter()
+            .find(|&&(address, ref data)| {
+                address <= start && start < end && end <= address + data.len() as u64
+            })
+            .map(|&(address, ref data)| {
+                &data[(start - address) as usize..(end - address) as usize]
+            });
+        if let Some(body) = body {
+            let mut hash = Fnv::default();
+            hash.write(body);
+            hashes.insert(start, hash.finish());
+        }
+    }
+    hashes
+}
+
+/// Whether a section holds the read-only data that string literals are kept
+/// in: `.rodata` in ELF, `.rdata` in PE, or `__cstring` and `__const` in Mach-O.
+fn is_read_only_data(name: &str) -> bool {
+    match name {
+        ".rodata" | ".rdata" | "__cstring" | "__const" => true,
+        _ => name.starts_with(".rodata."),
+    }
+}
+
+/// Find the `&str` constants, such as panic messages and the pieces of format
+/// strings, in a 64-bit executable or shared library, as the ranges of bytes
+/// that they point at. Each is a pointer, fixed up by a relative dynamic
+/// relocation, followed by a length. Pointers to C strings are followed by
+/// anything but a length that fits in the read-only data, so they are left out.
+fn str_constants(file: &object::File) -> Vec<(u64, u64)> {
+    let relative = match file.machine() {
+        object::Machine::X86_64 => 8,
+        object::Machine::Arm64 => 1027,
+        _ => return vec![],
+    };
+    let read_u64 = |bytes: &[u8]| {
+        let mut value = [0; 8];
+        value.copy_from_slice(&bytes[..8]);
+        if file.is_little_endian() {
+            u64::from_le_bytes(value)
+        } else {
+            u64::from_be_bytes(value)
+        }
+    };
+
+    let relocations = match file.section_data_by_name(".rela.dyn") {
+        Some(data) => data,
+        None => return vec![],
+    };
+    let sections: Vec<(u64, Cow<[u8]>)> = file
+        .sections()
+        .map(|section| (section.address(), section.data()))
+        .collect();
+    let read_only: Vec<(u64, u64)> = file
+        .sections()
+        .filter(|section| section.name().map_or(false, is_read_only_data))
+        .map(|section| (section.address(), section.address() + section.size()))
+        .collect();
+
+    // Each entry is an offset, an info word holding the relocation's type in
+    // its low half, and an addend, which is the target of relative ones.
+    let mut constants = vec![];
+    for entry in relocations.chunks_exact(24) {
+        if read_u64(&entry[8..]) & 0xffff_ffff != relative {
+            continue;
+        }
+        let (offset, target) = (read_u64(entry), read_u64(&entry[16..]));
+        let len = sections.iter().find_map(|&(address, ref data)| {
+            let at = offset.checked_add(8)?.checked_sub(address)? as usize;
+            data.get(at..at + 8).map(read_u64)
+        });
+        let len = match len {
+            Some(len) if len > 0 => len,
+            _ => continue,
+        };
+        let fits = read_only
+            .iter()
+            .any(|&(start, end)| start <= target && target.saturating_add(len) <= end);
+        if fits {
+            constants.push((target, target + len));
+        }
+    }
+    constants.sort();
+    constants
+}
+
+/// Find the string literals in a read-only data section at the given address.
+/// Unlike in wasm, literals that are laid out back to back can't be told apart
+/// by the offsets that code refers to them at, so each run of printable
+/// characters is split where a data symbol starts or ends, and around each
+/// `&str` constant that lines up with the rest: one that starts where the run
+/// or another piece of it does, or ends where one does or another constant
+/// starts. Other constants are more likely pointers to the tail of a C string
+/// that the linker merged with a longer one, followed by some number that
+/// isn't a length. Data symbols that hold anything but text, such as
+/// lookup tables, are skipped, since printable bytes in them are only there by
+/// chance.
+///
+/// Literals that code refers to directly, without a symbol or `&str` constant,
+/// can't be told apart from the ones next to them, and are found as one.
+fn read_only_strings<'a>(
+    file: &object::File,
+    constants: &[(u64, u64)],
+    address: u64,
+    data: &'a [u8],
+) -> Vec<(u64, &'a str)> {
+    let end = address + data.len() as u64;
+    let mut boundaries = BTreeSet::new();
+    let mut tables = vec![];
+    for symbol in file.symbols() {
+        let start = symbol.address();
+        if symbol.kind() != object::SymbolKind::Data
+            || symbol.size() == 0
+            || start < address
+            || start + symbol.size() > end
+        {
+            continue;
+        }
+        let contents = &data[(start - address) as usize..][..symbol.size() as usize];
+        let text = match contents.iter().rposition(|&b| b != 0) {
+            Some(last) => &contents[..=last],
+            None => &[][..],
+        };
+        let is_text =
+            ::std::str::from_utf8(text).map_or(false, |text| text.chars().all(is_printable));
+        if !is_text {
+            tables.push(start..start + symbol.size());
+        }
+        boundaries.insert(start);
+        boundaries.insert(start + symbol.size());
+    }
+
+    let mut strings = vec![];
+    for (offset, run) in printable_strings(data) {
+        let run_start = address + offset as u64;
+        let run_end = run_start + run.len() as u64;
+        let mut known: BTreeSet<u64> = boundaries.range(run_start..run_end).cloned().collect();
+        known.insert(run_start);
+        known.insert(run_end);
+        let in_run: Vec<_> = constants
+            .iter()
+            .filter(|&&(start, end)| run_start <= start && end <= run_end)
+            .collect();
+        let starts: BTreeSet<u64> = in_run.iter().map(|&&(start, _)| start).collect();
+        loop {
+            let before = known.len();
+            for &&(start, end) in &in_run {
+                if known.contains(&start) || known.contains(&end) || starts.contains(&end) {
+                    known.insert(start);
+                    known.insert(end);
+                }
+            }
+            if known.len() == before {
+                break;
+            }
+        }
+        known.remove(&run_end);
+        let mut cuts: Vec<usize> = known
+            .into_iter()
+            .map(|boundary| (boundary - run_start) as usize)
+            .filter(|&cut| run.is_char_boundary(cut))
+            .collect();
+        cuts.push(run.len());
+        for piece in cuts.windows(2) {
+            let start = run_start + piece[0] as u64;
+            let in_table = tables
+                .iter()
+                .any(|table| table.start <= start && start < table.end);
+            if piece[1] - piece[0] >= MIN_STRING_LEN && !in_table {
+                strings.push((start, &run[piece[0]..piece[1]]));
+            }
+        }
+    }
+    strings
+}
+
+/// Add an item for each read-only data section, along with the string
+/// literals found in it. Native code refers to these strings through
+/// instruction operands, which aren't decoded, so none of them are attributed
+/// to the functions that use them.
+fn parse_read_only_data(
+    file: &object::File,
+    items: &mut ir::ItemsBuilder,
+) -> Result<(), traits::Error> {
+    let constants = str_constants(file);
+    for (idx, section) in file.sections().enumerate() {
+        let name = match section.name() {
+            Some(name) if is_read_only_data(name) => name,
+            _ => continue,
+        };
+        let data = section.data();
+        let id = ir::Id::section(idx);
+        items.add_item(ir::Item::new(
+            id,
+            name,
+            data.len() as u32,
+            ir::Data::new(None),
+        ));
+
+        for (address, string) in read_only_strings(file, &constants, section.address(), &data) {
+            let address = u32::try_from(address).ok();
+            items.add_string(ir::StringLiteral::new(id, address, string));
+        }
+    }
+    Ok(())
+}
+
 impl<'input> Parse<'input> for object::File<'input> {
     type ItemsExtra = ();
 
@@ -58,6 +286,7 @@ impl<'input> Parse<'input> for object::File<'input> {
         let debug_types: gimli::DebugTypes<_> = load_section(&arena, self, endian);
 
         let rnglists = &gimli::RangeLists::new(debug_ranges, debug_rnglists)?;
+        let body_hashes = &symbol_body_hashes(self);
 
         // Load the `.debug_info` section, and parse the items in each compilation unit.
         let debug_info: gimli::DebugInfo<_> = load_section(&arena, self, endian);
@@ -69,11 +298,12 @@ impl<'input> Parse<'input> for object::File<'input> {
                 debug_str,
                 debug_types,
                 rnglists,
+                body_hashes,
             };
             unit.parse_items(items, extra)?
         }
 
-        Ok(())
+        parse_read_only_data(self, items)
     }
 
     type EdgesExtra = ();
@@ -6,8 +6,11 @@
 extern crate fallible_iterator;
 extern crate gimli;
 extern crate object;
-extern crate parity_wasm;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 extern crate typed_arena;
+extern crate wasmparser;
 
 extern crate twiggy_ir as ir;
 extern crate twiggy_traits as traits;
@@ -15,29 +18,142 @@ extern crate twiggy_traits as traits;
 mod object_parse;
 mod wasm_parse;
 
-use parity_wasm::elements;
 use std::fs;
+use std::hash::Hasher;
 use std::io::Read;
 use std::path;
 
-/// Parse the file at the given path into IR items.
-pub fn read_and_parse<P: AsRef<path::Path>>(path: P) -> Result<ir::Items, traits::Error> {
+/// Parse the file at the given path into IR items. The file may also be a
+/// snapshot written by `ir::Items::write_snapshot`.
+///
+/// For wasm binaries, code items are attributed to their original source files
+/// using a source map. If `source_map` is `None`, the map named by the
+/// binary's `sourceMappingURL` custom section is used, when there is one.
+/// Snapshots already contain any source locations, so `source_map` is ignored
+/// for them.
+pub fn read_and_parse<P: AsRef<path::Path>>(
+    path: P,
+    source_map: Option<&path::Path>,
+) -> Result<ir::Items, traits::Error> {
     let path = path.as_ref();
+    let data = read_file(path)?;
+
+    if is_snapshot(&data) {
+        return Ok(ir::Items::read_snapshot(&data)?);
+    }
+
+    Ok(parse_module(path, &data, source_map)?.finish())
+}
+
+/// Parse the files at the given paths, such as a wasm module and the modules it
+/// loads lazily, into one graph of IR items, linking each module's imports to
+/// the other modules' exports of the same name. See `ir::ItemsBuilder::link`.
+///
+/// The first path is the entry point, and `source_map` is only used for it.
+/// Snapshots can't be linked with other modules, but a single path may be a
+/// snapshot, as with `read_and_parse`.
+pub fn read_and_parse_modules<P: AsRef<path::Path>>(
+    paths: &[P],
+    source_map: Option<&path::Path>,
+) -> Result<ir::Items, traits::Error> {
+    if paths.len() == 1 {
+        return read_and_parse(&paths[0], source_map);
+    }
+
+    let mut modules = Vec::with_capacity(paths.len());
+    for (i, path) in paths.iter().enumerate() {
+        let path = path.as_ref();
+        let data = read_file(path)?;
+        if is_snapshot(&data) {
+            return Err(traits::Error::with_msg(format!(
+                "{} is a snapshot, which can't be linked with other modules",
+                path.display()
+            )));
+        }
+
+        let source_map = if i == 0 { source_map } else { None };
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        modules.push((name, parse_module(path, &data, source_map)?));
+    }
+
+    Ok(ir::ItemsBuilder::link(modules).finish())
+}
+
+/// Parse a single binary, choosing the parser by its file extension.
+fn parse_module(
+    path: &path::Path,
+    data: &[u8],
+    source_map: Option<&path::Path>,
+) -> Result<ir::ItemsBuilder, traits::Error> {
+    match path.extension().and_then(|s| s.to_str()) {
+        Some("wasm") => {
+            let mut warnings = vec![];
+            let source_map = match source_map {
+                Some(map_path) => Some(wasm_parse::SourceMap::parse(&read_file(map_path)?)?),
+                None => discover_source_map(path, data, &mut warnings),
+            };
+            parse_wasm(data, source_map.as_ref(), warnings)
+        }
+        _ => parse_other(data),
+    }
+}
+
+fn read_file(path: &path::Path) -> Result<Vec<u8>, traits::Error> {
     let mut file = fs::File::open(path)?;
     let mut data = vec![];
     file.read_to_end(&mut data)?;
+    Ok(data)
+}
 
-    match path.extension().and_then(|s| s.to_str()) {
-        Some("wasm") => parse_wasm(&data),
-        _ => parse_other(&data),
+/// Find and parse the source map named by the wasm binary's `sourceMappingURL`
+/// custom section. Only relative URLs are supported, which are resolved against
+/// the binary's directory. Since the user didn't ask for this map, problems
+/// with it are only warnings.
+fn discover_source_map(
+    path: &path::Path,
+    data: &[u8],
+    warnings: &mut Vec<String>,
+) -> Option<wasm_parse::SourceMap> {
+    let url = wasm_parse::source_mapping_url(data)?;
+    if url.contains("://") {
+        return None;
+    }
+
+    let map_path = path
+        .parent()
+        .unwrap_or_else(|| path::Path::new(""))
+        .join(&url);
+    match read_file(&map_path).and_then(|map| wasm_parse::SourceMap::parse(&map)) {
+        Ok(map) => Some(map),
+        Err(e) => {
+            warnings.push(format!(
+                "could not read the source map at {}: {}",
+                map_path.display(),
+                e
+            ));
+            None
+        }
     }
 }
 
-/// Parse the given data into IR items.
+/// Parse the given data into IR items. The data may also be a snapshot written
+/// by `ir::Items::write_snapshot`.
 pub fn parse(data: &[u8]) -> Result<ir::Items, traits::Error> {
+    if is_snapshot(data) {
+        return Ok(ir::Items::read_snapshot(data)?);
+    }
     parse_fallback(data)
 }
 
+/// Snapshots are JSON objects, while every binary format we support starts
+/// with some magic bytes.
+fn is_snapshot(data: &[u8]) -> bool {
+    data.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{')
+}
+
 /// A trait for parsing things into `ir::Item`s.
 pub(crate) trait Parse<'a> {
     /// Any extra data needed to parse this type's items.
@@ -62,23 +178,122 @@ pub(crate) trait Parse<'a> {
     ) -> Result<(), traits::Error>;
 }
 
-fn parse_wasm(data: &[u8]) -> Result<ir::Items, traits::Error> {
-    let mut items = ir::ItemsBuilder::new(data.len() as u32);
+/// The 64-bit FNV-1a hash, which unlike the standard library's hasher is
+/// guaranteed to be the same everywhere, so hashes can be saved in snapshots.
+#[derive(Debug)]
+struct Fnv(u64);
 
-    let module: elements::Module = elements::deserialize_buffer(data)?;
+impl Default for Fnv {
+    fn default() -> Fnv {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
 
-    // Greedily parse the name section, if it exists.
-    let module = match module.parse_names() {
-        Ok(m) | Err((_, m)) => m,
-    };
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
 
-    module.parse_items(&mut items, ())?;
-    module.parse_edges(&mut items, ())?;
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// The fewest bytes that a run of printable characters must have to be taken
+/// for a string literal, as with the `strings` utility.
+const MIN_STRING_LEN: usize = 4;
+
+/// Whether a character can be part of a string literal.
+fn is_printable(c: char) -> bool {
+    !c.is_control() || c == '\n' || c == '\t'
+}
 
-    Ok(items.finish())
+/// Find the runs of printable UTF-8 in some static data, along with the byte
+/// offset that each starts at.
+fn printable_strings(data: &[u8]) -> Vec<(usize, &str)> {
+    let mut strings = vec![];
+    let mut start = 0;
+    let mut i = 0;
+    while i < data.len() {
+        let width = match data[i] {
+            0x00..=0x7f => 1,
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => 0,
+        };
+        let printable = width > 0
+            && data.len() - i >= width
+            && ::std::str::from_utf8(&data[i..i + width])
+                .ok()
+                .and_then(|c| c.chars().next())
+                .map_or(false, is_printable);
+        if printable {
+            i += width;
+            continue;
+        }
+
+        if i - start >= MIN_STRING_LEN {
+            // Every byte was checked to be part of a valid character.
+            strings.push((start, ::std::str::from_utf8(&data[start..i]).unwrap()));
+        }
+        i += 1;
+        start = i;
+    }
+    if i - start >= MIN_STRING_LEN {
+        strings.push((start, ::std::str::from_utf8(&data[start..i]).unwrap()));
+    }
+    strings
+}
+
+fn parse_wasm(
+    data: &[u8],
+    source_map: Option<&wasm_parse::SourceMap>,
+    warnings: Vec<String>,
+) -> Result<ir::ItemsBuilder, traits::Error> {
+    let mut items = ir::ItemsBuilder::new(data.len() as u32);
+    for warning in &warnings {
+        items.add_warning(warning.as_str());
+    }
+
+    let parsed = wasm_parse::Module::parse(data).and_then(|module| {
+        module.parse_items(&mut items, source_map)?;
+        module.parse_edges(&mut items, ())
+    });
+
+    match parsed {
+        Ok(()) => Ok(items),
+        Err(e) => {
+            // Sections that we can't decode are reported as a whole by
+            // `Module::parse`, so this is a module whose sections can't even
+            // be told apart, or whose imports we can't make sense of. Start
+            // over, and report what we can by walking its raw section headers.
+            // If even that fails, it probably isn't wasm at all, so report the
+            // original error. Anything other than a decoding error is a real
+            // failure, and must not be hidden behind the fallback.
+            if !e.is_wasm_decode_error() {
+                return Err(e);
+            }
+            let mut items = ir::ItemsBuilder::new(data.len() as u32);
+            for warning in warnings {
+                items.add_warning(warning);
+            }
+            items.add_warning(format!(
+                "could not fully parse the wasm module, only reporting its sections: {}",
+                e
+            ));
+            if wasm_parse::parse_raw_sections(data, &mut items).is_err() {
+                return Err(e);
+            }
+            Ok(items)
+        }
+    }
 }
 
-fn parse_other(data: &[u8]) -> Result<ir::Items, traits::Error> {
+fn parse_other(data: &[u8]) -> Result<ir::ItemsBuilder, traits::Error> {
     let mut items = ir::ItemsBuilder::new(data.len() as u32);
 
     let file: object::File = object::File::parse(data)?;
@@ -86,9 +301,9 @@ fn parse_other(data: &[u8]) -> Result<ir::Items, traits::Error> {
     file.parse_items(&mut items, ())?;
     file.parse_edges(&mut items, ())?;
 
-    Ok(items.finish())
+    Ok(items)
 }
 
 fn parse_fallback(data: &[u8]) -> Result<ir::Items, traits::Error> {
-    parse_wasm(data)
+    parse_wasm(data, None, vec![]).map(ir::ItemsBuilder::finish)
 }
@@ -1,56 +1,592 @@
-use super::Parse;
+mod source_map;
+
+pub(crate) use self::source_map::SourceMap;
+
+use super::{printable_strings, Fnv, Parse};
 use ir::{self, Id};
-use parity_wasm::elements::{self, Section};
+use std::collections::BTreeMap;
 use std::fmt::Write;
+use std::hash::Hasher;
 use traits;
+use wasmparser::{self, Operator};
+
+/// A section of a wasm module, as split out by `Module::parse`.
+#[derive(Clone)]
+pub(crate) enum Section<'a> {
+    Custom(CustomSection<'a>),
+    Name(wasmparser::NameSectionReader<'a>),
+    Type(wasmparser::TypeSectionReader<'a>),
+    Import(wasmparser::ImportSectionReader<'a>),
+    Function(wasmparser::FunctionSectionReader<'a>),
+    Table(wasmparser::TableSectionReader<'a>),
+    Memory(wasmparser::MemorySectionReader<'a>),
+    Tag(wasmparser::TagSectionReader<'a>),
+    Global(wasmparser::GlobalSectionReader<'a>),
+    Export(wasmparser::ExportSectionReader<'a>),
+    Start(StartSection),
+    Element(wasmparser::ElementSectionReader<'a>),
+    DataCount(DataCountSection),
+    Code(wasmparser::CodeSectionReader<'a>),
+    Data(wasmparser::DataSectionReader<'a>),
+    Unknown(u8),
+    /// A section of the given kind that uses something we can't decode, and is
+    /// only reported as a whole.
+    Undecoded(String),
+}
+
+impl<'a> Section<'a> {
+    /// The name of this kind of section, as used in the wasm spec.
+    fn name(&self) -> String {
+        let name = match *self {
+            Section::Custom(ref custom) => return format!("custom '{}'", custom.reader.name()),
+            Section::Name(_) => "custom 'name'",
+            Section::Type(_) => "type",
+            Section::Import(_) => "import",
+            Section::Function(_) => "function",
+            Section::Table(_) => "table",
+            Section::Memory(_) => "memory",
+            Section::Tag(_) => "tag",
+            Section::Global(_) => "global",
+            Section::Export(_) => "export",
+            Section::Start(_) => "start",
+            Section::Element(_) => "element",
+            Section::DataCount(_) => "data count",
+            Section::Code(_) => "code",
+            Section::Data(_) => "data",
+            Section::Unknown(_) => "unknown",
+            Section::Undecoded(ref name) => return name.clone(),
+        };
+        name.to_string()
+    }
+}
+
+/// A wasm module's sections, in the order they appear in the binary.
+pub(crate) struct Module<'a> {
+    sections: Vec<Section<'a>>,
+    // The size of each section, including its header.
+    section_sizes: Vec<u32>,
+    // Why each section that is only reported as a whole couldn't be decoded.
+    warnings: Vec<String>,
+}
 
-fn serialized_size<T>(t: T) -> Result<u32, traits::Error>
+impl<'a> Module<'a> {
+    /// Split the given wasm binary up into its sections. Sections that use
+    /// something we can't decode are kept as `Section::Undecoded`, except for
+    /// the import section, without which no other section can be understood.
+    pub fn parse(data: &'a [u8]) -> Result<Module<'a>, traits::Error> {
+        use wasmparser::Payload;
+
+        let mut sections = vec![];
+        let mut section_sizes = vec![];
+        let mut warnings = vec![];
+        let mut section_start = 0;
+
+        for payload in wasmparser::Parser::new(0).parse_all(data) {
+            let payload = payload?;
+            let section_end = payload.as_section().map(|(_, range)| range.end);
+
+            let section = match payload {
+                Payload::Version {
+                    encoding, range, ..
+                } => {
+                    if encoding != wasmparser::Encoding::Module {
+                        return Err(traits::Error::with_msg("wasm components are not supported"));
+                    }
+                    section_start = range.end;
+                    continue;
+                }
+                Payload::End(_) => break,
+                // Individual function bodies are read back out of the code
+                // section as a whole.
+                Payload::CodeSectionEntry(_) => continue,
+                Payload::CustomSection(custom) => {
+                    let names =
+                        wasmparser::NameSectionReader::new(custom.data(), custom.data_offset());
+                    if custom.name() == "name" && decode_names(&names).is_ok() {
+                        Section::Name(names)
+                    } else {
+                        if custom.name() == "name" {
+                            warnings.push("the \"name\" section is malformed".to_string());
+                        }
+                        // The whole section, like every other section.
+                        let size = (custom.range().end - section_start) as u32;
+                        Section::Custom(CustomSection {
+                            reader: custom,
+                            size,
+                        })
+                    }
+                }
+                Payload::TypeSection(ty) => Section::Type(ty),
+                Payload::ImportSection(imports) => Section::Import(imports),
+                Payload::FunctionSection(funcs) => Section::Function(funcs),
+                Payload::TableSection(table) => Section::Table(table),
+                Payload::MemorySection(mem) => Section::Memory(mem),
+                Payload::TagSection(tags) => Section::Tag(tags),
+                Payload::GlobalSection(global) => Section::Global(global),
+                Payload::ExportSection(exports) => Section::Export(exports),
+                Payload::StartSection { func, range } => {
+                    let size = (range.end - section_start) as u32;
+                    Section::Start(StartSection { func, size })
+                }
+                Payload::ElementSection(elem) => Section::Element(elem),
+                Payload::DataCountSection { range, .. } => {
+                    let size = (range.end - section_start) as u32;
+                    Section::DataCount(DataCountSection { size })
+                }
+                Payload::CodeSectionStart { range, .. } => {
+                    let code =
+                        wasmparser::CodeSectionReader::new(&data[range.clone()], range.start)?;
+                    Section::Code(code)
+                }
+                Payload::DataSection(data) => Section::Data(data),
+                Payload::UnknownSection { id, .. } => {
+                    warnings.push(format!("section {} has unknown id {}", sections.len(), id));
+                    Section::Unknown(id)
+                }
+                _ => return Err(traits::Error::with_msg("wasm components are not supported")),
+            };
+
+            let section = match decode_section(&section) {
+                Ok(()) => section,
+                Err(e) => {
+                    if let Section::Import(_) = section {
+                        return Err(e);
+                    }
+                    warnings.push(format!(
+                        "could not decode section {} ({}), only reporting its size: {}",
+                        sections.len(),
+                        section.name(),
+                        e
+                    ));
+                    Section::Undecoded(section.name())
+                }
+            };
+
+            let section_end = section_end.unwrap_or(section_start);
+            sections.push(section);
+            section_sizes.push((section_end - section_start) as u32);
+            section_start = section_end;
+        }
+
+        Ok(Module {
+            sections,
+            section_sizes,
+            warnings,
+        })
+    }
+
+    /// The module's sections, in order.
+    pub fn sections(&self) -> &[Section<'a>] {
+        &self.sections
+    }
+
+    /// The number of imports of the given kind.
+    pub fn import_count(&self, kind: wasmparser::ExternalKind) -> Result<usize, traits::Error> {
+        let mut count = 0;
+        for section in self.sections() {
+            if let Section::Import(ref imports) = *section {
+                for imp in imports.clone() {
+                    let matches = match (imp?.ty, kind) {
+                        (wasmparser::TypeRef::Func(_), wasmparser::ExternalKind::Func)
+                        | (wasmparser::TypeRef::Table(_), wasmparser::ExternalKind::Table)
+                        | (wasmparser::TypeRef::Memory(_), wasmparser::ExternalKind::Memory)
+                        | (wasmparser::TypeRef::Global(_), wasmparser::ExternalKind::Global)
+                        | (wasmparser::TypeRef::Tag(_), wasmparser::ExternalKind::Tag) => true,
+                        _ => false,
+                    };
+                    if matches {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Decode everything in a section, without adding anything to the IR, to find
+/// out whether it can be parsed before parsing any of it.
+fn decode_section(section: &Section) -> Result<(), traits::Error> {
+    match *section {
+        Section::Name(ref names) => decode_names(names)?,
+        Section::Type(ref ty) => decode_all(ty.clone())?,
+        Section::Import(ref imports) => decode_all(imports.clone())?,
+        Section::Function(ref funcs) => decode_all(funcs.clone())?,
+        Section::Table(ref tables) => {
+            for table in tables.clone() {
+                if let wasmparser::TableInit::Expr(ref expr) = table?.init {
+                    decode_const_expr(expr)?;
+                }
+            }
+        }
+        Section::Memory(ref mem) => decode_all(mem.clone())?,
+        Section::Tag(ref tags) => decode_all(tags.clone())?,
+        Section::Global(ref globals) => {
+            for global in globals.clone() {
+                decode_const_expr(&global?.init_expr)?;
+            }
+        }
+        Section::Export(ref exports) => decode_all(exports.clone())?,
+        Section::Element(ref elems) => {
+            for elem in elems.clone() {
+                let elem = elem?;
+                if let wasmparser::ElementKind::Active {
+                    ref offset_expr, ..
+                } = elem.kind
+                {
+                    decode_const_expr(offset_expr)?;
+                }
+                match elem.items {
+                    wasmparser::ElementItems::Functions(funcs) => decode_all(funcs)?,
+                    wasmparser::ElementItems::Expressions(_, exprs) => {
+                        for expr in exprs {
+                            decode_const_expr(&expr?)?;
+                        }
+                    }
+                }
+            }
+        }
+        Section::Code(ref code) => {
+            for body in code.clone() {
+                let body = body?;
+                decode_all(body.get_locals_reader()?)?;
+                decode_all(body.get_operators_reader()?)?;
+            }
+        }
+        Section::Data(ref data) => {
+            for d in data.clone() {
+                if let wasmparser::DataKind::Active {
+                    ref offset_expr, ..
+                } = d?.kind
+                {
+                    decode_const_expr(offset_expr)?;
+                }
+            }
+        }
+        Section::Custom(_)
+        | Section::Start(_)
+        | Section::DataCount(_)
+        | Section::Unknown(_)
+        | Section::Undecoded(_) => {}
+    }
+    Ok(())
+}
+
+/// Decode every subsection of a name section, and every name in them.
+fn decode_names(names: &wasmparser::NameSectionReader) -> Result<(), traits::Error> {
+    use wasmparser::Name;
+
+    for subsection in names.clone() {
+        match subsection? {
+            Name::Function(map)
+            | Name::Type(map)
+            | Name::Table(map)
+            | Name::Memory(map)
+            | Name::Global(map)
+            | Name::Element(map)
+            | Name::Data(map) => decode_all(map)?,
+            Name::Local(map) | Name::Label(map) => {
+                for naming in map {
+                    decode_all(naming?.names)?;
+                }
+            }
+            Name::Module { .. } | Name::Unknown { .. } => {}
+        }
+    }
+    Ok(())
+}
+
+/// Decode every operator of a constant expression.
+fn decode_const_expr(expr: &wasmparser::ConstExpr) -> Result<(), traits::Error> {
+    decode_all(expr.get_operators_reader())
+}
+
+/// Decode every entry that the given reader yields.
+fn decode_all<I, T>(entries: I) -> Result<(), traits::Error>
+where
+    I: IntoIterator<Item = wasmparser::Result<T>>,
+{
+    for entry in entries {
+        entry?;
+    }
+    Ok(())
+}
+
+/// Read all of a section's entries, along with the number of bytes that each
+/// entry occupies in the binary.
+fn sized_entries<'a, T>(
+    section: &wasmparser::SectionLimited<'a, T>,
+) -> Result<Vec<(T, u32)>, traits::Error>
 where
-    T: elements::Serialize,
-    traits::Error: From<<T as elements::Serialize>::Error>,
+    T: wasmparser::FromReader<'a>,
 {
-    let mut buf = vec![];
-    t.serialize(&mut buf)?;
-    Ok(buf.len() as u32)
+    let end = section.range().end;
+    let entries = section
+        .clone()
+        .into_iter_with_offsets()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut sized = Vec::with_capacity(entries.len());
+    let mut entries = entries.into_iter().peekable();
+    while let Some((offset, entry)) = entries.next() {
+        let next = entries.peek().map_or(end, |&(next, _)| next);
+        sized.push((entry, (next - offset) as u32));
+    }
+    Ok(sized)
+}
+
+/// Get the offset that a data or element segment is placed at, if it is a
+/// constant.
+fn const_offset(expr: &wasmparser::ConstExpr) -> Option<i64> {
+    match expr.get_operators_reader().read() {
+        Ok(Operator::I32Const { value }) => Some(i64::from(value)),
+        Ok(Operator::I64Const { value }) => Some(value),
+        _ => None,
+    }
+}
+
+/// Get the address that an active data segment placed at the given offset
+/// begins at, if it fits in a 32-bit memory.
+fn segment_address(offset: i64, len: usize) -> Option<u32> {
+    if offset >= 0 && offset + len as i64 <= i64::from(u32::MAX) {
+        Some(offset as u32)
+    } else {
+        None
+    }
+}
+
+/// Look for pointers to string literals in a data segment placed at the given
+/// address, such as the file paths that Rust's panic locations point to. Every
+/// aligned 32-bit word outside of the segment's own strings is taken for a
+/// pointer, which is only a guess, but one that rarely lands on a string.
+fn reference_strings_from_data(items: &mut ir::ItemsBuilder, id: Id, address: u32, data: &[u8]) {
+    let mut strings = printable_strings(data).into_iter().peekable();
+    let mut i = (4 - address as usize % 4) % 4;
+    while i + 4 <= data.len() {
+        while strings
+            .peek()
+            .map_or(false, |&(start, s)| start + s.len() <= i)
+        {
+            strings.next();
+        }
+        let in_string = strings.peek().map_or(false, |&(start, _)| start < i + 4);
+        if !in_string {
+            let word = u32::from(data[i])
+                | u32::from(data[i + 1]) << 8
+                | u32::from(data[i + 2]) << 16
+                | u32::from(data[i + 3]) << 24;
+            items.reference_string(id, word);
+        }
+        i += 4;
+    }
+}
+
+/// Hash a function body as it is, and again after normalizing it so that
+/// bodies which only differ in which functions they call, or in how their
+/// locals are numbered, hash the same. Call targets and locals are renumbered
+/// in the order they are first used.
+fn hash_body(body: &wasmparser::FunctionBody) -> Result<(u64, u64), traits::Error> {
+    let mut reader = body.get_binary_reader();
+    let base = reader.original_position();
+    let bytes = reader.read_bytes(reader.bytes_remaining())?;
+
+    let mut exact = Fnv::default();
+    exact.write(bytes);
+
+    let mut normalized = Fnv::default();
+    let mut ops = body.get_operators_reader()?;
+    normalized.write(&bytes[..ops.original_position() - base]);
+
+    let mut functions = BTreeMap::new();
+    let mut locals = BTreeMap::new();
+    while !ops.eof() {
+        let (op, start) = ops.read_with_offset()?;
+        let op_bytes = &bytes[start - base..ops.original_position() - base];
+        let (renumbered, index) = match op {
+            Operator::Call { function_index }
+            | Operator::ReturnCall { function_index }
+            | Operator::RefFunc { function_index } => (&mut functions, function_index),
+            Operator::LocalGet { local_index }
+            | Operator::LocalSet { local_index }
+            | Operator::LocalTee { local_index } => (&mut locals, local_index),
+            _ => {
+                normalized.write(op_bytes);
+                continue;
+            }
+        };
+        let next = renumbered.len() as u32;
+        let index = *renumbered.entry(index).or_insert(next);
+        normalized.write_u8(op_bytes[0]);
+        normalized.write(&index.to_le_bytes());
+    }
+
+    Ok((exact.finish(), normalized.finish()))
+}
+
+/// The size of a function body, counting every integer in it as if it were
+/// encoded in as few bytes as it can be. Linkers pad the indices and addresses
+/// that they relocate out to five bytes, and this keeps that padding from
+/// counting towards the function.
+fn minimal_body_size(body: &wasmparser::FunctionBody) -> Result<u32, traits::Error> {
+    let mut reader = body.get_binary_reader();
+    let base = reader.original_position();
+    let len = reader.bytes_remaining();
+    let bytes = reader.read_bytes(len)?;
+
+    let mut ops = body.get_operators_reader()?;
+    let locals_end = ops.original_position();
+    let mut padding = leb_padding(&bytes[..locals_end - base], base)?;
+    while !ops.eof() {
+        let (op, start) = ops.read_with_offset()?;
+        // Skip over the opcode, which is always a single byte, possibly
+        // followed by a sub-opcode that is read as an integer like the rest.
+        let immediates = &bytes[start + 1 - base..ops.original_position() - base];
+        padding += match op {
+            Operator::I32Const { value } => immediates.len() - sleb_len(i64::from(value)),
+            Operator::I64Const { value } => immediates.len() - sleb_len(value),
+            Operator::F32Const { .. } | Operator::F64Const { .. } | Operator::V128Const { .. } => 0,
+            _ => leb_padding(immediates, start + 1)?,
+        };
+    }
+
+    let len = len - padding;
+    Ok((uleb_len(len as u64) + len) as u32)
+}
+
+/// The number of bytes that would be saved by encoding a run of unsigned
+/// LEB128 integers in as few bytes as they can be.
+fn leb_padding(bytes: &[u8], offset: usize) -> Result<usize, traits::Error> {
+    let mut reader = wasmparser::BinaryReader::new_with_offset(bytes, offset);
+    let mut padding = 0;
+    while !reader.eof() {
+        let start = reader.current_position();
+        let value = reader.read_var_u64()?;
+        padding += reader.current_position() - start - uleb_len(value);
+    }
+    Ok(padding)
 }
 
-impl<'a> Parse<'a> for elements::Module {
-    type ItemsExtra = ();
+/// The fewest bytes that an unsigned LEB128 integer can be encoded in.
+fn uleb_len(mut value: u64) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// The fewest bytes that a signed LEB128 integer can be encoded in.
+fn sleb_len(mut value: i64) -> usize {
+    let mut len = 1;
+    while !(-64..64).contains(&value) {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// The items that constant expressions, such as global initializers and
+/// segment offsets, can refer to.
+struct ConstExprTargets {
+    func_section: Option<usize>,
+    global_section: Option<usize>,
+    function_import_count: usize,
+    global_import_count: usize,
+}
+
+impl ConstExprTargets {
+    fn new(module: &Module) -> Result<ConstExprTargets, traits::Error> {
+        let mut func_section = None;
+        let mut global_section = None;
+
+        for (sect_idx, s) in module.sections().iter().enumerate() {
+            match *s {
+                Section::Function(_) => func_section = Some(sect_idx),
+                Section::Global(_) => global_section = Some(sect_idx),
+                _ => {}
+            }
+        }
+
+        Ok(ConstExprTargets {
+            func_section,
+            global_section,
+            function_import_count: module.import_count(wasmparser::ExternalKind::Func)?,
+            global_import_count: module.import_count(wasmparser::ExternalKind::Global)?,
+        })
+    }
+
+    /// Add edges from the given item to the globals and functions that the
+    /// constant expression references with `global.get` and `ref.func`. The
+    /// edges to functions have the given kind.
+    fn parse_edges(
+        &self,
+        items: &mut ir::ItemsBuilder,
+        from: Id,
+        expr: &wasmparser::ConstExpr,
+        ref_func_kind: ir::EdgeKind,
+    ) -> Result<(), traits::Error> {
+        for op in expr.get_operators_reader() {
+            match op? {
+                Operator::GlobalGet { global_index } => {
+                    let idx = global_index as usize;
+                    if let Some(global_section) = self.global_section {
+                        if idx >= self.global_import_count {
+                            let g_id = Id::entry(global_section, idx - self.global_import_count);
+                            items.add_edge(from, g_id, ir::EdgeKind::Reference);
+                        }
+                    }
+                }
+                Operator::RefFunc { function_index } => {
+                    let idx = function_index as usize;
+                    if let Some(func_section) = self.func_section {
+                        if idx >= self.function_import_count {
+                            let f_id = Id::entry(func_section, idx - self.function_import_count);
+                            items.add_edge(from, f_id, ref_func_kind);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Parse<'a> for Module<'a> {
+    type ItemsExtra = Option<&'a SourceMap>;
+
+    fn parse_items(
+        &self,
+        items: &mut ir::ItemsBuilder,
+        source_map: Self::ItemsExtra,
+    ) -> Result<(), traits::Error> {
+        for (section, &size) in self.sections().iter().zip(&self.section_sizes) {
+            items.add_section(ir::Section::new(section.name(), size));
+        }
+        for warning in &self.warnings {
+            items.add_warning(warning.as_str());
+        }
 
-    fn parse_items(&self, items: &mut ir::ItemsBuilder, _extra: ()) -> Result<(), traits::Error> {
         let mut function_names = None;
 
         // The custom name sections. Parse these first since they also give us
         // debugging information for later sections.
         for (idx, section) in self.sections().iter().enumerate() {
-            let name = match *section {
+            let names = match *section {
                 Section::Name(ref n) => n,
                 _ => continue,
             };
-            match *name {
-                elements::NameSection::Module(ref m) => {
-                    m.parse_items(items, idx)?;
-                }
-                elements::NameSection::Function(ref f) => {
-                    function_names = Some(f.names());
-                    f.parse_items(items, idx)?;
-                }
-                elements::NameSection::Local(ref l) => {
-                    l.parse_items(items, idx)?;
-                }
-                elements::NameSection::Unparsed { .. } => {
-                    unreachable!("we pre-parsed names sections")
-                }
-            };
+            names.parse_items(items, idx)?;
+            if function_names.is_none() {
+                function_names = function_name_map(names)?;
+            }
         }
 
         for (idx, section) in self.sections().iter().enumerate() {
             match *section {
                 // Already eagerly parsed above.
                 Section::Name(_) => continue,
-                Section::Unparsed { .. } => {
-                    unreachable!("we eagerly parse all lazily parsed sections (aka names sections)")
-                }
                 Section::Custom(ref custom) => {
                     custom.parse_items(items, idx)?;
                 }
@@ -69,27 +605,37 @@ impl<'a> Parse<'a> for elements::Module {
                 Section::Memory(ref mem) => {
                     mem.parse_items(items, idx)?;
                 }
+                Section::Tag(ref tags) => {
+                    tags.parse_items(items, idx)?;
+                }
                 Section::Global(ref global) => {
                     global.parse_items(items, idx)?;
                 }
                 Section::Export(ref exports) => {
                     exports.parse_items(items, idx)?;
                 }
-                Section::Start(_) => {
-                    let start = StartSection(section);
+                Section::Start(ref start) => {
                     start.parse_items(items, idx)?;
                 }
                 Section::Element(ref elem) => {
                     elem.parse_items(items, idx)?;
                 }
+                Section::DataCount(ref count) => {
+                    count.parse_items(items, idx)?;
+                }
                 Section::Code(ref code) => {
-                    code.parse_items(items, (self, function_names, idx))?;
+                    code.parse_items(items, (self, function_names.as_ref(), source_map, idx))?;
                 }
                 Section::Data(ref data) => {
                     data.parse_items(items, idx)?;
                 }
-                Section::Reloc(ref reloc) => {
-                    reloc.parse_items(items, idx)?;
+                Section::Unknown(id) => {
+                    let name = format!("unknown section {}", id);
+                    let size = self.section_sizes[idx];
+                    items.add_root(ir::Item::new(Id::section(idx), name, size, ir::Misc::new()));
+                }
+                Section::Undecoded(ref name) => {
+                    add_opaque_section(items, idx, name, self.section_sizes[idx]);
                 }
             }
         }
@@ -102,18 +648,8 @@ impl<'a> Parse<'a> for elements::Module {
     fn parse_edges(&self, items: &mut ir::ItemsBuilder, _extra: ()) -> Result<(), traits::Error> {
         for (idx, section) in self.sections().iter().enumerate() {
             match *section {
-                Section::Name(elements::NameSection::Unparsed { .. })
-                | Section::Unparsed { .. } => {
-                    unreachable!("we eagerly parse all lazily parsed sections")
-                }
-                Section::Name(elements::NameSection::Module(ref m)) => {
-                    m.parse_edges(items, ())?;
-                }
-                Section::Name(elements::NameSection::Function(ref f)) => {
-                    f.parse_edges(items, ())?;
-                }
-                Section::Name(elements::NameSection::Local(ref l)) => {
-                    l.parse_edges(items, ())?;
+                Section::Name(ref names) => {
+                    names.parse_edges(items, ())?;
                 }
                 Section::Custom(ref custom) => {
                     custom.parse_edges(items, ())?;
@@ -133,78 +669,238 @@ impl<'a> Parse<'a> for elements::Module {
                 Section::Memory(ref mem) => {
                     mem.parse_edges(items, ())?;
                 }
+                Section::Tag(ref tags) => {
+                    tags.parse_edges(items, ())?;
+                }
                 Section::Global(ref global) => {
-                    global.parse_edges(items, ())?;
+                    global.parse_edges(items, (self, idx))?;
                 }
                 Section::Export(ref exports) => {
                     exports.parse_edges(items, (self, idx))?;
                 }
-                Section::Start(_) => {
-                    let start = StartSection(section);
+                Section::Start(ref start) => {
                     start.parse_edges(items, (self, idx))?;
                 }
                 Section::Element(ref elem) => {
                     elem.parse_edges(items, (self, idx))?;
                 }
+                Section::DataCount(ref count) => {
+                    count.parse_edges(items, ())?;
+                }
                 Section::Code(ref code) => {
                     code.parse_edges(items, (self, idx))?;
                 }
                 Section::Data(ref data) => {
-                    data.parse_edges(items, ())?;
-                }
-                Section::Reloc(ref reloc) => {
-                    reloc.parse_edges(items, ())?;
+                    data.parse_edges(items, (self, idx))?;
                 }
+                Section::Unknown(_) | Section::Undecoded(_) => continue,
             }
         }
         Ok(())
     }
 }
 
-impl<'a> Parse<'a> for elements::ModuleNameSection {
-    type ItemsExtra = usize;
+/// Get the URL of the module's source map from its `sourceMappingURL` custom
+/// section, if it has one.
+pub(crate) fn source_mapping_url(data: &[u8]) -> Option<String> {
+    for payload in wasmparser::Parser::new(0).parse_all(data) {
+        match payload {
+            Ok(wasmparser::Payload::CustomSection(ref custom))
+                if custom.name() == "sourceMappingURL" =>
+            {
+                let mut reader =
+                    wasmparser::BinaryReader::new_with_offset(custom.data(), custom.data_offset());
+                return reader.read_string().ok().map(|url| url.to_string());
+            }
+            Ok(_) => {}
+            Err(_) => return None,
+        }
+    }
+    None
+}
 
-    fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        let id = Id::section(idx);
-        let name = "\"module name\" subsection";
-        let size = serialized_size(self.clone())?;
-        items.add_root(ir::Item::new(id, name, size, ir::DebugInfo::new()));
-        Ok(())
+/// Walk the raw section headers of a wasm module that could not be fully
+/// parsed, and add an item for each section with its exact size. Only custom
+/// sections are decoded any further.
+pub(crate) fn parse_raw_sections(
+    data: &[u8],
+    items: &mut ir::ItemsBuilder,
+) -> Result<(), traits::Error> {
+    let mut reader = wasmparser::BinaryReader::new(data);
+    if reader.read_bytes(4)? != b"\0asm" {
+        return Err(traits::Error::with_msg("not a wasm module"));
     }
+    reader.read_bytes(4)?;
+
+    let mut idx = 0;
+    while !reader.eof() {
+        let section_start = reader.original_position();
+        let header = reader
+            .read_u8()
+            .and_then(|id| reader.read_var_u32().map(|len| (id, len)));
+        let contents = header.and_then(|(id, len)| {
+            let contents_start = reader.original_position();
+            reader
+                .read_bytes(len as usize)
+                .map(|contents| (id, contents, contents_start))
+        });
 
-    type EdgesExtra = ();
+        let (id, contents, contents_start) = match contents {
+            Ok(section) => section,
+            Err(e) => {
+                // Attribute everything that is left to one last item, so that
+                // sizes still add up.
+                items.add_warning(format!("section {} is truncated or malformed: {}", idx, e));
+                let size = (data.len() - section_start) as u32;
+                let name = "unparsed bytes";
+                items.add_section(ir::Section::new(name, size));
+                items.add_root(ir::Item::new(Id::section(idx), name, size, ir::Misc::new()));
+                break;
+            }
+        };
 
-    fn parse_edges(&self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
-        Ok(())
+        let size = (reader.original_position() - section_start) as u32;
+        match raw_section_name(id) {
+            Some("custom") => parse_raw_custom_section(items, idx, contents, contents_start, size)?,
+            Some(section_name) => {
+                items.add_section(ir::Section::new(section_name, size));
+                add_opaque_section(items, idx, section_name, size);
+            }
+            None => {
+                items.add_warning(format!("section {} has unknown id {}", idx, id));
+                items.add_section(ir::Section::new("unknown", size));
+
+                let name = format!("unknown section {}", id);
+                items.add_root(ir::Item::new(Id::section(idx), name, size, ir::Misc::new()));
+            }
+        }
+
+        idx += 1;
     }
+
+    Ok(())
 }
 
-impl<'a> Parse<'a> for elements::FunctionNameSection {
-    type ItemsExtra = usize;
+/// Add a single item for the whole of a section that isn't decoded any further.
+fn add_opaque_section(items: &mut ir::ItemsBuilder, idx: usize, section_name: &str, size: u32) {
+    let id = Id::section(idx);
+    let name = format!("\"{}\" section", section_name);
+    match section_name {
+        "code" => {
+            let code = ir::Code::new(&name);
+            items.add_root(ir::Item::new(id, name, size, code));
+        }
+        "data" => {
+            items.add_root(ir::Item::new(id, name, size, ir::Data::new(None)));
+        }
+        _ => {
+            items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
+        }
+    }
+}
 
-    fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        let id = Id::section(idx);
-        let name = "\"function names\" subsection";
-        let size = serialized_size(self.clone())?;
-        items.add_root(ir::Item::new(id, name, size, ir::DebugInfo::new()));
-        Ok(())
+/// Decode a custom section found by `parse_raw_sections`, falling back to an
+/// opaque item if it is malformed.
+fn parse_raw_custom_section(
+    items: &mut ir::ItemsBuilder,
+    idx: usize,
+    contents: &[u8],
+    contents_start: usize,
+    size: u32,
+) -> Result<(), traits::Error> {
+    let reader = match wasmparser::CustomSectionReader::new(contents, contents_start) {
+        Ok(reader) => reader,
+        Err(e) => {
+            items.add_warning(format!("custom section {} is malformed: {}", idx, e));
+            items.add_section(ir::Section::new("custom", size));
+            items.add_root(ir::Item::new(
+                Id::section(idx),
+                "custom section",
+                size,
+                ir::Misc::new(),
+            ));
+            return Ok(());
+        }
+    };
+
+    let section_name = format!("custom '{}'", reader.name());
+    items.add_section(ir::Section::new(section_name, size));
+
+    if reader.name() == "name" {
+        let names = wasmparser::NameSectionReader::new(reader.data(), reader.data_offset());
+        if decode_names(&names).is_ok() {
+            return names.parse_items(items, idx);
+        }
+        items.add_warning("the \"name\" section is malformed");
     }
 
-    type EdgesExtra = ();
+    let custom = CustomSection { reader, size };
+    custom.parse_items(items, idx)
+}
 
-    fn parse_edges(&self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
-        Ok(())
+/// The name of the section with the given id, as used in the wasm spec.
+fn raw_section_name(id: u8) -> Option<&'static str> {
+    match id {
+        0 => Some("custom"),
+        1 => Some("type"),
+        2 => Some("import"),
+        3 => Some("function"),
+        4 => Some("table"),
+        5 => Some("memory"),
+        6 => Some("global"),
+        7 => Some("export"),
+        8 => Some("start"),
+        9 => Some("element"),
+        10 => Some("code"),
+        11 => Some("data"),
+        12 => Some("data count"),
+        13 => Some("tag"),
+        _ => None,
     }
 }
 
-impl<'a> Parse<'a> for elements::LocalNameSection {
+/// Get the function names map out of a names section, if it has one.
+fn function_name_map<'a>(
+    names: &wasmparser::NameSectionReader<'a>,
+) -> Result<Option<BTreeMap<u32, &'a str>>, traits::Error> {
+    for subsection in names.clone() {
+        if let wasmparser::Name::Function(map) = subsection? {
+            let mut function_names = BTreeMap::new();
+            for naming in map {
+                let naming = naming?;
+                function_names.insert(naming.index, naming.name);
+            }
+            return Ok(Some(function_names));
+        }
+    }
+    Ok(None)
+}
+
+impl<'a> Parse<'a> for wasmparser::NameSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        let id = Id::section(idx);
-        let name = "\"local names\" subsection";
-        let size = serialized_size(self.clone())?;
-        items.add_root(ir::Item::new(id, name, size, ir::DebugInfo::new()));
+        for (i, subsection) in self.clone().enumerate() {
+            use wasmparser::Name;
+
+            let (name, range) = match subsection? {
+                Name::Module { name_range, .. } => ("\"module name\" subsection", name_range),
+                Name::Function(ref m) => ("\"function names\" subsection", m.range()),
+                Name::Local(ref m) => ("\"local names\" subsection", m.range()),
+                Name::Label(ref m) => ("\"label names\" subsection", m.range()),
+                Name::Type(ref m) => ("\"type names\" subsection", m.range()),
+                Name::Table(ref m) => ("\"table names\" subsection", m.range()),
+                Name::Memory(ref m) => ("\"memory names\" subsection", m.range()),
+                Name::Global(ref m) => ("\"global names\" subsection", m.range()),
+                Name::Element(ref m) => ("\"elem names\" subsection", m.range()),
+                Name::Data(ref m) => ("\"data names\" subsection", m.range()),
+                Name::Unknown { range, .. } => ("unknown names subsection", range),
+            };
+
+            let id = Id::entry(idx, i);
+            let size = (range.end - range.start) as u32;
+            items.add_root(ir::Item::new(id, name, size, ir::DebugInfo::new()));
+        }
         Ok(())
     }
 
@@ -215,19 +911,105 @@ impl<'a> Parse<'a> for elements::LocalNameSection {
     }
 }
 
-impl<'a> Parse<'a> for elements::CustomSection {
+/// A custom section other than the name section, and the size of the whole
+/// section.
+#[derive(Clone)]
+pub(crate) struct CustomSection<'a> {
+    reader: wasmparser::CustomSectionReader<'a>,
+    size: u32,
+}
+
+impl<'a> CustomSection<'a> {
+    /// Decode the tools and languages listed in a `producers` section.
+    fn producers(&self) -> Result<Vec<ir::Producer>, traits::Error> {
+        let fields =
+            wasmparser::ProducersSectionReader::new(self.reader.data(), self.reader.data_offset())?;
+
+        let mut producers = vec![];
+        for field in fields {
+            let field = field?;
+            for value in field.values {
+                let value = value?;
+                producers.push(ir::Producer::new(field.name, value.name, value.version));
+            }
+        }
+        Ok(producers)
+    }
+
+    /// Decode the features listed in a `target_features` section.
+    fn target_features(&self) -> Result<Vec<ir::TargetFeature>, traits::Error> {
+        let entries: wasmparser::SectionLimited<TargetFeatureEntry> =
+            wasmparser::SectionLimited::new(self.reader.data(), self.reader.data_offset())?;
+
+        let mut features = vec![];
+        for entry in entries {
+            let entry = entry?;
+            let policy = match entry.prefix {
+                b'+' => ir::TargetFeaturePolicy::Used,
+                b'=' => ir::TargetFeaturePolicy::Required,
+                b'-' => ir::TargetFeaturePolicy::Disallowed,
+                prefix => {
+                    return Err(traits::Error::with_msg(format!(
+                        "unknown target feature prefix: 0x{:x}",
+                        prefix
+                    )))
+                }
+            };
+            features.push(ir::TargetFeature::new(policy, entry.name));
+        }
+        Ok(features)
+    }
+}
+
+/// An entry in the `target_features` custom section: a prefix saying how the
+/// feature is used, followed by its name.
+struct TargetFeatureEntry<'a> {
+    prefix: u8,
+    name: &'a str,
+}
+
+impl<'a> wasmparser::FromReader<'a> for TargetFeatureEntry<'a> {
+    fn from_reader(reader: &mut wasmparser::BinaryReader<'a>) -> wasmparser::Result<Self> {
+        let prefix = reader.read_u8()?;
+        let name = reader.read_string()?;
+        Ok(TargetFeatureEntry { prefix, name })
+    }
+}
+
+impl<'a> Parse<'a> for CustomSection<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
         let id = Id::section(idx);
-        let size = serialized_size(self.clone())?;
+        let custom_name = self.reader.name();
+
+        // Decode the metadata sections we know about. If they are malformed,
+        // just report them like any other custom section.
+        let decoded = match custom_name {
+            "producers" => self.producers().ok().map(|producers| {
+                for producer in producers {
+                    items.add_producer(producer);
+                }
+            }),
+            "target_features" => self.target_features().ok().map(|features| {
+                for feature in features {
+                    items.add_target_feature(feature);
+                }
+            }),
+            _ => None,
+        };
 
-        let mut name = String::with_capacity("custom section ''".len() + self.name().len());
-        name.push_str("custom section '");
-        name.push_str(self.name());
-        name.push_str("'");
+        let name = if decoded.is_some() {
+            format!("\"{}\" section", custom_name)
+        } else {
+            let mut name = String::with_capacity("custom section ''".len() + custom_name.len());
+            name.push_str("custom section '");
+            name.push_str(custom_name);
+            name.push_str("'");
+            name
+        };
 
-        items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
+        items.add_root(ir::Item::new(id, name, self.size, ir::Misc::new()));
         Ok(())
     }
 
@@ -238,16 +1020,33 @@ impl<'a> Parse<'a> for elements::CustomSection {
     }
 }
 
-impl<'a> Parse<'a> for elements::TypeSection {
+/// Count the types defined by a type section, rather than its recursion groups.
+fn type_count(types: &wasmparser::TypeSectionReader) -> Result<u32, traits::Error> {
+    let mut count = 0;
+    for group in types.clone() {
+        count += group?.types().len() as u32;
+    }
+    Ok(count)
+}
+
+impl<'a> Parse<'a> for wasmparser::TypeSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, ty) in self.types().iter().enumerate() {
-            let id = Id::entry(idx, i);
-            let size = serialized_size(ty.clone())?;
-            let mut name = String::with_capacity("type[]".len() + 4);
-            write!(&mut name, "type[{}]", i)?;
-            items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
+        // Types are indexed individually even when the GC proposal groups
+        // several of them into one explicit recursion group, so give each type
+        // its own item and split the group's bytes between them.
+        let mut i = 0;
+        for (group, size) in sized_entries(self)? {
+            let count = group.types().len() as u32;
+            for j in 0..count {
+                let id = Id::entry(idx, i);
+                let mut name = String::with_capacity("type[]".len() + 4);
+                write!(&mut name, "type[{}]", i)?;
+                let size = size / count + if j < size % count { 1 } else { 0 };
+                items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
+                i += 1;
+            }
         }
         Ok(())
     }
@@ -259,18 +1058,18 @@ impl<'a> Parse<'a> for elements::TypeSection {
     }
 }
 
-impl<'a> Parse<'a> for elements::ImportSection {
+impl<'a> Parse<'a> for wasmparser::ImportSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, imp) in self.entries().iter().enumerate() {
+        for (i, (imp, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-            let size = serialized_size(imp.clone())?;
             let mut name = String::with_capacity(
-                "import ".len() + imp.module().len() + "::".len() + imp.field().len(),
+                "import ".len() + imp.module.len() + "::".len() + imp.name.len(),
             );
-            write!(&mut name, "import {}::{}", imp.module(), imp.field())?;
+            write!(&mut name, "import {}::{}", imp.module, imp.name)?;
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
+            items.add_import(id, imp.module, imp.name);
         }
         Ok(())
     }
@@ -282,13 +1081,12 @@ impl<'a> Parse<'a> for elements::ImportSection {
     }
 }
 
-impl<'a> Parse<'a> for elements::FunctionSection {
+impl<'a> Parse<'a> for wasmparser::FunctionSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, func) in self.entries().iter().enumerate() {
+        for (i, (_, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-            let size = serialized_size(func.clone())?;
             let mut name = String::with_capacity("func[]".len() + 4);
             write!(&mut name, "func[{}]", i)?;
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
@@ -296,7 +1094,7 @@ impl<'a> Parse<'a> for elements::FunctionSection {
         Ok(())
     }
 
-    type EdgesExtra = (&'a elements::Module, usize);
+    type EdgesExtra = (&'a Module<'a>, usize);
 
     fn parse_edges(
         &self,
@@ -309,22 +1107,25 @@ impl<'a> Parse<'a> for elements::FunctionSection {
         // Get the indices for the type and code sections.
         for (sect_idx, s) in module.sections().iter().enumerate() {
             match *s {
-                Section::Type(_) => type_section = Some(sect_idx),
+                Section::Type(ref ty) => type_section = Some((sect_idx, type_count(ty)?)),
                 Section::Code(_) => code_section = Some(sect_idx),
                 _ => {}
             }
         }
 
-        for (func_i, func) in self.entries().iter().enumerate() {
+        for (func_i, type_ref) in self.clone().into_iter().enumerate() {
+            let type_ref = type_ref?;
             let func_id = Id::entry(idx, func_i);
 
-            if let Some(type_idx) = type_section {
-                let type_id = Id::entry(type_idx, func.type_ref() as usize);
-                items.add_edge(func_id, type_id);
+            if let Some((type_idx, type_count)) = type_section {
+                if type_ref < type_count {
+                    let type_id = Id::entry(type_idx, type_ref as usize);
+                    items.add_edge(func_id, type_id, ir::EdgeKind::Type);
+                }
             }
             if let Some(code_idx) = code_section {
                 let body_id = Id::entry(code_idx, func_i);
-                items.add_edge(func_id, body_id);
+                items.add_edge(func_id, body_id, ir::EdgeKind::Contains);
             }
         }
 
@@ -332,13 +1133,12 @@ impl<'a> Parse<'a> for elements::FunctionSection {
     }
 }
 
-impl<'a> Parse<'a> for elements::TableSection {
+impl<'a> Parse<'a> for wasmparser::TableSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, entry) in self.entries().iter().enumerate() {
+        for (i, (_, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-            let size = serialized_size(entry.clone())?;
             let mut name = String::with_capacity("table[]".len() + 4);
             write!(&mut name, "table[{}]", i)?;
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
@@ -353,13 +1153,12 @@ impl<'a> Parse<'a> for elements::TableSection {
     }
 }
 
-impl<'a> Parse<'a> for elements::MemorySection {
+impl<'a> Parse<'a> for wasmparser::MemorySectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, mem) in self.entries().iter().enumerate() {
+        for (i, (_, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-            let size = serialized_size(mem.clone())?;
             let mut name = String::with_capacity("memory[]".len() + 4);
             write!(&mut name, "memory[{}]", i)?;
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
@@ -374,44 +1173,77 @@ impl<'a> Parse<'a> for elements::MemorySection {
     }
 }
 
-impl<'a> Parse<'a> for elements::GlobalSection {
+impl<'a> Parse<'a> for wasmparser::TagSectionReader<'a> {
+    type ItemsExtra = usize;
+
+    fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
+        for (i, (_, size)) in sized_entries(self)?.into_iter().enumerate() {
+            let id = Id::entry(idx, i);
+            let mut name = String::with_capacity("tag[]".len() + 4);
+            write!(&mut name, "tag[{}]", i)?;
+            items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
+        }
+        Ok(())
+    }
+
+    type EdgesExtra = ();
+
+    fn parse_edges(&self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
+        Ok(())
+    }
+}
+
+impl<'a> Parse<'a> for wasmparser::GlobalSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, g) in self.entries().iter().enumerate() {
+        for (i, (g, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
             let mut name = String::with_capacity("global[]".len() + 4);
             write!(&mut name, "global[{}]", i).unwrap();
 
-            let size = serialized_size(g.clone())?;
-            let ty = g.global_type().content_type().to_string();
+            let ty = g.ty.content_type.to_string();
             items.add_item(ir::Item::new(id, name, size, ir::Data::new(Some(ty))));
         }
         Ok(())
     }
 
-    type EdgesExtra = ();
+    type EdgesExtra = (&'a Module<'a>, usize);
 
-    fn parse_edges(&self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
+    fn parse_edges(
+        &self,
+        items: &mut ir::ItemsBuilder,
+        (module, idx): Self::EdgesExtra,
+    ) -> Result<(), traits::Error> {
+        let targets = ConstExprTargets::new(module)?;
+
+        for (i, g) in self.clone().into_iter().enumerate() {
+            targets.parse_edges(
+                items,
+                Id::entry(idx, i),
+                &g?.init_expr,
+                ir::EdgeKind::Reference,
+            )?;
+        }
         Ok(())
     }
 }
 
-impl<'a> Parse<'a> for elements::ExportSection {
+impl<'a> Parse<'a> for wasmparser::ExportSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, exp) in self.entries().iter().enumerate() {
+        for (i, (exp, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-            let mut name = String::with_capacity("export \"\"".len() + exp.field().len());
-            write!(&mut name, "export \"{}\"", exp.field())?;
-            let size = serialized_size(exp.clone())?;
+            let mut name = String::with_capacity("export \"\"".len() + exp.name.len());
+            write!(&mut name, "export \"{}\"", exp.name)?;
             items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
+            items.add_export(id, exp.name);
         }
         Ok(())
     }
 
-    type EdgesExtra = (&'a elements::Module, usize);
+    type EdgesExtra = (&'a Module<'a>, usize);
 
     fn parse_edges(
         &self,
@@ -433,38 +1265,33 @@ impl<'a> Parse<'a> for elements::ExportSection {
             }
         }
 
-        let function_import_count = module.import_count(elements::ImportCountType::Function);
-        let table_import_count = module.import_count(elements::ImportCountType::Table);
-        let memory_import_count = module.import_count(elements::ImportCountType::Memory);
-        let global_import_count = module.import_count(elements::ImportCountType::Global);
+        let function_import_count = module.import_count(wasmparser::ExternalKind::Func)?;
+        let table_import_count = module.import_count(wasmparser::ExternalKind::Table)?;
+        let memory_import_count = module.import_count(wasmparser::ExternalKind::Memory)?;
+        let global_import_count = module.import_count(wasmparser::ExternalKind::Global)?;
 
-        for (i, exp) in self.entries().iter().enumerate() {
+        for (i, exp) in self.clone().into_iter().enumerate() {
+            let exp = exp?;
             let exp_id = Id::entry(idx, i);
-            match *exp.internal() {
-                elements::Internal::Function(exported_func_idx) => {
-                    if let Some(func_section) = func_section {
-                        let func_idx = exported_func_idx as usize - function_import_count;
-                        items.add_edge(exp_id, Id::entry(func_section, func_idx));
-                    }
-                }
-                elements::Internal::Table(exported_table_idx) => {
-                    if let Some(table_section) = table_section {
-                        let table_idx = exported_table_idx as usize - table_import_count;
-                        items.add_edge(exp_id, Id::entry(table_section, table_idx));
-                    }
-                }
-                elements::Internal::Memory(exported_memory_idx) => {
-                    if let Some(memory_section) = memory_section {
-                        let memory_idx = exported_memory_idx as usize - memory_import_count;
-                        items.add_edge(exp_id, Id::entry(memory_section, memory_idx));
-                    }
-                }
-                elements::Internal::Global(exported_global_idx) => {
-                    if let Some(global_section) = global_section {
-                        let global_idx = exported_global_idx as usize - global_import_count;
-                        items.add_edge(exp_id, Id::entry(global_section, global_idx));
-                    }
-                }
+            let index = exp.index as usize;
+            let (section, import_count) = match exp.kind {
+                wasmparser::ExternalKind::Func => (func_section, function_import_count),
+                wasmparser::ExternalKind::Table => (table_section, table_import_count),
+                wasmparser::ExternalKind::Memory => (memory_section, memory_import_count),
+                wasmparser::ExternalKind::Global => (global_section, global_import_count),
+                wasmparser::ExternalKind::Tag => continue,
+            };
+
+            // Re-exports of imports don't reference anything we've parsed.
+            if index < import_count {
+                continue;
+            }
+            if let Some(section) = section {
+                items.add_edge(
+                    exp_id,
+                    Id::entry(section, index - import_count),
+                    ir::EdgeKind::Reference,
+                );
             }
         }
 
@@ -472,37 +1299,31 @@ impl<'a> Parse<'a> for elements::ExportSection {
     }
 }
 
-#[derive(Debug)]
-struct StartSection<'a>(&'a Section);
+/// The start section: the index of the function to call when the module is
+/// instantiated, and the size of the whole section.
+#[derive(Clone, Debug)]
+pub(crate) struct StartSection {
+    func: u32,
+    size: u32,
+}
 
-impl<'a> Parse<'a> for StartSection<'a> {
+impl<'a> Parse<'a> for StartSection {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        assert!(match *self.0 {
-            Section::Start(_) => true,
-            _ => false,
-        });
-
         let id = Id::section(idx);
-        let size = serialized_size(self.0.clone())?;
         let name = "\"start\" section";
-        items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
+        items.add_root(ir::Item::new(id, name, self.size, ir::Misc::new()));
         Ok(())
     }
 
-    type EdgesExtra = (&'a elements::Module, usize);
+    type EdgesExtra = (&'a Module<'a>, usize);
 
     fn parse_edges(
         &self,
         items: &mut ir::ItemsBuilder,
         (module, idx): Self::EdgesExtra,
     ) -> Result<(), traits::Error> {
-        let f_i = match *self.0 {
-            Section::Start(i) => i,
-            _ => unreachable!(),
-        };
-
         let mut func_section = None;
 
         for (sect_idx, s) in module.sections().iter().enumerate() {
@@ -512,21 +1333,52 @@ impl<'a> Parse<'a> for StartSection<'a> {
             }
         }
 
+        let function_import_count = module.import_count(wasmparser::ExternalKind::Func)?;
+        let f_i = self.func as usize;
+        if f_i < function_import_count {
+            // Starting with an imported function.
+            return Ok(());
+        }
+
         if let Some(func_idx) = func_section {
-            items.add_edge(Id::section(idx), Id::entry(func_idx, f_i as usize));
+            let f_id = Id::entry(func_idx, f_i - function_import_count);
+            items.add_edge(Id::section(idx), f_id, ir::EdgeKind::Call);
         }
 
         Ok(())
     }
 }
 
-impl<'a> Parse<'a> for elements::ElementSection {
+/// The data count section, required by the bulk memory operations to declare
+/// the number of data segments up front.
+#[derive(Clone, Debug)]
+pub(crate) struct DataCountSection {
+    size: u32,
+}
+
+impl<'a> Parse<'a> for DataCountSection {
+    type ItemsExtra = usize;
+
+    fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
+        let id = Id::section(idx);
+        let name = "\"data count\" section";
+        items.add_root(ir::Item::new(id, name, self.size, ir::Misc::new()));
+        Ok(())
+    }
+
+    type EdgesExtra = ();
+
+    fn parse_edges(&self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
+        Ok(())
+    }
+}
+
+impl<'a> Parse<'a> for wasmparser::ElementSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, elem) in self.entries().iter().enumerate() {
+        for (i, (_, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-            let size = serialized_size(elem.clone())?;
             let mut name = String::with_capacity("elem[]".len() + 4);
             write!(&mut name, "elem[{}]", i)?;
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
@@ -534,7 +1386,7 @@ impl<'a> Parse<'a> for elements::ElementSection {
         Ok(())
     }
 
-    type EdgesExtra = (&'a elements::Module, usize);
+    type EdgesExtra = (&'a Module<'a>, usize);
 
     fn parse_edges(
         &self,
@@ -552,17 +1404,62 @@ impl<'a> Parse<'a> for elements::ElementSection {
             }
         }
 
-        let num_imported_funcs = module.import_count(elements::ImportCountType::Function);
-        for (i, elem) in self.entries().iter().enumerate() {
+        let num_imported_funcs = module.import_count(wasmparser::ExternalKind::Func)?;
+        let num_imported_tables = module.import_count(wasmparser::ExternalKind::Table)?;
+        let targets = ConstExprTargets::new(module)?;
+
+        for (i, elem) in self.clone().into_iter().enumerate() {
+            let elem = elem?;
             let elem_id = Id::entry(idx, i);
-            if let Some(table_idx) = table_section {
-                let entry_id = Id::entry(table_idx, elem.index() as usize);
-                items.add_edge(elem_id, entry_id);
+
+            // Passive and declared segments aren't written into any table
+            // until a `table.init`, if ever.
+            if let wasmparser::ElementKind::Active {
+                table_index,
+                ref offset_expr,
+            } = elem.kind
+            {
+                targets.parse_edges(items, elem_id, offset_expr, ir::EdgeKind::Reference)?;
+
+                let table_i = table_index.unwrap_or(0) as usize;
+                if let Some(table_idx) = table_section {
+                    if table_i >= num_imported_tables {
+                        let entry_id = Id::entry(table_idx, table_i - num_imported_tables);
+                        items.add_edge(elem_id, entry_id, ir::EdgeKind::Reference);
+                    }
+                }
+            }
+
+            // Declared segments only allow taking references to their members
+            // with `ref.func`, while the others fill tables.
+            let member_kind = match elem.kind {
+                wasmparser::ElementKind::Declared => ir::EdgeKind::Reference,
+                _ => ir::EdgeKind::IndirectCall,
+            };
+
+            let mut members = vec![];
+            match elem.items {
+                wasmparser::ElementItems::Functions(funcs) => {
+                    for f_i in funcs {
+                        members.push(f_i?);
+                    }
+                }
+                wasmparser::ElementItems::Expressions(_, exprs) => {
+                    for expr in exprs {
+                        targets.parse_edges(items, elem_id, &expr?, member_kind)?;
+                    }
+                }
             }
+
             if let Some(func_idx) = func_section {
-                for &f_i in elem.members() {
-                    let f_id = Id::entry(func_idx, f_i as usize - num_imported_funcs);
-                    items.add_edge(elem_id, f_id);
+                for f_i in members {
+                    let f_i = f_i as usize;
+                    if f_i < num_imported_funcs {
+                        // A table slot holding an imported function.
+                        continue;
+                    }
+                    let f_id = Id::entry(func_idx, f_i - num_imported_funcs);
+                    items.add_edge(elem_id, f_id, member_kind);
                 }
             }
         }
@@ -570,21 +1467,26 @@ impl<'a> Parse<'a> for elements::ElementSection {
     }
 }
 
-impl<'a> Parse<'a> for elements::CodeSection {
-    type ItemsExtra = (&'a elements::Module, Option<&'a elements::NameMap>, usize);
+impl<'a> Parse<'a> for wasmparser::CodeSectionReader<'a> {
+    type ItemsExtra = (
+        &'a Module<'a>,
+        Option<&'a BTreeMap<u32, &'a str>>,
+        Option<&'a SourceMap>,
+        usize,
+    );
 
     fn parse_items(
         &self,
         items: &mut ir::ItemsBuilder,
-        (module, function_names, idx): Self::ItemsExtra,
+        (module, function_names, source_map, idx): Self::ItemsExtra,
     ) -> Result<(), traits::Error> {
-        let table_offset = module.import_count(elements::ImportCountType::Function);
+        let table_offset = module.import_count(wasmparser::ExternalKind::Func)?;
 
-        for (i, body) in self.bodies().iter().enumerate() {
+        for (i, (body, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
             let name = function_names
                 .as_ref()
-                .and_then(|names| names.get((i + table_offset) as u32))
+                .and_then(|names| names.get(&((i + table_offset) as u32)))
                 .map_or_else(
                     || {
                         let mut name = String::with_capacity("code[]".len() + 4);
@@ -594,15 +1496,30 @@ impl<'a> Parse<'a> for elements::CodeSection {
                     |name| name.to_string(),
                 );
 
-            let size = serialized_size(body.clone())?;
-            let code = ir::Code::new(&name);
+            let mut code = ir::Code::new(&name);
+            // A body we can't decode just can't be compared with the others.
+            if let Ok((body_hash, normalized_body_hash)) = hash_body(&body) {
+                code.set_body_hashes(body_hash, normalized_body_hash);
+            }
+            if let Some(source_map) = source_map {
+                // Source maps only map the body's instructions, and the bytes
+                // of its locals are still covered by the previous function's
+                // last mapping.
+                let start = body.get_operators_reader()?.original_position();
+                let end = body.range().end;
+                if let Some(location) = source_map.locate(start as u32, end as u32) {
+                    code.set_location(location);
+                }
+            }
+            // Fall back to the encoded size of bodies we can't decode.
+            let size = minimal_body_size(&body).unwrap_or(size);
             items.add_item(ir::Item::new(id, name, size, code));
         }
 
         Ok(())
     }
 
-    type EdgesExtra = (&'a elements::Module, usize);
+    type EdgesExtra = (&'a Module<'a>, usize);
 
     fn parse_edges(
         &self,
@@ -611,86 +1528,103 @@ impl<'a> Parse<'a> for elements::CodeSection {
     ) -> Result<(), traits::Error> {
         let mut func_section = None;
         let mut global_section = None;
+        let mut elem_section = None;
+        let mut data_section = None;
 
         for (sect_idx, s) in module.sections().iter().enumerate() {
             match *s {
                 Section::Function(_) => func_section = Some(sect_idx),
                 Section::Global(_) => global_section = Some(sect_idx),
+                Section::Element(_) => elem_section = Some(sect_idx),
+                Section::Data(_) => data_section = Some(sect_idx),
                 _ => {}
             }
         }
 
-        let function_import_count = module.import_count(elements::ImportCountType::Function);
-        let global_import_count = module.import_count(elements::ImportCountType::Global);
-
-        for (b_i, body) in self.bodies().iter().enumerate() {
-            use parity_wasm::elements::Opcode::*;
+        let function_import_count = module.import_count(wasmparser::ExternalKind::Func)?;
+        let global_import_count = module.import_count(wasmparser::ExternalKind::Global)?;
 
+        for (b_i, body) in self.clone().into_iter().enumerate() {
             let body_id = Id::entry(idx, b_i);
-            let code = body.code().elements();
-
-            for i in 0..code.len() {
-                match code[i] {
-                    Call(idx) => {
-                        let idx = idx as usize;
+            let mut prev = None;
+
+            for op in body?.get_operators_reader()? {
+                let op = op?;
+                match op {
+                    Operator::Call { function_index }
+                    | Operator::ReturnCall { function_index }
+                    | Operator::RefFunc { function_index } => {
+                        let kind = match op {
+                            Operator::RefFunc { .. } => ir::EdgeKind::Reference,
+                            _ => ir::EdgeKind::Call,
+                        };
+                        let idx = function_index as usize;
                         if let Some(func_section) = func_section {
-                            let func_section = func_section as usize;
-
-                            if idx < function_import_count {
-                                // Calling an imported function.
-                                continue;
+                            if idx >= function_import_count {
+                                let f_id = Id::entry(func_section, idx - function_import_count);
+                                items.add_edge(body_id, f_id, kind);
                             }
-
-                            let f_id = Id::entry(func_section, idx - function_import_count);
-                            items.add_edge(body_id, f_id);
                         }
                     }
 
                     // TODO: Rather than looking at indirect calls, need to look
                     // at where the vtables get initialized and/or vtable
                     // indices get pushed onto the stack.
-                    CallIndirect(_idx, _reserved) => continue,
+                    Operator::CallIndirect { .. } | Operator::ReturnCallIndirect { .. } => {}
 
-                    GetGlobal(idx) | SetGlobal(idx) => {
-                        let idx = idx as usize;
-                        if let Some(global_section) = global_section {
-                            let global_section = global_section as usize;
+                    // Constants that are the addresses of string literals, as
+                    // when passing a `&str` or a panic message to a function.
+                    Operator::I32Const { value } => items.reference_string(body_id, value as u32),
 
-                            if idx < global_import_count {
-                                // Referencing an imported global.
-                                continue;
+                    Operator::GlobalGet { global_index } | Operator::GlobalSet { global_index } => {
+                        let idx = global_index as usize;
+                        if let Some(global_section) = global_section {
+                            if idx >= global_import_count {
+                                let g_id = Id::entry(global_section, idx - global_import_count);
+                                items.add_edge(body_id, g_id, ir::EdgeKind::Reference);
                             }
+                        }
+                    }
 
-                            let g_id = Id::entry(global_section, idx - global_import_count);
-                            items.add_edge(body_id, g_id);
+                    Operator::MemoryInit { data_index, .. } | Operator::DataDrop { data_index } => {
+                        if let Some(data_section) = data_section {
+                            let d_id = Id::entry(data_section, data_index as usize);
+                            items.add_edge(body_id, d_id, ir::EdgeKind::Data);
                         }
                     }
 
-                    I32Load(_, off)
-                    | I32Load8S(_, off)
-                    | I32Load8U(_, off)
-                    | I32Load16S(_, off)
-                    | I32Load16U(_, off)
-                    | I64Load(_, off)
-                    | I64Load8S(_, off)
-                    | I64Load8U(_, off)
-                    | I64Load16S(_, off)
-                    | I64Load16U(_, off)
-                    | I64Load32S(_, off)
-                    | I64Load32U(_, off)
-                    | F32Load(_, off)
-                    | F64Load(_, off) => {
-                        if i > 0 {
-                            if let I32Const(base) = code[i - 1] {
-                                if let Some(data_id) = items.get_data(base as u32 + off) {
-                                    items.add_edge(body_id, data_id);
-                                }
+                    Operator::TableInit { elem_index, .. } | Operator::ElemDrop { elem_index } => {
+                        if let Some(elem_section) = elem_section {
+                            let e_id = Id::entry(elem_section, elem_index as usize);
+                            items.add_edge(body_id, e_id, ir::EdgeKind::Reference);
+                        }
+                    }
+
+                    Operator::I32Load { memarg }
+                    | Operator::I32Load8S { memarg }
+                    | Operator::I32Load8U { memarg }
+                    | Operator::I32Load16S { memarg }
+                    | Operator::I32Load16U { memarg }
+                    | Operator::I64Load { memarg }
+                    | Operator::I64Load8S { memarg }
+                    | Operator::I64Load8U { memarg }
+                    | Operator::I64Load16S { memarg }
+                    | Operator::I64Load16U { memarg }
+                    | Operator::I64Load32S { memarg }
+                    | Operator::I64Load32U { memarg }
+                    | Operator::F32Load { memarg }
+                    | Operator::F64Load { memarg } => {
+                        if let Some(Operator::I32Const { value: base }) = prev {
+                            let offset = base as u32 + memarg.offset as u32;
+                            if let Some(data_id) = items.get_data(offset) {
+                                items.add_edge(body_id, data_id, ir::EdgeKind::Data);
                             }
                         }
                     }
 
-                    _ => continue,
+                    _ => {}
                 }
+                prev = Some(op);
             }
         }
 
@@ -698,60 +1632,87 @@ impl<'a> Parse<'a> for elements::CodeSection {
     }
 }
 
-impl<'a> Parse<'a> for elements::DataSection {
+impl<'a> Parse<'a> for wasmparser::DataSectionReader<'a> {
     type ItemsExtra = usize;
 
     fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, d) in self.entries().iter().enumerate() {
-            use parity_wasm::elements::Opcode::*;
-
+        for (i, (d, size)) in sized_entries(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
             let mut name = String::with_capacity("data[]".len() + 4);
             write!(&mut name, "data[{}]", i).unwrap();
 
-            let size = serialized_size(d.clone())?; // serialized size
-            let length = d.value().len(); // size of data
+            let length = d.data.len(); // size of data
             let ty = None;
-            let offset_code = d.offset().code();
-            let offset = offset_code.get(0).and_then(|op| match *op {
-                I32Const(o) => Some(i64::from(o)),
-                I64Const(o) => Some(o),
-                _ => None,
-            });
 
             items.add_item(ir::Item::new(id, name, size, ir::Data::new(ty)));
 
-            if let Some(off) = offset {
-                items.link_data(off, length, id);
+            // Passive segments aren't placed in memory until a `memory.init`,
+            // so there is no fixed address to link them at.
+            let mut address = None;
+            if let wasmparser::DataKind::Active {
+                ref offset_expr, ..
+            } = d.kind
+            {
+                if let Some(off) = const_offset(offset_expr) {
+                    items.link_data(off, length, id);
+                    address = segment_address(off, length);
+                }
+            }
+
+            for (start, string) in printable_strings(d.data) {
+                let string_address = address.map(|address| address + start as u32);
+                items.add_string(ir::StringLiteral::new(id, string_address, string));
             }
         }
         Ok(())
     }
 
-    type EdgesExtra = ();
+    type EdgesExtra = (&'a Module<'a>, usize);
 
-    fn parse_edges(&self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
-        Ok(())
-    }
-}
-
-impl<'a> Parse<'a> for elements::RelocSection {
-    type ItemsExtra = usize;
+    fn parse_edges(
+        &self,
+        items: &mut ir::ItemsBuilder,
+        (module, idx): Self::EdgesExtra,
+    ) -> Result<(), traits::Error> {
+        let mut memory_section = None;
 
-    fn parse_items(&self, items: &mut ir::ItemsBuilder, idx: usize) -> Result<(), traits::Error> {
-        for (i, rel) in self.entries().iter().enumerate() {
-            let id = Id::entry(idx, i);
-            let size = serialized_size(rel.clone())?;
-            let mut name = String::with_capacity("reloc[]".len() + 4);
-            write!(&mut name, "reloc[{}]", i)?;
-            items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
+        for (sect_idx, s) in module.sections().iter().enumerate() {
+            match *s {
+                Section::Memory(_) => memory_section = Some(sect_idx),
+                _ => {}
+            }
         }
-        Ok(())
-    }
 
-    type EdgesExtra = ();
+        let num_imported_memories = module.import_count(wasmparser::ExternalKind::Memory)?;
+        let targets = ConstExprTargets::new(module)?;
+
+        for (i, d) in self.clone().into_iter().enumerate() {
+            let data_id = Id::entry(idx, i);
+            let d = d?;
+
+            // Passive segments aren't written into any memory until a
+            // `memory.init`.
+            if let wasmparser::DataKind::Active {
+                memory_index,
+                ref offset_expr,
+            } = d.kind
+            {
+                targets.parse_edges(items, data_id, offset_expr, ir::EdgeKind::Reference)?;
+                if let Some(address) =
+                    const_offset(offset_expr).and_then(|off| segment_address(off, d.data.len()))
+                {
+                    reference_strings_from_data(items, data_id, address, d.data);
+                }
 
-    fn parse_edges(&self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
+                let memory_i = memory_index as usize;
+                if let Some(memory_idx) = memory_section {
+                    if memory_i >= num_imported_memories {
+                        let entry_id = Id::entry(memory_idx, memory_i - num_imported_memories);
+                        items.add_edge(data_id, entry_id, ir::EdgeKind::Reference);
+                    }
+                }
+            }
+        }
         Ok(())
     }
 }
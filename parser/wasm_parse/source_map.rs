@@ -0,0 +1,517 @@
+//! Source maps, which map byte offsets in a wasm binary back to locations in
+//! the original source files. Toolchains like Emscripten emit these in place
+//! of DWARF.
+//!
+//! See https://sourcemaps.info/spec.html for the format. For wasm, the
+//! "generated column" of each mapping is a byte offset into the binary.
+
+use ir;
+use serde_json;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use traits;
+
+#[derive(Deserialize)]
+struct RawSourceMap {
+    version: u32,
+    #[serde(rename = "sourceRoot", default)]
+    source_root: Option<String>,
+    sources: Vec<String>,
+    #[serde(default)]
+    names: Vec<String>,
+    mappings: String,
+}
+
+/// A mapping from a byte offset in the binary to an original source location.
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    offset: u32,
+    // The source index, line and column, or `None` if the bytes starting at
+    // this offset don't map to any source.
+    location: Option<(usize, u32, u32)>,
+    // The index of the original name, which for wasm is the function that the
+    // bytes came from, if the segment has one.
+    name: Option<usize>,
+}
+
+/// A decoded source map.
+#[derive(Debug)]
+pub(crate) struct SourceMap {
+    sources: Vec<String>,
+    names: Vec<String>,
+    // Sorted by offset.
+    segments: Vec<Segment>,
+}
+
+impl SourceMap {
+    /// Parse the JSON source map in the given data.
+    pub fn parse(data: &[u8]) -> Result<SourceMap, traits::Error> {
+        let raw: RawSourceMap = serde_json::from_slice(data)?;
+        if raw.version != 3 {
+            return Err(traits::Error::with_msg(format!(
+                "unsupported source map version: {}",
+                raw.version
+            )));
+        }
+
+        let sources = match raw.source_root {
+            Some(ref root) if !root.is_empty() => raw
+                .sources
+                .iter()
+                .map(|source| {
+                    if root.ends_with('/') {
+                        format!("{}{}", root, source)
+                    } else {
+                        format!("{}/{}", root, source)
+                    }
+                })
+                .collect(),
+            _ => raw.sources,
+        };
+
+        let mut segments = decode_mappings(&raw.mappings)?;
+        for segment in &segments {
+            if let Some((source, _, _)) = segment.location {
+                if source >= sources.len() {
+                    return Err(traits::Error::with_msg(format!(
+                        "source map refers to unknown source {}",
+                        source
+                    )));
+                }
+            }
+            if let Some(name) = segment.name {
+                if name >= raw.names.len() {
+                    return Err(traits::Error::with_msg(format!(
+                        "source map refers to unknown name {}",
+                        name
+                    )));
+                }
+            }
+        }
+        segments.sort_by_key(|segment| segment.offset);
+
+        Ok(SourceMap {
+            sources,
+            names: raw.names,
+            segments,
+        })
+    }
+
+    /// Find the original source location for the bytes in the range
+    /// `start..end`. When they came from more than one source file, such as
+    /// when functions were inlined, pick the file that most of the bytes came
+    /// from, and the first location within it. The location's function is the
+    /// first name given to the bytes from that file.
+    pub fn locate(&self, start: u32, end: u32) -> Option<ir::Location> {
+        // The first segment that covers `start` is the last one that begins at
+        // or before it.
+        let first = match self.segments.binary_search_by_key(&start, |s| s.offset) {
+            Ok(mut i) => {
+                while i > 0 && self.segments[i - 1].offset == start {
+                    i -= 1;
+                }
+                i
+            }
+            Err(0) => 0,
+            Err(i) => i - 1,
+        };
+
+        let mut bytes_per_source = BTreeMap::new();
+        let mut first_location = BTreeMap::new();
+        let mut first_name = BTreeMap::new();
+
+        let covering = self.segments[first..]
+            .iter()
+            .enumerate()
+            .take_while(|&(_, segment)| segment.offset < end);
+        for (i, segment) in covering {
+            let (source, line, column) = match segment.location {
+                Some(location) => location,
+                None => continue,
+            };
+            let segment_end = self
+                .segments
+                .get(first + i + 1)
+                .map_or(end, |next| next.offset.min(end));
+            let segment_start = segment.offset.max(start);
+            if segment_end <= segment_start {
+                continue;
+            }
+
+            *bytes_per_source.entry(source).or_insert(0) += segment_end - segment_start;
+            first_location.entry(source).or_insert((line, column));
+            if let Some(name) = segment.name {
+                first_name.entry(source).or_insert(name);
+            }
+        }
+
+        // Break ties in favor of the source listed first.
+        let source = bytes_per_source
+            .iter()
+            .fold(
+                None,
+                |best: Option<(usize, u32)>, (&source, &bytes)| match best {
+                    Some((_, best_bytes)) if best_bytes >= bytes => best,
+                    _ => Some((source, bytes)),
+                },
+            )?
+            .0;
+
+        let (line, column) = first_location[&source];
+        let mut location = ir::Location::new(self.sources[source].clone(), line + 1, column + 1);
+        if let Some(&name) = first_name.get(&source) {
+            location.set_function(self.names[name].clone());
+        }
+        Some(location)
+    }
+}
+
+/// Decode the base64 VLQ encoded `mappings` field of a source map.
+fn decode_mappings(mappings: &str) -> Result<Vec<Segment>, traits::Error> {
+    let mut segments = vec![];
+
+    // All fields but the generated column are relative to the previous
+    // segment, even across lines.
+    let mut source: i64 = 0;
+    let mut line: i64 = 0;
+    let mut column: i64 = 0;
+    let mut name: i64 = 0;
+
+    for generated_line in mappings.split(';') {
+        // The generated column is relative to the previous segment on the same
+        // line.
+        let mut offset: i64 = 0;
+
+        for segment in generated_line.split(',').filter(|s| !s.is_empty()) {
+            let fields = decode_vlqs(segment)?;
+            offset = add_delta(offset, fields[0], "offset")?;
+
+            let location = match fields.len() {
+                1 => None,
+                4 | 5 => {
+                    source = add_delta(source, fields[1], "source")?;
+                    line = add_delta(line, fields[2], "line")?;
+                    column = add_delta(column, fields[3], "column")?;
+                    Some((
+                        in_range(source, "source")?,
+                        in_range(line, "line")?,
+                        in_range(column, "column")?,
+                    ))
+                }
+                n => {
+                    return Err(traits::Error::with_msg(format!(
+                        "source map segment has {} fields",
+                        n
+                    )))
+                }
+            };
+
+            // The optional fifth field is the index of the original name.
+            let name = match fields.get(4) {
+                Some(&delta) => {
+                    name = add_delta(name, delta, "name")?;
+                    Some(in_range(name, "name")?)
+                }
+                None => None,
+            };
+
+            segments.push(Segment {
+                offset: in_range(offset, "offset")?,
+                location,
+                name,
+            });
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Apply a relative field of a segment to its running value.
+fn add_delta(value: i64, delta: i64, field: &str) -> Result<i64, traits::Error> {
+    value
+        .checked_add(delta)
+        .ok_or_else(|| traits::Error::with_msg(format!("source map {} overflows", field)))
+}
+
+/// Convert a decoded field into the type it is stored as, if it fits.
+fn in_range<T: TryFrom<i64>>(value: i64, field: &str) -> Result<T, traits::Error> {
+    T::try_from(value).map_err(|_| {
+        traits::Error::with_msg(format!(
+            "source map has an out of range {}: {}",
+            field, value
+        ))
+    })
+}
+
+/// Decode the base64 VLQ values in a single segment.
+fn decode_vlqs(segment: &str) -> Result<Vec<i64>, traits::Error> {
+    let mut values = vec![];
+    let mut value: i64 = 0;
+    let mut shift = 0;
+
+    for byte in segment.bytes() {
+        let digit = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => {
+                return Err(traits::Error::with_msg(format!(
+                    "invalid base64 character in source map: {:?}",
+                    byte as char
+                )))
+            }
+        };
+
+        // Leave room for all five bits of the digit without overflowing.
+        if shift > 55 {
+            return Err(traits::Error::with_msg("source map value is too large"));
+        }
+        value += i64::from(digit & 0b1_1111) << shift;
+        shift += 5;
+
+        // The continuation bit.
+        if digit & 0b10_0000 == 0 {
+            // The sign is stored in the lowest bit.
+            let magnitude = value >> 1;
+            values.push(if value & 1 == 1 {
+                -magnitude
+            } else {
+                magnitude
+            });
+            value = 0;
+            shift = 0;
+        }
+    }
+
+    if shift != 0 {
+        return Err(traits::Error::with_msg("truncated value in source map"));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode a value the way a source map would.
+    fn vlq(value: i64) -> String {
+        const DIGITS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut rest = if value < 0 {
+            (-value << 1) | 1
+        } else {
+            value << 1
+        };
+        let mut encoded = String::new();
+        loop {
+            let mut digit = rest & 0b1_1111;
+            rest >>= 5;
+            if rest != 0 {
+                digit |= 0b10_0000;
+            }
+            encoded.push(DIGITS[digit as usize] as char);
+            if rest == 0 {
+                return encoded;
+            }
+        }
+    }
+
+    fn source_map(source_root: Option<&str>, sources: &[&str], mappings: &str) -> SourceMap {
+        named_source_map(source_root, sources, &[], mappings)
+    }
+
+    fn named_source_map(
+        source_root: Option<&str>,
+        sources: &[&str],
+        names: &[&str],
+        mappings: &str,
+    ) -> SourceMap {
+        let json = format!(
+            r#"{{"version":3,"sourceRoot":{},"sources":{},"names":{},"mappings":{}}}"#,
+            serde_json::to_string(&source_root).unwrap(),
+            serde_json::to_string(sources).unwrap(),
+            serde_json::to_string(names).unwrap(),
+            serde_json::to_string(mappings).unwrap(),
+        );
+        SourceMap::parse(json.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn vlq_sign_bit() {
+        assert_eq!(decode_vlqs("A").unwrap(), vec![0]);
+        assert_eq!(decode_vlqs("C").unwrap(), vec![1]);
+        assert_eq!(decode_vlqs("D").unwrap(), vec![-1]);
+        assert_eq!(decode_vlqs("F").unwrap(), vec![-2]);
+    }
+
+    #[test]
+    fn vlq_continuation() {
+        assert_eq!(decode_vlqs("gB").unwrap(), vec![16]);
+        assert_eq!(decode_vlqs("hB").unwrap(), vec![-16]);
+        assert_eq!(decode_vlqs("gBhBC").unwrap(), vec![16, -16, 1]);
+        for &value in &[0, 15, 16, -17, 1 << 20, -(1 << 40)] {
+            assert_eq!(decode_vlqs(&vlq(value)).unwrap(), vec![value]);
+        }
+    }
+
+    #[test]
+    fn vlq_truncated() {
+        assert!(decode_vlqs("g").is_err());
+        assert!(decode_vlqs("Cg").is_err());
+    }
+
+    #[test]
+    fn vlq_too_large() {
+        assert!(decode_vlqs("gggggggggggggB").is_err());
+    }
+
+    #[test]
+    fn vlq_invalid_character() {
+        assert!(decode_vlqs("A*").is_err());
+    }
+
+    #[test]
+    fn segment_fields() {
+        // One field: the bytes map to no source. Four fields: source, line and
+        // column. Five fields: also a name.
+        let segments = decode_mappings("A,CAAA,CAEG,CACEC,CAAAD").unwrap();
+        let locations: Vec<_> = segments
+            .iter()
+            .map(|s| (s.offset, s.location, s.name))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                (0, None, None),
+                (1, Some((0, 0, 0)), None),
+                (2, Some((0, 2, 3)), None),
+                (3, Some((0, 3, 5)), Some(1)),
+                (4, Some((0, 3, 5)), Some(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn segment_field_counts() {
+        assert!(decode_mappings("CA").is_err());
+        assert!(decode_mappings("CAA").is_err());
+        assert!(decode_mappings("CAAAAA").is_err());
+    }
+
+    #[test]
+    fn generated_column_resets_per_line() {
+        let segments = decode_mappings("EAAA;EAAA").unwrap();
+        let offsets: Vec<_> = segments.iter().map(|s| s.offset).collect();
+        assert_eq!(offsets, vec![2, 2]);
+    }
+
+    #[test]
+    fn negative_fields() {
+        assert!(decode_mappings("D").is_err());
+        assert!(decode_mappings("ADAA").is_err());
+        assert!(decode_mappings("AADA").is_err());
+        assert!(decode_mappings("AAAD").is_err());
+    }
+
+    #[test]
+    fn out_of_range_fields() {
+        let too_big = vlq(i64::from(u32::MAX) + 1);
+        assert!(decode_mappings(&too_big).is_err());
+        assert!(decode_mappings(&format!("AA{}A", too_big)).is_err());
+        assert!(decode_mappings(&format!("AAA{}", too_big)).is_err());
+    }
+
+    #[test]
+    fn overflowing_fields() {
+        // Every value fits on its own, but their running sum doesn't.
+        let huge = vlq(1 << 59);
+        let segment = format!("A{}AA", huge);
+        let mappings = vec![segment; 16].join(",");
+        assert!(decode_mappings(&mappings).is_err());
+    }
+
+    #[test]
+    fn source_root() {
+        let map = source_map(None, &["a.rs"], "");
+        assert_eq!(map.sources, vec!["a.rs"]);
+        let map = source_map(Some(""), &["a.rs"], "");
+        assert_eq!(map.sources, vec!["a.rs"]);
+        let map = source_map(Some("src"), &["a.rs"], "");
+        assert_eq!(map.sources, vec!["src/a.rs"]);
+        let map = source_map(Some("src/"), &["a.rs"], "");
+        assert_eq!(map.sources, vec!["src/a.rs"]);
+    }
+
+    #[test]
+    fn unknown_source() {
+        let json = r#"{"version":3,"sources":["a.rs"],"mappings":"ACAA"}"#;
+        assert!(SourceMap::parse(json.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn unknown_name() {
+        let json = r#"{"version":3,"sources":["a.rs"],"names":["f"],"mappings":"AAAAC"}"#;
+        assert!(SourceMap::parse(json.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let json = r#"{"version":2,"sources":[],"mappings":""}"#;
+        assert!(SourceMap::parse(json.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn locate_most_bytes() {
+        // a.rs covers 0..2 and b.rs covers 2..8.
+        let map = source_map(None, &["a.rs", "b.rs"], "AAAA,ECEC");
+        assert_eq!(map.locate(0, 8), Some(ir::Location::new("b.rs", 3, 2)));
+        assert_eq!(map.locate(0, 2), Some(ir::Location::new("a.rs", 1, 1)));
+    }
+
+    #[test]
+    fn locate_ties_pick_first_source() {
+        // b.rs covers 0..2 and a.rs covers 2..4: the same number of bytes, so
+        // a.rs wins because it is listed first.
+        let map = source_map(None, &["a.rs", "b.rs"], "ACAA,EDAA");
+        assert_eq!(map.locate(0, 4), Some(ir::Location::new("a.rs", 1, 1)));
+    }
+
+    #[test]
+    fn locate_before_first_mapping() {
+        let map = source_map(None, &["a.rs"], "EAAA");
+        assert_eq!(map.locate(0, 2), None);
+        assert_eq!(map.locate(0, 4), Some(ir::Location::new("a.rs", 1, 1)));
+    }
+
+    #[test]
+    fn locate_unmapped_bytes() {
+        // Bytes 2..4 map to no source at all.
+        let map = source_map(None, &["a.rs"], "AAAA,E");
+        assert_eq!(map.locate(2, 4), None);
+        assert_eq!(map.locate(0, 4), Some(ir::Location::new("a.rs", 1, 1)));
+    }
+
+    #[test]
+    fn locate_function_name() {
+        // f covers 0..2 in a.rs, g covers 2..8 in b.rs, and h covers 8..10 in
+        // b.rs again.
+        let map = named_source_map(
+            None,
+            &["a.rs", "b.rs"],
+            &["f", "g", "h"],
+            "AAAAA,ECECC,MAAAC",
+        );
+        let location = map.locate(0, 10).unwrap();
+        assert_eq!(location.file(), "b.rs");
+        assert_eq!(location.function(), Some("g"));
+        assert_eq!(map.locate(0, 2).unwrap().function(), Some("f"));
+        assert_eq!(map.locate(8, 10).unwrap().function(), Some("h"));
+    }
+
+    #[test]
+    fn locate_without_names() {
+        let map = source_map(None, &["a.rs"], "AAAA");
+        assert_eq!(map.locate(0, 2).unwrap().function(), None);
+    }
+}
@@ -8,9 +8,10 @@ extern crate csv;
 extern crate failure;
 extern crate gimli;
 extern crate regex;
+extern crate serde_json;
 
-extern crate parity_wasm as wasm;
 extern crate twiggy_ir as ir;
+extern crate wasmparser;
 
 use std::fmt;
 use std::io;
@@ -48,7 +49,7 @@ enum ErrorInner {
     Io(#[cause] io::Error),
 
     #[fail(display = "WASM error: {}", _0)]
-    Wasm(#[cause] wasm::elements::Error),
+    Wasm(#[cause] wasmparser::BinaryReaderError),
 
     #[fail(display = "formatting error: {}", _0)]
     Fmt(#[cause] fmt::Error),
@@ -61,6 +62,9 @@ enum ErrorInner {
 
     #[fail(display = "Gimli error: {}", _0)]
     Gimli(#[cause] gimli::Error),
+
+    #[fail(display = "JSON error: {}", _0)]
+    Json(#[cause] serde_json::Error),
 }
 
 impl<'a> From<&'a str> for Error {
@@ -77,8 +81,8 @@ impl From<io::Error> for Error {
     }
 }
 
-impl From<wasm::elements::Error> for Error {
-    fn from(e: wasm::elements::Error) -> Error {
+impl From<wasmparser::BinaryReaderError> for Error {
+    fn from(e: wasmparser::BinaryReaderError) -> Error {
         Error {
             inner: Box::new(ErrorInner::Wasm(e)),
         }
@@ -117,6 +121,14 @@ impl From<gimli::Error> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Error {
+        Error {
+            inner: Box::new(ErrorInner::Json(e)),
+        }
+    }
+}
+
 impl Error {
     /// Create an error with the given message.
     pub fn with_msg<S: Into<String>>(msg: S) -> Error {
@@ -124,6 +136,11 @@ impl Error {
             inner: Box::new(ErrorInner::Msg(msg.into())),
         }
     }
+
+    /// Is this an error from decoding the wasm binary itself?
+    pub fn is_wasm_decode_error(&self) -> bool {
+        matches!(*self.inner, ErrorInner::Wasm(_))
+    }
 }
 
 #[test]
@@ -159,6 +176,13 @@ pub enum OutputFormat {
     /// JavaScript Object Notation format.
     #[cfg(feature = "emit_json")]
     Json,
+    /// Scalable Vector Graphics, for analyses that can draw a picture.
+    #[cfg(feature = "emit_svg")]
+    Svg,
+    /// Folded stacks, one per line, as read by flamegraph tools such as
+    /// `inferno` and `flamegraph.pl`.
+    #[cfg(feature = "emit_folded")]
+    Folded,
 }
 
 #[cfg(feature = "emit_text")]
@@ -181,6 +205,10 @@ impl FromStr for OutputFormat {
             "json" => Ok(OutputFormat::Json),
             #[cfg(feature = "emit_csv")]
             "csv" => Ok(OutputFormat::Csv),
+            #[cfg(feature = "emit_svg")]
+            "svg" => Ok(OutputFormat::Svg),
+            #[cfg(feature = "emit_folded")]
+            "folded" => Ok(OutputFormat::Folded),
             _ => Err(Error::with_msg(format!("Unknown output format: {}", s))),
         }
     }
@@ -205,6 +233,10 @@ pub trait Emit {
             OutputFormat::Csv => self.emit_csv(items, destination),
             #[cfg(feature = "emit_json")]
             OutputFormat::Json => self.emit_json(items, destination),
+            #[cfg(feature = "emit_svg")]
+            OutputFormat::Svg => self.emit_svg(items, destination),
+            #[cfg(feature = "emit_folded")]
+            OutputFormat::Folded => self.emit_folded(items, destination),
         }
     }
 
@@ -225,6 +257,25 @@ pub trait Emit {
     /// Emit JSON.
     #[cfg(feature = "emit_json")]
     fn emit_json(&self, items: &ir::Items, destination: &mut io::Write) -> Result<(), Error>;
+
+    /// Emit an SVG image. Most analyses don't have a picture to draw, so by
+    /// default this is an error.
+    #[cfg(feature = "emit_svg")]
+    fn emit_svg(&self, _items: &ir::Items, _destination: &mut io::Write) -> Result<(), Error> {
+        Err(Error::with_msg(
+            "this analysis doesn't support the `svg` output format",
+        ))
+    }
+
+    /// Emit folded stacks, such as `root;parent;child 42`, where the number is
+    /// a size in bytes. Only trees and paths have stacks to fold, so by default
+    /// this is an error.
+    #[cfg(feature = "emit_folded")]
+    fn emit_folded(&self, _items: &ir::Items, _destination: &mut io::Write) -> Result<(), Error> {
+        Err(Error::with_msg(
+            "this analysis doesn't support the `folded` output format",
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,51 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test that passive segments are retained by the bulk memory and reference
+    ;; types instructions that use them.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wat2wasm --debug-names --enable-bulk-memory --enable-reference-types \
+    ;;     bulk_memory.wat -o bulk_memory.wasm
+    ;; -------------------------------------------------------------------------
+
+    (memory 1)
+    (table 2 funcref)
+
+    ;; A passive data segment, only copied into memory by `memory.init`.
+    (data $passive "hello")
+
+    ;; An active data segment, which is always placed in memory.
+    (data (i32.const 16) "world")
+
+    ;; A passive element segment, only copied into the table by `table.init`.
+    (elem $passiveElem func $passiveFunc)
+
+    ;; An active element segment, which is always placed in the table.
+    (elem (i32.const 0) func $activeFunc)
+
+    ;; A declarative element segment, making `$refFunc` usable with `ref.func`.
+    (elem declare func $refFunc)
+
+    (func $passiveFunc)
+    (func $activeFunc)
+    (func $refFunc)
+
+    ;; Initialize memory and the table from the passive segments, drop them,
+    ;; and take a reference to `$refFunc`.
+    (func $init (export "init")
+        i32.const 0
+        i32.const 0
+        i32.const 5
+        memory.init $passive
+        data.drop $passive
+        i32.const 1
+        i32.const 0
+        i32.const 1
+        table.init $passiveElem
+        elem.drop $passiveElem
+        ref.func $refFunc
+        drop))
@@ -0,0 +1,342 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test splitting C++ template instantiations into their templates and
+    ;; template arguments, including member functions of class templates,
+    ;; operator templates like `operator<` and templates instantiated with
+    ;; lambdas. The symbols come from compiling C++ code along these lines with
+    ;; g++, and the function bodies are padding:
+    ;;
+    ;;     template <typename T> T twice(T x);
+    ;;     template <typename K, typename V> struct Table {
+    ;;         void insert(const K& k, const V& v);
+    ;;     };
+    ;;     template <typename T>
+    ;;     bool operator<(const std::vector<T>& a, const Point& p);
+    ;;     template <typename F> int call(F f);
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse cpp_templates.wat -o cpp_templates.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $main
+        call $_Z5twiceIiET_S0_
+        call $_Z5twiceIdET_S0_
+        call $_Z5twiceINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEEET_S6_
+        call $_ZN5TableINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEEiE6insertERKS5_RKi
+        call $_ZN5TableINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEEdE6insertERKS5_RKd
+        call $_ZN5TableIiSt6vectorIiSaIiEEE6insertERKiRKS2_
+        call $_ZltIiEbRKSt6vectorIT_SaIS1_EERK5Point
+        call $_ZltINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEEEbRKSt6vectorIT_SaIS7_EERK5Point
+        call $_Z4callIZ4mainEUliE_EiT_
+        call $_Z4callIZ4mainEUliE0_EiT_
+        nop
+        nop
+    )
+
+    (func $_Z5twiceIiET_S0_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_Z5twiceIdET_S0_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_Z5twiceINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEEET_S6_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_ZN5TableINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEEiE6insertERKS5_RKi
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_ZN5TableINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEEdE6insertERKS5_RKd
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_ZN5TableIiSt6vectorIiSaIiEEE6insertERKiRKS2_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_ZltIiEbRKSt6vectorIT_SaIS1_EERK5Point
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_ZltINSt7__cxx1112basic_stringIcSt11char_traitsIcESaIcEEEEbRKSt6vectorIT_SaIS7_EERK5Point
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_Z4callIZ4mainEUliE_EiT_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_Z4callIZ4mainEUliE0_EiT_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (export "main" (func $main))
+)
@@ -0,0 +1,96 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test the `twiggy cycles` command. It contains a pair of mutually
+    ;; recursive functions, entered from two exports, a self-recursive
+    ;; function, and a cycle of three functions that is only entered at one of
+    ;; them.
+    ;;
+    ;;     [is_even]  [is_odd]      [fact]       [walk]
+    ;;         |          |          |  ^           |
+    ;;         v          v          +--+           v
+    ;;      'even' <--> 'odd'                 'visit' --> 'expand'
+    ;;                                           ^            |
+    ;;                                           |            v
+    ;;                                           +------- 'children'
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse cycles.wat -o cycles.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $even (param i32) (result i32)
+        local.get 0
+        i32.eqz
+        if (result i32)
+            i32.const 1
+        else
+            local.get 0
+            i32.const 1
+            i32.sub
+            call $odd
+        end)
+
+    (func $odd (param i32) (result i32)
+        local.get 0
+        i32.eqz
+        if (result i32)
+            i32.const 0
+        else
+            local.get 0
+            i32.const 1
+            i32.sub
+            call $even
+        end)
+
+    (func $is_even (export "is_even") (param i32) (result i32)
+        local.get 0
+        call $even)
+
+    (func $is_odd (export "is_odd") (param i32) (result i32)
+        local.get 0
+        call $odd)
+
+    (func $fact (export "fact") (param i32) (result i32)
+        local.get 0
+        i32.const 2
+        i32.lt_u
+        if (result i32)
+            i32.const 1
+        else
+            local.get 0
+            local.get 0
+            i32.const 1
+            i32.sub
+            call $fact
+            i32.mul
+        end)
+
+    (func $walk (export "walk") (param i32) (result i32)
+        local.get 0
+        call $visit)
+
+    (func $visit (param i32) (result i32)
+        local.get 0
+        i32.eqz
+        if (result i32)
+            i32.const 0
+        else
+            local.get 0
+            call $expand
+        end)
+
+    (func $expand (param i32) (result i32)
+        local.get 0
+        i32.const 2
+        i32.div_u
+        call $children)
+
+    (func $children (param i32) (result i32)
+        local.get 0
+        call $visit
+        i32.const 1
+        i32.add)
+)
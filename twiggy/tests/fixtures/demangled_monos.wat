@@ -0,0 +1,73 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test monomorphizations whose names are already demangled in the "name"
+    ;; section, as toolchains that demangle C++ names for profiling write them.
+    ;; The template arguments are split out of the demangled text, and the
+    ;; function bodies are padding.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse demangled_monos.wat -o demangled_monos.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $main (@name "main")
+        call $sort_int
+        call $sort_float
+        call $sort_pair
+        call $insert_int_double
+        call $insert_string_double
+        call $insert_int_vector
+        nop
+        nop
+    )
+
+    (func $sort_int (@name "void sort<int>(int*, int*)")
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $sort_float (@name "void sort<float>(float*, float*)")
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $sort_pair (@name "void sort<std::pair<int, float> >(std::pair<int, float>*, std::pair<int, float>*)")
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $insert_int_double (@name "Table<int, double>::insert(int const&, double const&)")
+        nop
+        nop
+        nop
+    )
+
+    (func $insert_string_double (@name "Table<std::string, double>::insert(std::string const&, double const&)")
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $insert_int_vector (@name "Table<int, std::vector<int> >::insert(int const&, std::vector<int> const&)")
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (export "main" (func $main))
+)
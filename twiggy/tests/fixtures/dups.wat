@@ -0,0 +1,97 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test the `twiggy dups` command. It contains three identical copies of a
+    ;; clamping function, two functions that only differ in which logging
+    ;; function they call, two that only differ in how their locals are
+    ;; numbered, and a function with no duplicates.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse dups.wat -o dups.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $clamp_u8 (param i32) (result i32)
+        local.get 0
+        i32.const 255
+        local.get 0
+        i32.const 255
+        i32.lt_u
+        select)
+
+    (func $clamp_u8_copy (param i32) (result i32)
+        local.get 0
+        i32.const 255
+        local.get 0
+        i32.const 255
+        i32.lt_u
+        select)
+
+    (func $clamp_u8_again (param i32) (result i32)
+        local.get 0
+        i32.const 255
+        local.get 0
+        i32.const 255
+        i32.lt_u
+        select)
+
+    (func $log_info (param i32)
+        local.get 0
+        i32.const 1
+        i32.store)
+
+    (func $log_warn (param i32)
+        local.get 0
+        i32.const 2
+        i32.store)
+
+    (func $sum_and_log_info (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add
+        call $log_info
+        local.get 0
+        local.get 1
+        i32.add)
+
+    (func $sum_and_log_warn (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add
+        call $log_warn
+        local.get 0
+        local.get 1
+        i32.add)
+
+    (func $scale_first (param i32 i32) (result i32)
+        local.get 0
+        i32.const 3
+        i32.mul
+        local.get 1
+        i32.sub)
+
+    (func $scale_second (param i32 i32) (result i32)
+        local.get 1
+        i32.const 3
+        i32.mul
+        local.get 0
+        i32.sub)
+
+    (func $unique (param i32) (result i32)
+        local.get 0
+        i32.const 7
+        i32.rem_u)
+
+    (memory 1)
+
+    (export "clamp_u8" (func $clamp_u8))
+    (export "clamp_u8_copy" (func $clamp_u8_copy))
+    (export "clamp_u8_again" (func $clamp_u8_again))
+    (export "sum_and_log_info" (func $sum_and_log_info))
+    (export "sum_and_log_warn" (func $sum_and_log_warn))
+    (export "scale_first" (func $scale_first))
+    (export "scale_second" (func $scale_second))
+    (export "unique" (func $unique))
+)
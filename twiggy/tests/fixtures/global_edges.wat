@@ -0,0 +1,35 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test the edges from global initializers and segment offsets to the items
+    ;; they reference, and from segments to the memory and table they fill.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse global_edges.wat -o global_edges.wasm
+    ;; -------------------------------------------------------------------------
+
+    (import "env" "base" (global $base i32))
+
+    ;; Initialized from an imported global, which has no item of its own.
+    (global $copy i32 (global.get $base))
+
+    ;; Used as the offset of both segments below.
+    (global $offset i32 (i32.const 16))
+
+    ;; Initialized with a reference to `$referenced`.
+    (global $funcref funcref (ref.func $referenced))
+
+    (memory 1)
+    (table 2 funcref)
+
+    (data (global.get $offset) "hello")
+    (elem (global.get $offset) func $inTable)
+
+    (func $referenced)
+    (func $inTable)
+
+    (export "copy" (global $copy))
+    (export "funcref" (global $funcref)))
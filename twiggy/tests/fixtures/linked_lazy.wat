@@ -0,0 +1,37 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test linking several modules into one graph. It is loaded lazily by
+    ;; `linked_main.wasm`, which only imports `render`, so `render_legacy` and
+    ;; the code that only it uses are dead.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse linked_lazy.wat -o linked_lazy.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $render (export "render") (param i32) (result i32)
+        local.get 0
+        call $layout)
+
+    (func $layout (param i32) (result i32)
+        local.get 0
+        i32.const 1
+        i32.add)
+
+    (func $render_legacy (export "render_legacy") (param i32) (result i32)
+        local.get 0
+        call $legacy_layout
+        call $layout)
+
+    (func $legacy_layout (param i32) (result i32)
+        local.get 0
+        i32.const 3
+        i32.mul
+        i32.const 7
+        i32.add
+        i32.const 5
+        i32.rem_u)
+)
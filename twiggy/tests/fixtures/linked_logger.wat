@@ -0,0 +1,18 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test linking several modules into one graph. It exports a `log` function
+    ;; just like the `env` module that `linked_main.wasm` imports `log` from,
+    ;; but it is not that module, so nothing imports its `log`.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse linked_logger.wat -o linked_logger.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $log (export "log") (param i32)
+        local.get 0
+        drop)
+)
@@ -0,0 +1,28 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test linking several modules into one graph. It is the entry module of a
+    ;; small application, and calls into `linked_lazy.wasm`, which it loads
+    ;; lazily, through its imports.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse linked_main.wat -o linked_main.wasm
+    ;; -------------------------------------------------------------------------
+
+    (import "linked_lazy" "render" (func $render (param i32) (result i32)))
+    (import "env" "log" (func $log (param i32)))
+
+    (func $main (export "main") (result i32)
+        i32.const 1
+        call $render
+        call $log
+        i32.const 0)
+
+    (func $unused_in_main (param i32) (result i32)
+        local.get 0
+        i32.const 2
+        i32.mul)
+)
@@ -0,0 +1,31 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test the `twiggy info` command. It contains the `producers` and
+    ;; `target_features` custom sections that toolchains like LLVM emit.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse producers.wat -o producers.wasm
+    ;; -------------------------------------------------------------------------
+
+    (@producers
+        (language "Rust" "")
+        (processed-by "rustc" "1.70.0 (90c541806 2023-05-31)")
+        (processed-by "wasm-bindgen" "0.2.87"))
+
+    ;; Four features: `mutable-globals` and `sign-ext` are used, `atomics` is
+    ;; required, and `simd128` is disallowed.
+    (@custom "target_features"
+        "\04"
+        "+\0fmutable-globals"
+        "+\08sign-ext"
+        "=\07atomics"
+        "-\07simd128")
+
+    (memory 1)
+
+    (func $answer (export "answer") (result i32)
+        i32.const 42))
@@ -0,0 +1,21 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test that every type in an explicit recursion group gets its own item
+    ;; and index.
+    ;; -------------------------------------------------------------------------
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse rec_groups.wat -o rec_groups.wasm
+    ;; -------------------------------------------------------------------------
+
+    ;; One recursion group holding two types: `type[0]` and `type[1]`.
+    (rec
+        (type (func))
+        (type (func (param i32))))
+
+    ;; An implicit recursion group, which is `type[2]` rather than `type[1]`.
+    (type (func (result i32)))
+
+    (func (export "answer") (type 2)
+        i32.const 42))
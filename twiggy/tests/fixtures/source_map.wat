@@ -0,0 +1,34 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test attributing code to source files with a source map. Its
+    ;; `sourceMappingURL` custom section names `source_map.wasm.map`, which maps
+    ;; `$add` and `$sub` to `src/lib.rs`, and `$double` to `src/util.rs`, except
+    ;; for its last few bytes, which were inlined from `src/lib.rs`. The map
+    ;; also names the original function of each of these.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, since the source map refers to byte
+    ;; offsets within it. Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse source_map.wat -o source_map.wasm
+    ;; -------------------------------------------------------------------------
+
+    (@custom "sourceMappingURL" "\13source_map.wasm.map")
+
+    (func $add (export "add") (param $a i32) (param $b i32) (result i32)
+        local.get $a
+        local.get $b
+        i32.add)
+
+    (func $sub (export "sub") (param $a i32) (param $b i32) (result i32)
+        local.get $a
+        local.get $b
+        i32.sub)
+
+    (func $double (export "double") (param $a i32) (result i32)
+        local.get $a
+        local.get $a
+        call $add
+        i32.const 0
+        i32.add))
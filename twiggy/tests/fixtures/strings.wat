@@ -0,0 +1,69 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test the `twiggy strings` command. Its data holds a panic message laid
+    ;; out right before the source file path of the panic's location, the
+    ;; location itself, which points to the path, an assertion message, a
+    ;; format string, and a greeting and file path that are both duplicated.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse strings.wat -o strings.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $panic (param i32 i32 i32)
+        unreachable)
+
+    (func $unwrap_failed
+        i32.const 1024
+        i32.const 43
+        i32.const 1080
+        call $panic)
+
+    (func $check (param i32)
+        local.get 0
+        i32.const 10
+        i32.ge_u
+        if
+            i32.const 1100
+            i32.const 28
+            i32.const 1080
+            call $panic
+        end)
+
+    (func $show (result i32)
+        i32.const 1129)
+
+    (func $greet (result i32)
+        i32.const 1139)
+
+    (func $greet_again (result i32)
+        i32.const 1200)
+
+    (memory 1)
+
+    ;; The panic message at 1024, its file path at 1067, and its location at
+    ;; 1080: a pointer to the path, the path's length, the line and the column.
+    (data (i32.const 1024)
+        "called `Option::unwrap()` on a `None` value"
+        "src/lib.rs"
+        "\00\00\00"
+        "\2b\04\00\00" "\0a\00\00\00" "\07\00\00\00" "\05\00\00\00")
+
+    (data (i32.const 1100)
+        "assertion failed: index < 10" "\00"
+        "value: {}" "\00"
+        "hello world")
+
+    (data (i32.const 1200)
+        "hello world" "\00"
+        "src/lib.rs")
+
+    (export "unwrap_failed" (func $unwrap_failed))
+    (export "check" (func $check))
+    (export "show" (func $show))
+    (export "greet" (func $greet))
+    (export "greet_again" (func $greet_again))
+)
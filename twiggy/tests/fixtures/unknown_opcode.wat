@@ -0,0 +1,33 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test that `twiggy` reports a section it can't decode as a whole, and still
+    ;; parses the rest of the module.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, and then has the single `i32.add`
+    ;; opcode (0x6a) in `$add` replaced with 0xff, which is not an opcode.
+    ;; Compile this file using the following commands:
+    ;;
+    ;; wasm-tools parse unknown_opcode.wat -o unknown_opcode.wasm
+    ;; python3 -c "import sys; p = sys.argv[1]; b = open(p, 'rb').read(); \
+    ;;     open(p, 'wb').write(b.replace(b'\x20\x00\x20\x01\x6a', b'\x20\x00\x20\x01\xff'))" \
+    ;;     unknown_opcode.wasm
+    ;;
+    ;; `truncated.wasm` is `unknown_opcode.wasm` with its last 5 bytes cut off,
+    ;; to test the fallback for modules whose sections can't be told apart:
+    ;;
+    ;; head -c -5 unknown_opcode.wasm > truncated.wasm
+    ;; -------------------------------------------------------------------------
+
+    (@producers
+        (language "Rust" ""))
+
+    (memory 1)
+
+    (data (i32.const 0) "some static data")
+
+    (func $add (export "add") (param $a i32) (param $b i32) (result i32)
+        local.get $a
+        local.get $b
+        i32.add))
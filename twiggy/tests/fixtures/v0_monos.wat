@@ -0,0 +1,418 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test monomorphizations of Rust generic functions whose symbols use the
+    ;; v0 mangling scheme, which includes their generic arguments. The symbols
+    ;; come from compiling a small crate named `v0_monos` with
+    ;; `-C symbol-mangling-version=v0`, and the function bodies are padding.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse v0_monos.wat -o v0_monos.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $_RNvCsdfd7mGykvEd_8v0_monos4main
+        call $_RINvCsdfd7mGykvEd_8v0_monos3sumhEB2_
+        call $_RINvCsdfd7mGykvEd_8v0_monos3summEB2_
+        call $_RNvMCsdfd7mGykvEd_8v0_monosINtB2_5StackhE4pushB2_
+        call $_RNvMCsdfd7mGykvEd_8v0_monosINtB2_5StackReE4pushB2_
+        nop
+        nop
+    )
+
+    (func $_RINvCsdfd7mGykvEd_8v0_monos3sumhEB2_
+        call $_RNvMNtCsgEmfK2I1SDS_4core5sliceSh4iterCsdfd7mGykvEd_8v0_monos
+        call $_RNCINvCsdfd7mGykvEd_8v0_monos3sumhE0B4_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RINvCsdfd7mGykvEd_8v0_monos3summEB2_
+        call $_RNvMNtCsgEmfK2I1SDS_4core5sliceSm4iterCsdfd7mGykvEd_8v0_monos
+        call $_RNCINvCsdfd7mGykvEd_8v0_monos3summE0B4_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNCINvCsdfd7mGykvEd_8v0_monos3sumhE0B4_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNCINvCsdfd7mGykvEd_8v0_monos3summE0B4_
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvMNtCsgEmfK2I1SDS_4core5sliceSh4iterCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvMNtCsgEmfK2I1SDS_4core5sliceSm4iterCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvMCsdfd7mGykvEd_8v0_monosINtB2_5StackhE4pushB2_
+        call $_RNvMsF_NtCslNYArtu3iFV_5alloc3vecINtB5_3VechE4pushCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvMCsdfd7mGykvEd_8v0_monosINtB2_5StackReE4pushB2_
+        call $_RNvMsF_NtCslNYArtu3iFV_5alloc3vecINtB5_3VecReE4pushCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvMsF_NtCslNYArtu3iFV_5alloc3vecINtB5_3VechE4pushCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvMsF_NtCslNYArtu3iFV_5alloc3vecINtB5_3VecReE4pushCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RINvNtCsgEmfK2I1SDS_4core3ptr13drop_in_placeINtCsdfd7mGykvEd_8v0_monos5StackhEEBJ_
+        call $_RINvNtCsgEmfK2I1SDS_4core3ptr13drop_in_placeINtNtCslNYArtu3iFV_5alloc3vec3VechEECsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RINvNtCsgEmfK2I1SDS_4core3ptr13drop_in_placeINtCsdfd7mGykvEd_8v0_monos5StackReEEBJ_
+        call $_RINvNtCsgEmfK2I1SDS_4core3ptr13drop_in_placeINtNtCslNYArtu3iFV_5alloc3vec3VecReEECsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RINvNtCsgEmfK2I1SDS_4core3ptr13drop_in_placeINtNtCslNYArtu3iFV_5alloc3vec3VechEECsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RINvNtCsgEmfK2I1SDS_4core3ptr13drop_in_placeINtNtCslNYArtu3iFV_5alloc3vec3VecReEECsdfd7mGykvEd_8v0_monos
+        call $_RNvXso_NtCslNYArtu3iFV_5alloc3vecINtB5_3VecReENtNtNtCsgEmfK2I1SDS_4core3ops4drop4Drop4dropCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvXso_NtCslNYArtu3iFV_5alloc3vecINtB5_3VecReENtNtNtCsgEmfK2I1SDS_4core3ops4drop4Drop4dropCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvXs1_NtCsgEmfK2I1SDS_4core7converthINtB5_4IntoyE4intoCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (func $_RNvXs1_NtCsgEmfK2I1SDS_4core7convertmINtB5_4IntoyE4intoCsdfd7mGykvEd_8v0_monos
+        nop
+        nop
+        nop
+        nop
+        nop
+        nop
+    )
+
+    (export "main" (func $_RNvCsdfd7mGykvEd_8v0_monos4main))
+    (export "drop_stack_u8" (func $_RINvNtCsgEmfK2I1SDS_4core3ptr13drop_in_placeINtCsdfd7mGykvEd_8v0_monos5StackhEEBJ_))
+    (export "drop_stack_str" (func $_RINvNtCsgEmfK2I1SDS_4core3ptr13drop_in_placeINtCsdfd7mGykvEd_8v0_monos5StackReEEBJ_))
+    (export "u8_into" (func $_RNvXs1_NtCsgEmfK2I1SDS_4core7converthINtB5_4IntoyE4intoCsdfd7mGykvEd_8v0_monos))
+    (export "u32_into" (func $_RNvXs1_NtCsgEmfK2I1SDS_4core7convertmINtB5_4IntoyE4intoCsdfd7mGykvEd_8v0_monos))
+)
@@ -74,6 +74,55 @@ macro_rules! test {
     }
 }
 
+/// Like `test!`, but for `twiggy` invocations that should fail. The expectation
+/// is the end of what `twiggy` writes to stderr.
+macro_rules! test_error {
+    ( $name:ident $( , $args:expr )* ) => {
+        #[test]
+        fn $name() {
+            let output = Command::new("cargo")
+                .arg("run")
+                .arg("--")
+                $(
+                    .arg($args)
+                )*
+                .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests"))
+                .output()
+                .unwrap();
+
+            assert!(
+                !output.status.success(),
+                "should have failed to run `twiggy`\n\n\
+                 ============================== stdout ==============================\n\n\
+                 {}\n\n",
+                String::from_utf8_lossy(&output.stdout),
+            );
+
+            let expected_path = concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/expectations/",
+                stringify!($name)
+            );
+
+            let expected = slurp(expected_path).unwrap_or(vec![]);
+            let expected = String::from_utf8_lossy(&expected);
+            let expected = expected.trim();
+
+            let actual = String::from_utf8_lossy(&output.stderr);
+            let actual = actual.trim();
+
+            assert!(
+                actual.ends_with(expected),
+                "\n`twiggy` did not fail with the expected error!\n\n\
+                 --- {}\n{}\n\n+++ actual stderr\n{}\n",
+                expected_path,
+                expected,
+                actual,
+            );
+        }
+    }
+}
+
 test!(
     top_wee_alloc,
     "top",
@@ -402,3 +451,693 @@ test!(
 );
 
 test!(elf_top_hello_world_rs, "top", "./fixtures/hello_elf");
+
+test!(
+    paths_bulk_memory_passive_segments,
+    "paths",
+    "./fixtures/bulk_memory.wasm",
+    "data[0]",
+    "elem[0]",
+    "refFunc"
+);
+
+test!(
+    garbage_bulk_memory,
+    "garbage",
+    "./fixtures/bulk_memory.wasm"
+);
+
+test!(top_rec_groups, "top", "./fixtures/rec_groups.wasm");
+
+test!(
+    paths_rec_groups,
+    "paths",
+    "./fixtures/rec_groups.wasm",
+    "type[2]",
+    "type[1]"
+);
+
+test!(info_producers, "info", "./fixtures/producers.wasm");
+
+test!(
+    info_producers_json,
+    "info",
+    "./fixtures/producers.wasm",
+    "-f",
+    "json"
+);
+
+test!(
+    info_producers_csv,
+    "info",
+    "./fixtures/producers.wasm",
+    "-f",
+    "csv"
+);
+
+test!(info_wee_alloc, "info", "./fixtures/wee_alloc.wasm");
+
+test!(
+    paths_global_edges,
+    "paths",
+    "./fixtures/global_edges.wasm",
+    "global[1]",
+    "memory[0]",
+    "table[0]",
+    "referenced"
+);
+
+test!(top_unknown_opcode, "top", "./fixtures/unknown_opcode.wasm");
+
+test!(
+    info_unknown_opcode,
+    "info",
+    "./fixtures/unknown_opcode.wasm"
+);
+
+test!(top_truncated, "top", "./fixtures/truncated.wasm");
+
+test!(info_source_map, "info", "./fixtures/source_map.wasm");
+
+test!(
+    top_source_map_json,
+    "top",
+    "./fixtures/source_map.wasm",
+    "-f",
+    "json"
+);
+
+test!(
+    info_source_map_json,
+    "info",
+    "./fixtures/source_map.wasm",
+    "-f",
+    "json"
+);
+
+test!(
+    info_explicit_source_map,
+    "info",
+    "./fixtures/source_map.wasm",
+    "--source-map",
+    "./fixtures/source_map.wasm.map"
+);
+
+test!(
+    paths_bulk_memory_edge_kinds,
+    "paths",
+    "./fixtures/bulk_memory.wasm",
+    "data[0]",
+    "elem[0]",
+    "refFunc",
+    "--edge-kinds"
+);
+
+test!(
+    paths_global_edges_edge_kinds_json,
+    "paths",
+    "./fixtures/global_edges.wasm",
+    "global[1]",
+    "--edge-kinds",
+    "-f",
+    "json"
+);
+
+test!(
+    dominators_wee_alloc_ignore_type_edges,
+    "dominators",
+    "./fixtures/wee_alloc.wasm",
+    "--ignore-edges",
+    "type"
+);
+
+test!(
+    garbage_wee_alloc_ignore_data_edges,
+    "garbage",
+    "./fixtures/wee_alloc.wasm",
+    "--ignore-edges",
+    "data"
+);
+
+test_error!(
+    garbage_wee_alloc_ignore_root_edges,
+    "garbage",
+    "./fixtures/wee_alloc.wasm",
+    "--ignore-edges",
+    "root"
+);
+
+test!(snapshot_wee_alloc, "snapshot", "./fixtures/wee_alloc.wasm");
+
+test!(
+    snapshot_unknown_opcode,
+    "snapshot",
+    "./fixtures/unknown_opcode.wasm"
+);
+
+test!(
+    top_wee_alloc_snapshot,
+    "top",
+    "./fixtures/wee_alloc.snapshot.json",
+    "--retained",
+    "-n",
+    "10"
+);
+
+test!(
+    diff_wee_alloc_snapshot,
+    "diff",
+    "./fixtures/wee_alloc.snapshot.json",
+    "./fixtures/wee_alloc.2.wasm"
+);
+
+test!(
+    paths_wee_alloc_name_without_hash,
+    "paths",
+    "./fixtures/wee_alloc.wasm",
+    "wee_alloc::alloc_with_refill"
+);
+
+test!(
+    paths_wee_alloc_raw_name,
+    "paths",
+    "./fixtures/wee_alloc.wasm",
+    "_ZN9wee_alloc17alloc_with_refill17hb32c1bbce9ebda8eE"
+);
+
+test_error!(
+    dominators_monos_shared_name,
+    "dominators",
+    "./fixtures/monos.wasm",
+    "alloc::slice::merge_sort",
+    "-d",
+    "1"
+);
+
+test!(
+    dominators_monos_shared_name_regex,
+    "dominators",
+    "./fixtures/monos.wasm",
+    "--regex",
+    "^alloc::slice::merge_sort::h[0-9a-f]+$",
+    "-d",
+    "1"
+);
+
+test_error!(
+    paths_monos_shared_name,
+    "paths",
+    "./fixtures/monos.wasm",
+    "alloc::slice::merge_sort"
+);
+
+test!(
+    top_wee_alloc_raw_names,
+    "top",
+    "./fixtures/wee_alloc.wasm",
+    "-n",
+    "10",
+    "--names",
+    "raw"
+);
+
+test!(
+    dominators_wee_alloc_short_names,
+    "dominators",
+    "./fixtures/wee_alloc.wasm",
+    "--names",
+    "short"
+);
+
+test!(
+    diff_wee_alloc_no_hash_names_json,
+    "diff",
+    "./fixtures/wee_alloc.wasm",
+    "./fixtures/wee_alloc.2.wasm",
+    "--names",
+    "no-hash",
+    "-f",
+    "json"
+);
+
+test!(monos_v0, "monos", "./fixtures/v0_monos.wasm");
+
+test!(
+    monos_v0_json,
+    "monos",
+    "./fixtures/v0_monos.wasm",
+    "-m",
+    "2",
+    "-f",
+    "json"
+);
+
+test!(
+    monos_cpp_templates,
+    "monos",
+    "./fixtures/cpp_templates.wasm"
+);
+
+test!(
+    monos_cpp_templates_by_argument,
+    "monos",
+    "./fixtures/cpp_templates.wasm",
+    "--group-by",
+    "argument",
+    "-n",
+    "2"
+);
+
+test!(
+    monos_cpp_templates_by_both_json,
+    "monos",
+    "./fixtures/cpp_templates.wasm",
+    "--group-by",
+    "both",
+    "-f",
+    "json"
+);
+
+test!(
+    monos_demangled_by_argument,
+    "monos",
+    "./fixtures/demangled_monos.wasm",
+    "--group-by",
+    "argument"
+);
+
+test!(
+    monos_demangled_by_both_json,
+    "monos",
+    "./fixtures/demangled_monos.wasm",
+    "--group-by",
+    "both",
+    "-f",
+    "json"
+);
+
+test_error!(
+    monos_legacy_rust_by_argument,
+    "monos",
+    "./fixtures/monos.wasm",
+    "--group-by",
+    "argument"
+);
+
+test!(
+    garbage_linked_modules,
+    "garbage",
+    "./fixtures/linked_main.wasm",
+    "--module",
+    "./fixtures/linked_lazy.wasm"
+);
+
+test!(
+    garbage_linked_unrelated_module,
+    "garbage",
+    "./fixtures/linked_main.wasm",
+    "--module",
+    "./fixtures/linked_logger.wasm"
+);
+
+test!(
+    dominators_linked_modules,
+    "dominators",
+    "./fixtures/linked_main.wasm",
+    "--module",
+    "./fixtures/linked_lazy.wasm"
+);
+
+test!(
+    paths_linked_modules_edge_kinds,
+    "paths",
+    "./fixtures/linked_main.wasm",
+    "--module",
+    "./fixtures/linked_lazy.wasm",
+    "layout",
+    "--edge-kinds"
+);
+
+test!(
+    garbage_ignore_root,
+    "garbage",
+    "./fixtures/paths_test.wasm",
+    "--ignore-root",
+    "export \"woof\""
+);
+
+test!(
+    dominators_root,
+    "dominators",
+    "./fixtures/paths_test.wasm",
+    "--root",
+    "^export \"(woof|bark)\"$"
+);
+
+test!(
+    top_retained_ignore_root,
+    "top",
+    "./fixtures/wee_alloc.wasm",
+    "--retained",
+    "-n",
+    "10",
+    "--ignore-root",
+    "export \"hello\""
+);
+
+test!(
+    whatif_exports,
+    "whatif",
+    "./fixtures/paths_test.wasm",
+    "export \"woof\"",
+    "export \"bark\""
+);
+
+test!(
+    whatif_cut_survivor,
+    "whatif",
+    "./fixtures/paths_test.wasm",
+    "--cut",
+    "awoo",
+    "func[2]"
+);
+
+test!(
+    whatif_wee_alloc_regex_json,
+    "whatif",
+    "./fixtures/wee_alloc.wasm",
+    "--regex",
+    "wee_alloc",
+    "-n",
+    "3",
+    "-f",
+    "json"
+);
+
+test!(cycles, "cycles", "./fixtures/cycles.wasm");
+
+test!(
+    cycles_json,
+    "cycles",
+    "./fixtures/cycles.wasm",
+    "-n",
+    "1",
+    "-f",
+    "json"
+);
+
+test!(
+    cycles_csv_max_members,
+    "cycles",
+    "./fixtures/cycles.wasm",
+    "-m",
+    "2",
+    "-f",
+    "csv"
+);
+
+test!(
+    paths_test_desc_json,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "export \"bark\"",
+    "--descending",
+    "-f",
+    "json"
+);
+
+test!(
+    paths_test_desc_csv,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "export \"bark\"",
+    "--descending",
+    "-f",
+    "csv"
+);
+
+test!(
+    neighbors,
+    "neighbors",
+    "./fixtures/paths_test.wasm",
+    "calledTwice",
+    "func[1]",
+    "bark"
+);
+
+test!(
+    neighbors_wee_alloc_regex_max,
+    "neighbors",
+    "./fixtures/wee_alloc.wasm",
+    "--regex",
+    "alloc_with_refill",
+    "-n",
+    "1"
+);
+
+test!(
+    neighbors_json,
+    "neighbors",
+    "./fixtures/paths_test.wasm",
+    "func[1]",
+    "-f",
+    "json"
+);
+
+test!(
+    neighbors_csv,
+    "neighbors",
+    "./fixtures/paths_test.wasm",
+    "func[1]",
+    "-f",
+    "csv"
+);
+
+test!(
+    paths_test_shortest,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "calledTwice",
+    "--shortest",
+    "2"
+);
+
+test!(
+    paths_test_shortest_by_size_json,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "calledTwice",
+    "--shortest",
+    "3",
+    "--weight",
+    "size",
+    "--edge-kinds",
+    "-f",
+    "json"
+);
+
+test!(
+    paths_test_shortest_csv,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "calledTwice",
+    "calledOnce",
+    "--shortest",
+    "3",
+    "-f",
+    "csv"
+);
+
+test!(dups, "dups", "./fixtures/dups.wasm");
+
+test!(dups_near, "dups", "./fixtures/dups.wasm", "--near");
+
+test!(
+    dups_near_json_max_members,
+    "dups",
+    "./fixtures/dups.wasm",
+    "--near",
+    "-m",
+    "1",
+    "-f",
+    "json"
+);
+
+test!(dups_csv, "dups", "./fixtures/dups.wasm", "-f", "csv");
+
+test_error!(
+    dups_unknown_opcode,
+    "dups",
+    "./fixtures/unknown_opcode.wasm"
+);
+
+test!(
+    elf_dups_hello_world_rs,
+    "dups",
+    "./fixtures/hello_elf",
+    "-n",
+    "3",
+    "-m",
+    "3"
+);
+
+test!(strings, "strings", "./fixtures/strings.wasm");
+
+test!(
+    strings_json,
+    "strings",
+    "./fixtures/strings.wasm",
+    "-f",
+    "json"
+);
+
+test!(
+    strings_csv_max_strings,
+    "strings",
+    "./fixtures/strings.wasm",
+    "-n",
+    "3",
+    "-f",
+    "csv"
+);
+
+test!(
+    elf_strings_hello_world_rs,
+    "strings",
+    "./fixtures/hello_elf",
+    "-n",
+    "5"
+);
+
+test!(rust_bloat, "rust-bloat", "./fixtures/monos.wasm", "-n", "3");
+
+test!(
+    rust_bloat_json,
+    "rust-bloat",
+    "./fixtures/monos.wasm",
+    "-n",
+    "2",
+    "-f",
+    "json"
+);
+
+test!(
+    rust_bloat_wee_alloc_csv,
+    "rust-bloat",
+    "./fixtures/wee_alloc.wasm",
+    "-f",
+    "csv"
+);
+
+test!(stats, "stats", "./fixtures/wee_alloc.wasm");
+
+test!(
+    stats_json,
+    "stats",
+    "./fixtures/monos.wasm",
+    "-n",
+    "5",
+    "-f",
+    "json"
+);
+
+test!(
+    stats_csv_tiny,
+    "stats",
+    "./fixtures/dups.wasm",
+    "--tiny",
+    "11",
+    "-f",
+    "csv"
+);
+
+test!(
+    namespaces,
+    "namespaces",
+    "./fixtures/wee_alloc.wasm",
+    "-d",
+    "2"
+);
+
+test!(
+    namespaces_max_rows,
+    "namespaces",
+    "./fixtures/monos.wasm",
+    "-d",
+    "3",
+    "-r",
+    "20"
+);
+
+test!(
+    namespaces_v0_json,
+    "namespaces",
+    "./fixtures/v0_monos.wasm",
+    "-d",
+    "3",
+    "-f",
+    "json"
+);
+
+test!(
+    namespaces_v0_csv,
+    "namespaces",
+    "./fixtures/v0_monos.wasm",
+    "-f",
+    "csv"
+);
+
+test!(
+    namespaces_svg,
+    "namespaces",
+    "./fixtures/wee_alloc.wasm",
+    "-f",
+    "svg"
+);
+
+test!(
+    dominators_wee_alloc_folded,
+    "dominators",
+    "./fixtures/wee_alloc.wasm",
+    "-f",
+    "folded"
+);
+
+test!(
+    dominators_wee_alloc_folded_with_depth,
+    "dominators",
+    "./fixtures/wee_alloc.wasm",
+    "-d",
+    "3",
+    "-f",
+    "folded"
+);
+
+test!(
+    paths_test_called_twice_folded,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "calledTwice",
+    "-f",
+    "folded"
+);
+
+test!(
+    paths_test_default_output_desc_folded,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "--descending",
+    "-f",
+    "folded"
+);
+
+test!(
+    paths_test_shortest_folded,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "calledTwice",
+    "--shortest",
+    "2",
+    "-f",
+    "folded"
+);
@@ -6,6 +6,7 @@
 extern crate failure;
 extern crate structopt;
 extern crate twiggy_analyze as analyze;
+extern crate twiggy_ir as ir;
 extern crate twiggy_opt as opt;
 extern crate twiggy_parser as parser;
 extern crate twiggy_traits as traits;
@@ -27,21 +28,49 @@ fn main() {
 }
 
 fn run(opts: opt::Options) -> Result<(), traits::Error> {
-    let mut items = parser::read_and_parse(opts.input())?;
+    let name_mode: ir::NameMode = opts.names().parse().map_err(traits::Error::with_msg)?;
+
+    let mut inputs = vec![opts.input()];
+    inputs.extend(opts.modules().iter().map(|path| path.as_path()));
+    let mut items = parser::read_and_parse_modules(&inputs, opts.source_map())?;
+    report_warnings(&items);
+    items.set_name_mode(name_mode);
 
     let data = match opts {
         opt::Options::Top(ref top) => analyze::top(&mut items, top)?,
         opt::Options::Dominators(ref doms) => analyze::dominators(&mut items, doms)?,
         opt::Options::Paths(ref paths) => analyze::paths(&mut items, paths)?,
+        opt::Options::Neighbors(ref neighbors) => analyze::neighbors(&mut items, neighbors)?,
         opt::Options::Monos(ref monos) => analyze::monos(&mut items, monos)?,
+        opt::Options::Dups(ref dups) => analyze::dups(&items, dups)?,
+        opt::Options::Strings(ref strings) => analyze::strings(&items, strings)?,
+        opt::Options::RustBloat(ref bloat) => analyze::rust_bloat(&items, bloat)?,
+        opt::Options::Stats(ref stats) => analyze::stats(&items, stats)?,
+        opt::Options::Namespaces(ref namespaces) => analyze::namespaces(&mut items, namespaces)?,
         opt::Options::Garbage(ref garbo) => analyze::garbage(&mut items, garbo)?,
+        opt::Options::Whatif(ref whatif) => analyze::whatif(&mut items, whatif)?,
+        opt::Options::Cycles(ref cycles) => analyze::cycles(&mut items, cycles)?,
+        opt::Options::Info(ref info) => analyze::info(&items, info)?,
         opt::Options::Diff(ref diff) => {
-            let mut new_items = parser::read_and_parse(diff.new_input())?;
+            let mut new_items = parser::read_and_parse(diff.new_input(), diff.new_source_map())?;
+            report_warnings(&new_items);
+            new_items.set_name_mode(name_mode);
             analyze::diff(&mut items, &mut new_items, diff)?
         }
+        opt::Options::Snapshot(_) => {
+            let mut dest = opts.output_destination().open()?;
+            items.write_snapshot(&mut *dest)?;
+            return Ok(());
+        }
     };
 
     let mut dest = opts.output_destination().open()?;
 
     data.emit(&items, &mut *dest, opts.output_format())
 }
+
+fn report_warnings(items: &ir::Items) {
+    for warning in items.warnings() {
+        eprintln!("warning: {}", warning);
+    }
+}
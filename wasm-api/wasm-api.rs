@@ -58,4 +58,17 @@ impl Items {
         diff.emit_json(&self.items, &mut buf).unwrap();
         String::from_utf8(buf).unwrap()
     }
+
+    pub fn info(&self, options: &opt::Info) -> String {
+        let info = analyze::info(&self.items, options).unwrap();
+        let mut buf = Vec::new();
+        info.emit_json(&self.items, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    pub fn snapshot(&self) -> String {
+        let mut buf = Vec::new();
+        self.items.write_snapshot(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
 }